@@ -0,0 +1,160 @@
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// adb processes allowed to run at the same time.
+static MAX_RUNNING: AtomicUsize = AtomicUsize::new(6);
+/// Calls allowed to wait for a free slot; more are refused right away.
+const MAX_QUEUED: usize = 32;
+
+/// adb executable; empty runs `adb` from `PATH`.
+static PROGRAM: Mutex<String> = Mutex::new(String::new());
+
+static SHORT_SECS: AtomicU64 = AtomicU64::new(10);
+static LONG_SECS: AtomicU64 = AtomicU64::new(120);
+
+/// How long an adb call may take, set with `configure`.
+#[derive(Debug, Clone, Copy)]
+pub enum Timeout {
+    Short,
+    Long,
+}
+
+impl Timeout {
+    fn duration(self) -> Duration {
+        let secs = match self {
+            Timeout::Short => &SHORT_SECS,
+            Timeout::Long => &LONG_SECS,
+        };
+        Duration::from_secs(secs.load(Ordering::Relaxed))
+    }
+}
+
+/// Timeout for quick queries like `getprop` or `adb devices`.
+pub const SHORT: Timeout = Timeout::Short;
+/// Timeout for installs, pulls and other transfers.
+pub const LONG: Timeout = Timeout::Long;
+
+/// Applies the limits from `advanced.toml`.
+pub fn configure(short_secs: u64, long_secs: u64, max_running: usize) {
+    SHORT_SECS.store(short_secs.max(1), Ordering::Relaxed);
+    LONG_SECS.store(long_secs.max(1), Ordering::Relaxed);
+    MAX_RUNNING.store(max_running.max(1), Ordering::Relaxed);
+}
+
+/// Runs adb from `path` instead of `PATH`, e.g. a managed platform-tools
+/// download or a fake in tests.
+pub fn set_program(path: &str) {
+    *PROGRAM.lock().unwrap() = path.to_string();
+}
+
+/// The adb executable for `Command::new`.
+pub fn program() -> String {
+    let program = PROGRAM.lock().unwrap();
+    if program.is_empty() { "adb".to_string() } else { program.clone() }
+}
+
+struct Slots {
+    running: usize,
+    queued: usize,
+}
+
+static SLOTS: Mutex<Slots> = Mutex::new(Slots { running: 0, queued: 0 });
+static FREED: Condvar = Condvar::new();
+
+/// A running slot, released when dropped.
+struct Slot;
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        SLOTS.lock().unwrap().running -= 1;
+        FREED.notify_one();
+    }
+}
+
+fn acquire(deadline: Instant, cancel: Option<&AtomicBool>) -> Result<Slot, String> {
+    let max_running = MAX_RUNNING.load(Ordering::Relaxed);
+    let mut slots = SLOTS.lock().unwrap();
+    if slots.running >= max_running && slots.queued >= MAX_QUEUED {
+        return Err("adb queue is full".to_string());
+    }
+    slots.queued += 1;
+    let result = loop {
+        if slots.running < max_running {
+            slots.running += 1;
+            break Ok(Slot);
+        }
+        if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            break Err("cancelled".to_string());
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            break Err("timed out waiting for other adb calls".to_string());
+        }
+        // Wake up regularly to notice cancellation
+        slots = FREED.wait_timeout(slots, (deadline - now).min(Duration::from_millis(100))).unwrap().0;
+    };
+    slots.queued -= 1;
+    result
+}
+
+fn drain(stream: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut data = Vec::new();
+        if let Some(mut stream) = stream {
+            let _ = stream.read_to_end(&mut data);
+        }
+        data
+    })
+}
+
+/// Runs `adb <args>` through the shared queue. The process is killed when
+/// `timeout` (including the wait for a slot) passes or `cancel` is raised,
+/// so one hung device cannot hold up calls for the others.
+pub fn run(args: &[&str], timeout: Timeout, cancel: Option<&AtomicBool>) -> Result<Output, String> {
+    let timeout = timeout.duration();
+    let deadline = Instant::now() + timeout;
+    let _slot = acquire(deadline, cancel)?;
+    let mut child = Command::new(program())
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("adb failed: {}", e))?;
+    // Read both pipes while waiting so large output can't block adb
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| format!("adb failed: {}", e))? {
+            break status;
+        }
+        let stop = if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            Some("cancelled".to_string())
+        } else if Instant::now() >= deadline {
+            Some(format!("adb {} timed out after {}s", args.join(" "), timeout.as_secs()))
+        } else {
+            None
+        };
+        if let Some(reason) = stop {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(reason);
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    Ok(Output { status, stdout: stdout.join().unwrap_or_default(), stderr: stderr.join().unwrap_or_default() })
+}
+
+/// `adb -s <serial> shell <command>` with the short timeout; stdout on success,
+/// stderr as the error otherwise.
+pub fn shell(serial: &str, command: &str) -> Result<String, String> {
+    let output = run(&["-s", serial, "shell", command], SHORT, None)?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,62 @@
+use crate::persist;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Optional expert settings the UI doesn't expose, read once at startup.
+pub const ADVANCED_PATH: &str = "advanced.toml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Advanced {
+    /// Seconds between `adb devices` listings and session health checks.
+    pub device_poll_secs: f32,
+    /// adb timeout for quick queries, in seconds.
+    pub adb_short_timeout_secs: u64,
+    /// adb timeout for installs and transfers, in seconds.
+    pub adb_long_timeout_secs: u64,
+    /// adb processes allowed to run at the same time.
+    pub adb_max_parallel: usize,
+    /// UI scale on top of the monitor's.
+    pub ui_zoom: f32,
+    /// Milliseconds between repaints when nothing happens; 0 repaints every frame.
+    pub repaint_interval_ms: u64,
+}
+
+impl Default for Advanced {
+    fn default() -> Self {
+        Self {
+            device_poll_secs: 1.0,
+            adb_short_timeout_secs: 10,
+            adb_long_timeout_secs: 120,
+            adb_max_parallel: 6,
+            ui_zoom: 1.0,
+            repaint_interval_ms: 0,
+        }
+    }
+}
+
+impl Advanced {
+    /// The file's settings, defaults when it doesn't exist. A file that
+    /// doesn't parse is reported and ignored as a whole.
+    pub fn load() -> (Self, Option<String>) {
+        match std::fs::read_to_string(persist::data(ADVANCED_PATH)) {
+            Ok(text) => match toml::from_str(&text) {
+                Ok(advanced) => (advanced, None),
+                Err(e) => {
+                    eprintln!("Failed to load {}: {}", ADVANCED_PATH, e);
+                    (Self::default(), Some(e.to_string()))
+                }
+            },
+            Err(_) => (Self::default(), None),
+        }
+    }
+
+    pub fn device_poll(&self) -> Duration {
+        Duration::from_secs_f32(self.device_poll_secs.clamp(0.2, 60.0))
+    }
+
+    /// Effective values as TOML, for the read-only view.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+}
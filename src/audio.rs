@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// scrcpy `--audio-codec` for forwarded audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioCodec {
+    Opus,
+    Aac,
+    Raw,
+}
+
+impl AudioCodec {
+    pub const ALL: [AudioCodec; 3] = [AudioCodec::Opus, AudioCodec::Aac, AudioCodec::Raw];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AudioCodec::Opus => "opus",
+            AudioCodec::Aac => "aac",
+            AudioCodec::Raw => "raw",
+        }
+    }
+}
+
+/// scrcpy `--audio-source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioSource {
+    Output,
+    Mic,
+}
+
+impl AudioSource {
+    pub const ALL: [AudioSource; 2] = [AudioSource::Output, AudioSource::Mic];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AudioSource::Output => "output",
+            AudioSource::Mic => "mic",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioSource::Output => "Device output",
+            AudioSource::Mic => "Microphone",
+        }
+    }
+}
+
+/// Launch flags for the audio settings, leaving out scrcpy's defaults and
+/// anything `args` already sets.
+pub fn audio_args(forward: bool, codec: AudioCodec, source: AudioSource, args: &[String]) -> Vec<String> {
+    let has = |name: &str| args.iter().any(|a| a.starts_with(name));
+    if !forward {
+        return if has("--no-audio") { Vec::new() } else { vec!["--no-audio".to_string()] };
+    }
+    let mut extra = Vec::new();
+    if codec != AudioCodec::Opus && !has("--audio-codec") {
+        extra.push(format!("--audio-codec={}", codec.name()));
+    }
+    if source != AudioSource::Output && !has("--audio-source") {
+        extra.push(format!("--audio-source={}", source.name()));
+    }
+    extra
+}
@@ -0,0 +1,40 @@
+use crate::adb;
+
+/// Below this level the device selector flags the battery.
+pub const LOW_LEVEL: u8 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Battery {
+    /// Percent, `level` scaled by `scale`.
+    pub level: u8,
+    pub charging: bool,
+}
+
+impl Battery {
+    /// Short tag for the device selector, e.g. `🔋85%` or `⚡40%`.
+    pub fn label(&self) -> String {
+        let icon = if self.charging { "⚡" } else { "🔋" };
+        let low = if self.level < LOW_LEVEL && !self.charging { " ⚠" } else { "" };
+        format!("{}{}%{}", icon, self.level, low)
+    }
+}
+
+/// Parses `dumpsys battery`. Status 2 is charging and 5 full; a full battery
+/// still on power counts as charging.
+pub fn parse(output: &str) -> Option<Battery> {
+    let field = |name: &str| {
+        output.lines().find_map(|l| l.trim().strip_prefix(name).and_then(|v| v.strip_prefix(':')).and_then(|v| v.trim().parse::<u32>().ok()))
+    };
+    let level = field("level")?;
+    let scale = field("scale").filter(|&s| s > 0).unwrap_or(100);
+    let charging = matches!(field("status"), Some(2 | 5));
+    Some(Battery { level: (level * 100 / scale).min(100) as u8, charging })
+}
+
+/// Battery state of each device; devices that don't answer are left out.
+pub fn query(serials: &[String]) -> Vec<(String, Battery)> {
+    serials
+        .iter()
+        .filter_map(|serial| adb::shell(serial, "dumpsys battery").ok().and_then(|out| parse(&out)).map(|b| (serial.clone(), b)))
+        .collect()
+}
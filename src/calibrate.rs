@@ -0,0 +1,212 @@
+use crate::session::{self, Session};
+use crate::settings::QualityOverrides;
+use crate::{ScrcpyGuiApp, cmdline};
+use eframe::egui;
+use std::time::Duration;
+
+/// `(max size, video bit rate in Mbps)` tried, best quality first.
+const CANDIDATES: [(u32, f32); 4] = [(1920, 16.0), (1600, 8.0), (1280, 4.0), (1024, 2.0)];
+/// How long each test session runs; the first FPS report is ignored as
+/// warm-up.
+const TRIAL_TIME: Duration = Duration::from_secs(10);
+/// A trial keeping up this share of the best frame rate counts as smooth.
+const SMOOTH_SHARE: f32 = 0.9;
+
+/// What one test session achieved.
+#[derive(Debug, Clone)]
+pub struct Trial {
+    pub max_size: u32,
+    pub bit_rate_mbps: f32,
+    /// Mean of scrcpy's FPS reports, `None` if it printed none.
+    pub fps: Option<f32>,
+    /// Frames decoded but never shown because the next one was already there.
+    pub skipped: u32,
+    pub error: Option<String>,
+}
+
+/// A calibration run for one device over its current link.
+pub struct Calibration {
+    pub serial: String,
+    pub wireless: bool,
+    pub trials: Vec<Trial>,
+    session: Option<Session>,
+    pub cancelled: bool,
+}
+
+impl Calibration {
+    pub fn is_done(&self) -> bool {
+        self.cancelled || self.trials.len() == CANDIDATES.len()
+    }
+
+    /// The best-quality trial that kept up with the smoothest one, with few
+    /// skipped frames.
+    pub fn suggestion(&self) -> Option<&Trial> {
+        let best = self.trials.iter().filter_map(|t| t.fps).fold(0.0, f32::max);
+        if best <= 0.0 {
+            return None;
+        }
+        let smooth = |t: &&Trial| {
+            t.fps.is_some_and(|fps| fps >= best * SMOOTH_SHARE && t.skipped as f32 <= fps * TRIAL_TIME.as_secs_f32() * 0.05)
+        };
+        self.trials.iter().find(smooth).or_else(|| self.trials.iter().find(|t| t.fps == Some(best)))
+    }
+}
+
+/// `(mean fps, skipped frames)` from `--print-fps` output such as
+/// `INFO: 58 fps (+3 frames skipped)`, leaving out the first report.
+fn parse_fps(lines: &[String]) -> (Option<f32>, u32) {
+    let mut reports = Vec::new();
+    for line in lines {
+        let Some(rest) = line.strip_prefix("INFO: ") else { continue };
+        let Some((fps, tail)) = rest.split_once(" fps") else { continue };
+        let Ok(fps) = fps.trim().parse::<f32>() else { continue };
+        let tail_skipped = tail
+            .trim()
+            .strip_prefix("(+")
+            .and_then(|t| t.split_whitespace().next())
+            .and_then(|n| n.parse::<u32>().ok())
+            .unwrap_or(0);
+        reports.push((fps, tail_skipped));
+    }
+    let measured = if reports.len() > 1 { &reports[1..] } else { &reports[..] };
+    let skipped = measured.iter().map(|(_, skipped)| skipped).sum();
+    let fps = (!measured.is_empty()).then(|| measured.iter().map(|(fps, _)| fps).sum::<f32>() / measured.len() as f32);
+    (fps, skipped)
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn start_calibration(&mut self, serial: &str) {
+        if self.sessions.is_running(serial) {
+            self.notices.device(serial, "⚠️ Stop the session before calibrating.");
+            return;
+        }
+        self.calibration = Some(Calibration {
+            serial: serial.to_string(),
+            wireless: session::is_wireless_serial(serial),
+            trials: Vec::new(),
+            session: None,
+            cancelled: false,
+        });
+    }
+
+    /// Launches the next test session, and records the running one once it
+    /// has run for `TRIAL_TIME` or exited.
+    pub(crate) fn run_calibration(&mut self) {
+        let Some(calibration) = &self.calibration else { return };
+        if calibration.is_done() {
+            return;
+        }
+        let serial = calibration.serial.clone();
+        let (max_size, bit_rate_mbps) = CANDIDATES[calibration.trials.len()];
+        let Some(session) = &calibration.session else {
+            let quality = QualityOverrides { max_size: Some(max_size), video_bit_rate_mbps: Some(bit_rate_mbps), max_fps: None };
+            let config = cmdline::merge_args(self.crop_args.as_deref().unwrap_or_default(), &quality.args());
+            let mut args = vec!["--serial".to_string(), serial.clone()];
+            args.extend(config.split_whitespace().filter(|a| !a.starts_with("--record")).map(str::to_string));
+            args.extend(["--print-fps", "--no-audio", "--no-control", "--window-title=Calibrating…"].map(str::to_string));
+            let binary = self.scrcpy_binary_for(&serial);
+            let calibration = self.calibration.as_mut().unwrap();
+            match Session::spawn(&binary, &serial, args) {
+                Ok(session) => calibration.session = Some(session),
+                Err(e) => {
+                    let error = Some(format!("Failed to start scrcpy: {}", e));
+                    calibration.trials.push(Trial { max_size, bit_rate_mbps, fps: None, skipped: 0, error });
+                }
+            }
+            return;
+        };
+        if session.is_running() && session.duration() < TRIAL_TIME {
+            return;
+        }
+        let calibration = self.calibration.as_mut().unwrap();
+        let mut session = calibration.session.take().unwrap();
+        let exited_early = !session.is_running();
+        session.stop();
+        let (fps, skipped) = parse_fps(&session.log_lines());
+        let error = if exited_early { Some(session.last_error().unwrap_or_else(|| "scrcpy exited".to_string())) } else { None };
+        calibration.trials.push(Trial { max_size, bit_rate_mbps, fps, skipped, error });
+    }
+
+    pub(crate) fn cancel_calibration(&mut self) {
+        if let Some(calibration) = &mut self.calibration {
+            if let Some(mut session) = calibration.session.take() {
+                session.stop();
+            }
+            calibration.cancelled = true;
+        }
+    }
+
+    /// Progress, per-trial results and the suggested quality, which can be
+    /// saved as the device's quality overrides.
+    pub(crate) fn show_calibration_window(&mut self, ctx: &egui::Context) {
+        let Some(calibration) = &self.calibration else { return };
+        let mut open = true;
+        let mut apply = None;
+        let mut cancel = false;
+        let name = self.device_display_name(&calibration.serial);
+        egui::Window::new(format!("🎯 Calibrate {}", name)).open(&mut open).resizable(false).show(ctx, |ui| {
+            ui.label(format!("Link: {}", if calibration.wireless { "Wi-Fi" } else { "USB" }));
+            if !calibration.is_done() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Test {} of {}…", calibration.trials.len() + 1, CANDIDATES.len()));
+                    cancel = ui.button("Cancel").clicked();
+                });
+                ui.weak("Keep something moving on the device (e.g. play a video): scrcpy only sends frames when the screen changes.");
+            }
+            egui::Grid::new("calibration_trials").striped(true).show(ui, |ui| {
+                for header in ["Max size", "Bit rate", "FPS", "Skipped"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for trial in &calibration.trials {
+                    ui.label(format!("{} px", trial.max_size));
+                    ui.label(format!("{} Mbps", trial.bit_rate_mbps));
+                    match (&trial.error, trial.fps) {
+                        (Some(error), _) => {
+                            ui.colored_label(egui::Color32::RED, "failed").on_hover_text(error);
+                        }
+                        (None, Some(fps)) => {
+                            ui.label(format!("{:.0}", fps));
+                        }
+                        (None, None) => {
+                            ui.weak("no frames");
+                        }
+                    }
+                    ui.label(trial.skipped.to_string());
+                    ui.end_row();
+                }
+            });
+            if calibration.is_done() {
+                match calibration.suggestion() {
+                    Some(trial) => {
+                        ui.label(format!("Suggested: {} px at {} Mbps", trial.max_size, trial.bit_rate_mbps));
+                        if ui.button("💾 Use for this device").on_hover_text("Saved as the device's quality options").clicked() {
+                            apply = Some((trial.max_size, trial.bit_rate_mbps));
+                        }
+                    }
+                    None if calibration.cancelled => {
+                        ui.weak("Cancelled.");
+                    }
+                    None => {
+                        ui.colored_label(egui::Color32::RED, "⚠️ No frame rate measured; keep the screen changing and try again.");
+                    }
+                }
+            }
+        });
+        if cancel || !open {
+            self.cancel_calibration();
+        }
+        if let Some((max_size, bit_rate_mbps)) = apply {
+            let serial = self.calibration.as_ref().unwrap().serial.clone();
+            let quality = self.settings.quality.entry(serial.clone()).or_default();
+            quality.max_size = Some(max_size);
+            quality.video_bit_rate_mbps = Some(bit_rate_mbps);
+            let _ = self.settings.save();
+            self.notices.device(&serial, format!("✅ Quality set to {} px at {} Mbps.", max_size, bit_rate_mbps));
+        }
+        if !open {
+            self.calibration = None;
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/// Version of this build, compared against `Settings::last_seen_version`.
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub struct Release {
+    pub version: &'static str,
+    pub date: &'static str,
+    pub changes: &'static [&'static str],
+}
+
+/// Newest release first.
+pub const CHANGELOG: &[Release] = &[Release {
+    version: "0.1.0",
+    date: "2025-07-01",
+    changes: &[
+        "Device selection with model detection and per-model scrcpy config",
+        "Config download from a remote URL, optionally on start",
+        "Per-device scrcpy binary override",
+        "Device properties fetched in the background for all devices",
+        "Session watchdog with reconnect after a device disconnects",
+        "Session log capture, export and retention",
+        "What's new panel and version history",
+    ],
+}];
+
+pub fn parse_version(v: &str) -> Vec<u32> {
+    v.split('.').map(|p| p.trim().parse().unwrap_or(0)).collect()
+}
+
+/// Releases newer than `last_seen`; all of them when nothing was seen yet.
+pub fn releases_since(last_seen: Option<&str>) -> Vec<&'static Release> {
+    let last_seen = last_seen.map(parse_version);
+    CHANGELOG
+        .iter()
+        .filter(|r| last_seen.as_ref().is_none_or(|seen| parse_version(r.version) > *seen))
+        .collect()
+}
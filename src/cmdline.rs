@@ -0,0 +1,111 @@
+/// Splits a command line into arguments, honouring single and double quotes
+/// and backslash escapes inside double quotes. `Err` on an unterminated quote.
+pub fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) => current.push(c),
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Splits an argument string into options, each with its value when given as
+/// `--opt=value` or as the following word.
+fn options(args: &str) -> Vec<(String, Vec<String>)> {
+    let mut options: Vec<(String, Vec<String>)> = Vec::new();
+    let mut words = args.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        let name = word.split('=').next().unwrap_or(word);
+        let name = match name {
+            "-m" => "--max-size",
+            "-b" => "--video-bit-rate",
+            name => name,
+        }
+        .to_string();
+        let mut parts = vec![word.to_string()];
+        if !word.contains('=')
+            && let Some(value) = words.next_if(|next| !next.starts_with('-'))
+        {
+            parts.push(value.to_string());
+        }
+        options.push((name, parts));
+    }
+    options
+}
+
+/// `base` with every option that `overrides` also sets replaced by the
+/// override, e.g. a group member's `--crop` instead of the profile's.
+pub fn merge_args(base: &str, overrides: &str) -> String {
+    let overrides = options(overrides);
+    let mut merged: Vec<_> = options(base).into_iter().filter(|(name, _)| !overrides.iter().any(|(o, _)| o == name)).collect();
+    merged.extend(overrides);
+    merged
+        .into_iter()
+        .flat_map(|(_, parts)| parts)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Value of an option in an argument string, e.g. `1080` for `--max-size`
+/// in `-m 1080`.
+pub fn option_value(args: &str, name: &str) -> Option<String> {
+    let (_, parts) = options(args).into_iter().rev().find(|(n, _)| n == name)?;
+    match parts.as_slice() {
+        [word] => word.split_once('=').map(|(_, v)| v.to_string()),
+        [_, value] => Some(value.clone()),
+        _ => None,
+    }
+}
+
+/// `arg` quoted for the platform shell when it holds anything beyond plain
+/// option characters, e.g. `--window-title='Pixel 7'`.
+pub fn quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_=.,/:@%+".contains(c)) {
+        return arg.to_string();
+    }
+    if cfg!(windows) {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
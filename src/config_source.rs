@@ -0,0 +1,272 @@
+use crate::{packages, persist};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+/// Where the device config is fetched from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceKind {
+    #[default]
+    Http,
+    Git,
+    LocalDir,
+    S3,
+}
+
+impl SourceKind {
+    pub const ALL: [SourceKind; 4] = [SourceKind::Http, SourceKind::Git, SourceKind::LocalDir, SourceKind::S3];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SourceKind::Http => "HTTP(S)",
+            SourceKind::Git => "Git repository",
+            SourceKind::LocalDir => "Local directory",
+            SourceKind::S3 => "S3-compatible storage",
+        }
+    }
+
+    /// Label of `Settings::config_url`, which holds the location of every kind.
+    pub fn location_label(self) -> &'static str {
+        match self {
+            SourceKind::Http => "Config URL:",
+            SourceKind::Git => "Repository:",
+            SourceKind::LocalDir => "Directory:",
+            SourceKind::S3 => "Endpoint:",
+        }
+    }
+}
+
+/// Options of the non-HTTP sources; the location itself stays in
+/// `Settings::config_url`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceSettings {
+    pub kind: SourceKind,
+    /// Config file in the repository or directory, or the S3 object key.
+    pub file: String,
+    /// Git branch; empty uses the remote's default.
+    pub branch: String,
+    pub bucket: String,
+    pub region: String,
+    /// S3 access key id; the secret key is kept in the keychain. Empty reads
+    /// a public bucket without signing.
+    pub access_key: String,
+}
+
+impl Default for SourceSettings {
+    fn default() -> Self {
+        Self {
+            kind: SourceKind::Http,
+            file: "scrcpy_device_config.json".to_string(),
+            branch: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            access_key: String::new(),
+        }
+    }
+}
+
+/// A place device configs are read from.
+pub trait ConfigSource: Send {
+    /// The whole config as JSON text.
+    fn fetch(&self) -> Result<String, String>;
+
+    /// The entry of one model as JSON, `None` when the source has none. By
+    /// default it is picked out of the whole config.
+    fn fetch_entry(&self, model: &str) -> Result<Option<String>, String> {
+        let mut config: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&self.fetch()?).map_err(|e| format!("invalid config: {}", e))?;
+        Ok(config.remove(model).map(|entry| entry.to_string()))
+    }
+}
+
+pub struct HttpSource {
+    url: String,
+    token: Option<String>,
+}
+
+impl HttpSource {
+    fn get(&self, query: &[(&str, &str)]) -> Result<reqwest::blocking::Response, String> {
+        let mut request = reqwest::blocking::Client::new().get(&self.url).query(query);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        request.send().map_err(|e| e.to_string())
+    }
+}
+
+impl ConfigSource for HttpSource {
+    fn fetch(&self) -> Result<String, String> {
+        self.get(&[])?.error_for_status().and_then(|r| r.text()).map_err(|e| e.to_string())
+    }
+
+    /// `GET <url>?model=<model>`; a 404 means the server has no entry.
+    fn fetch_entry(&self, model: &str) -> Result<Option<String>, String> {
+        let response = self.get(&[("model", model)])?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response.error_for_status().and_then(|r| r.text()).map(Some).map_err(|e| e.to_string())
+    }
+}
+
+/// A shallow clone under the cache, pulled on every fetch. Credentials come
+/// from git's own helpers.
+pub struct GitSource {
+    repo: String,
+    branch: String,
+    file: String,
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git").args(args).output().map_err(|e| format!("failed to run git: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+impl GitSource {
+    fn checkout() -> PathBuf {
+        persist::data(packages::CACHE_DIR).join("config_repo")
+    }
+
+    /// Clones again when the repository or branch changed since the last fetch.
+    fn update(&self) -> Result<PathBuf, String> {
+        let dir = Self::checkout();
+        let path = dir.to_string_lossy().to_string();
+        if dir.exists() {
+            let remote = git(&["-C", &path, "remote", "get-url", "origin"]).unwrap_or_default();
+            let branch = git(&["-C", &path, "rev-parse", "--abbrev-ref", "HEAD"]).unwrap_or_default();
+            if remote == self.repo && (self.branch.is_empty() || branch == self.branch) {
+                git(&["-C", &path, "pull", "--ff-only", "--depth", "1"])?;
+                return Ok(dir);
+            }
+            fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+        }
+        let mut args = vec!["clone", "--depth", "1"];
+        if !self.branch.is_empty() {
+            args.extend(["--branch", self.branch.as_str()]);
+        }
+        // A repository starting with "-" must not be read as an option
+        args.extend(["--", self.repo.as_str(), path.as_str()]);
+        git(&args)?;
+        Ok(dir)
+    }
+}
+
+impl ConfigSource for GitSource {
+    fn fetch(&self) -> Result<String, String> {
+        let path = self.update()?.join(&self.file);
+        fs::read_to_string(&path).map_err(|e| format!("{}: {}", self.file, e))
+    }
+}
+
+/// A file in a local or network directory, re-read whenever it changes.
+pub struct DirSource {
+    path: PathBuf,
+}
+
+impl ConfigSource for DirSource {
+    fn fetch(&self) -> Result<String, String> {
+        fs::read_to_string(&self.path).map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+/// A single object fetched with a path-style `GET <endpoint>/<bucket>/<key>`,
+/// signed with AWS Signature Version 4 when an access key is set. Works with
+/// AWS, MinIO, Cloudflare R2 and the like.
+pub struct S3Source {
+    endpoint: String,
+    bucket: String,
+    key: String,
+    region: String,
+    access_key: String,
+    secret_key: Option<String>,
+}
+
+/// SHA-256 of an empty body, the payload hash of every GET.
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.chain_update(data).finalize().into_bytes().into()
+}
+
+/// Percent-encodes an object key for the canonical URI, keeping `/`.
+fn encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+impl ConfigSource for S3Source {
+    fn fetch(&self) -> Result<String, String> {
+        let uri = format!("/{}/{}", self.bucket, encode_key(self.key.trim_start_matches('/')));
+        let url = reqwest::Url::parse(&format!("{}{}", self.endpoint.trim_end_matches('/'), uri)).map_err(|e| format!("invalid endpoint: {}", e))?;
+        let mut request = reqwest::blocking::Client::new().get(url.clone());
+        if !self.access_key.is_empty() {
+            let secret = self.secret_key.as_deref().ok_or("no secret key stored for the access key")?;
+            let host = match url.port() {
+                Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+                None => url.host_str().unwrap_or_default().to_string(),
+            };
+            let now = chrono::Utc::now();
+            let (amz_date, date) = (now.format("%Y%m%dT%H%M%SZ").to_string(), now.format("%Y%m%d").to_string());
+            let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+            let canonical = format!(
+                "GET\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+                uri, host, EMPTY_SHA256, amz_date, signed_headers, EMPTY_SHA256
+            );
+            let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+            let to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, hex(&Sha256::digest(canonical)));
+            let key = [self.region.as_str(), "s3", "aws4_request"]
+                .iter()
+                .fold(hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes()), |key, part| hmac_sha256(&key, part.as_bytes()));
+            let signature = hex(&hmac_sha256(&key, to_sign.as_bytes()));
+            request = request
+                .header("x-amz-date", amz_date)
+                .header("x-amz-content-sha256", EMPTY_SHA256)
+                .header(
+                    "Authorization",
+                    format!("AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}", self.access_key, scope, signed_headers, signature),
+                );
+        }
+        request.send().and_then(|r| r.error_for_status()).and_then(|r| r.text()).map_err(|e| e.to_string())
+    }
+}
+
+/// The source configured in settings. `secret` is the keychain secret:
+/// the bearer token for HTTP, the secret access key for S3.
+pub fn open(settings: &SourceSettings, location: &str, secret: Option<String>) -> Box<dyn ConfigSource> {
+    match settings.kind {
+        SourceKind::Http => Box::new(HttpSource { url: location.to_string(), token: secret }),
+        SourceKind::Git => Box::new(GitSource { repo: location.to_string(), branch: settings.branch.clone(), file: settings.file.clone() }),
+        SourceKind::LocalDir => Box::new(DirSource { path: Path::new(location).join(&settings.file) }),
+        SourceKind::S3 => Box::new(S3Source {
+            endpoint: location.to_string(),
+            bucket: settings.bucket.clone(),
+            key: settings.file.clone(),
+            region: settings.region.clone(),
+            access_key: settings.access_key.clone(),
+            secret_key: secret,
+        }),
+    }
+}
+
+/// Modification time of the watched config file of a local directory
+/// source; `None` for other sources or when it can't be read.
+pub fn watched_mtime(settings: &SourceSettings, location: &str) -> Option<SystemTime> {
+    (settings.kind == SourceKind::LocalDir).then(|| fs::metadata(Path::new(location).join(&settings.file)).and_then(|m| m.modified()).ok())?
+}
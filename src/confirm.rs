@@ -0,0 +1,93 @@
+use crate::power::PowerAction;
+use crate::recordings::RecordingEntry;
+use crate::{PaletteAction, ScrcpyGuiApp};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Destructive actions whose confirmation prompt can be turned off in settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Confirm {
+    StopSession,
+    StopAllSessions,
+    RebootDevice,
+    OverwriteRecording,
+}
+
+impl Confirm {
+    pub const ALL: [Confirm; 4] = [
+        Confirm::StopSession,
+        Confirm::StopAllSessions,
+        Confirm::RebootDevice,
+        Confirm::OverwriteRecording,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Confirm::StopSession => "Stopping a session",
+            Confirm::StopAllSessions => "Stopping all sessions or a group recording",
+            Confirm::RebootDevice => "Rebooting a device",
+            Confirm::OverwriteRecording => "Overwriting an existing recording",
+        }
+    }
+}
+
+/// What runs once the user confirms.
+pub enum ConfirmedAction {
+    Run(PaletteAction),
+    EndSession(String),
+    EmbedChapters(RecordingEntry),
+    Power(String, PowerAction),
+}
+
+pub struct PendingConfirm {
+    pub message: String,
+    pub action: ConfirmedAction,
+}
+
+impl ScrcpyGuiApp {
+    /// Runs `action` straight away, or asks first when the policy for `kind`
+    /// requires confirmation.
+    pub(crate) fn confirm_then(&mut self, kind: Confirm, message: impl Into<String>, action: ConfirmedAction) {
+        if self.settings.needs_confirmation(kind) {
+            self.pending_confirm = Some(PendingConfirm { message: message.into(), action });
+        } else {
+            self.run_confirmed(action);
+        }
+    }
+
+    fn run_confirmed(&mut self, action: ConfirmedAction) {
+        match action {
+            ConfirmedAction::Run(action) => self.run_palette_action(action),
+            ConfirmedAction::EndSession(serial) => self.end_session(&serial),
+            ConfirmedAction::EmbedChapters(entry) => self.embed_chapters(entry),
+            ConfirmedAction::Power(serial, action) => self.run_power_action(&serial, action),
+        }
+    }
+
+    pub(crate) fn show_confirm_window(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_confirm else { return };
+        let mut answer = None;
+        egui::Window::new("Please confirm")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(&pending.message);
+                ui.horizontal(|ui| {
+                    if ui.button("Yes").clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        answer = Some(false);
+                    }
+                });
+                ui.small("Confirmations can be turned off in Settings → General.");
+            });
+        if let Some(yes) = answer
+            && let Some(pending) = self.pending_confirm.take()
+            && yes
+        {
+            self.run_confirmed(pending.action);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::adb;
+use crate::job::Job;
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// Console output entries kept in memory.
+const MAX_ENTRIES: usize = 100;
+/// Free-form commands remembered across runs.
+const MAX_HISTORY: usize = 50;
+
+/// Read-only `adb shell` commands offered without the free-form toggle.
+pub const TEMPLATES: &[(&str, &str)] = &[
+    ("All properties", "getprop"),
+    ("Android version", "getprop ro.build.version.release"),
+    ("Battery", "dumpsys battery"),
+    ("Displays", "dumpsys display displays"),
+    ("Memory summary", "dumpsys meminfo -s"),
+    ("Focused window", "dumpsys window windows | grep -E mCurrentFocus"),
+    ("Screen size", "wm size"),
+    ("Screen density", "wm density"),
+    ("Installed packages", "pm list packages"),
+    ("Third-party packages", "pm list packages -3"),
+];
+
+#[derive(Debug, Clone)]
+pub struct ConsoleEntry {
+    pub at: DateTime<Local>,
+    pub serial: String,
+    pub command: String,
+    pub output: String,
+    pub ok: bool,
+}
+
+/// Runs `adb -s <serial> shell <command>` and captures stdout and stderr.
+/// The command goes over as one argument, so the device shell sees the
+/// quoting as typed and reports unbalanced quotes itself.
+fn run(serial: &str, command: &str) -> ConsoleEntry {
+    let (output, ok) = match adb::run(&["-s", serial, "shell", command], adb::LONG, None) {
+        Ok(out) => {
+            let text = String::from_utf8_lossy(&out.stdout).to_string() + &String::from_utf8_lossy(&out.stderr);
+            (text.trim_end().to_string(), out.status.success())
+        }
+        Err(e) => (e, false),
+    };
+    ConsoleEntry { at: Local::now(), serial: serial.to_string(), command: command.to_string(), output, ok }
+}
+
+/// Per-device "Run command" box: vetted templates, or any shell command once
+/// `free_form` is enabled.
+#[derive(Default)]
+pub struct AdbConsole {
+    pub template: usize,
+    pub free_form: bool,
+    pub input: String,
+    pub entries: VecDeque<ConsoleEntry>,
+    /// Position in the history while browsing it with the arrow keys.
+    history_pos: Option<usize>,
+    job: Option<Job<ConsoleEntry>>,
+}
+
+/// Moves `command` to the end of `history`, dropping the oldest entries
+/// beyond `MAX_HISTORY`.
+pub fn remember(history: &mut Vec<String>, command: &str) {
+    history.retain(|c| c != command);
+    history.push(command.to_string());
+    if history.len() > MAX_HISTORY {
+        history.drain(..history.len() - MAX_HISTORY);
+    }
+}
+
+impl AdbConsole {
+    pub fn is_busy(&self) -> bool {
+        self.job.is_some()
+    }
+
+    /// The command that "Run" would execute.
+    pub fn command(&self) -> String {
+        if self.free_form {
+            self.input.trim().to_string()
+        } else {
+            TEMPLATES.get(self.template).map(|(_, c)| c.to_string()).unwrap_or_default()
+        }
+    }
+
+    pub fn run(&mut self, serial: &str) {
+        let command = self.command();
+        if command.is_empty() || self.is_busy() {
+            return;
+        }
+        self.history_pos = None;
+        let serial = serial.to_string();
+        self.job = Some(Job::spawn(move || run(&serial, &command)));
+    }
+
+    /// Puts the previous (`older`) or next history entry into the input, like
+    /// the arrow keys in a terminal.
+    pub fn recall(&mut self, history: &[String], older: bool) {
+        if history.is_empty() {
+            return;
+        }
+        let pos = match (self.history_pos, older) {
+            (None, true) => Some(history.len() - 1),
+            (None, false) => None,
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) => (pos + 1 < history.len()).then_some(pos + 1),
+        };
+        self.history_pos = pos;
+        self.input = pos.map(|pos| history[pos].clone()).unwrap_or_default();
+    }
+
+    pub fn clear(&mut self, serial: &str) {
+        self.entries.retain(|e| e.serial != serial);
+    }
+
+    pub fn poll(&mut self) {
+        if let Some(entry) = self.job.as_ref().and_then(Job::poll) {
+            self.job = None;
+            if self.entries.len() >= MAX_ENTRIES {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(entry);
+        }
+    }
+}
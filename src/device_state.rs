@@ -0,0 +1,135 @@
+use crate::adb::shell as adb_shell;
+use crate::persist;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const DEVICE_STATE_PATH: &str = "device_state.json";
+
+/// Device settings the app can change and knows how to put back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Tweak {
+    StayAwake,
+    ShowTouches,
+    Density,
+    Resolution,
+}
+
+impl Tweak {
+    pub fn label(self) -> &'static str {
+        match self {
+            Tweak::StayAwake => "Stay awake while plugged in",
+            Tweak::ShowTouches => "Show touches",
+            Tweak::Density => "Density",
+            Tweak::Resolution => "Resolution",
+        }
+    }
+
+    /// Shell command reading the current value.
+    fn get_command(self) -> &'static str {
+        match self {
+            Tweak::StayAwake => "settings get global stay_on_while_plugged_in",
+            Tweak::ShowTouches => "settings get system show_touches",
+            Tweak::Density => "wm density",
+            Tweak::Resolution => "wm size",
+        }
+    }
+
+    /// Shell command setting `value`; `reset` clears a `wm` override.
+    fn set_command(self, value: &str) -> String {
+        match self {
+            Tweak::StayAwake => format!("settings put global stay_on_while_plugged_in {}", value),
+            Tweak::ShowTouches => format!("settings put system show_touches {}", value),
+            Tweak::Density => format!("wm density {}", value),
+            Tweak::Resolution => format!("wm size {}", value),
+        }
+    }
+}
+
+
+/// Current value in a form `set_command` accepts. `wm` prints the physical
+/// value plus an "Override" line when one is set; without an override the
+/// default is restored with `reset`.
+fn current_value(serial: &str, tweak: Tweak) -> Result<String, String> {
+    let out = adb_shell(serial, tweak.get_command())?;
+    Ok(match tweak {
+        Tweak::Density | Tweak::Resolution => out
+            .lines()
+            .find_map(|l| l.strip_prefix("Override ").and_then(|l| l.split_once(':')).map(|(_, v)| v.trim().to_string()))
+            .unwrap_or_else(|| "reset".to_string()),
+        Tweak::StayAwake | Tweak::ShowTouches if out == "null" => "0".to_string(),
+        _ => out,
+    })
+}
+
+/// Applies `value` and returns the value it replaced.
+pub fn change(serial: &str, tweak: Tweak, value: &str) -> Result<String, String> {
+    let original = current_value(serial, tweak)?;
+    adb_shell(serial, &tweak.set_command(value))?;
+    Ok(original)
+}
+
+/// Puts every recorded tweak back; returns the ones that could not be restored.
+pub fn restore(serial: &str, originals: &BTreeMap<Tweak, String>) -> Result<(), String> {
+    let failed: Vec<String> = originals
+        .iter()
+        .filter_map(|(tweak, value)| adb_shell(serial, &tweak.set_command(value)).err().map(|e| format!("{}: {}", tweak.label(), e)))
+        .collect();
+    if failed.is_empty() { Ok(()) } else { Err(failed.join("; ")) }
+}
+
+/// Original values of settings the app changed, per device, persisted in
+/// `device_state.json` so they can still be restored after a crash.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceLedger {
+    pub dirty: BTreeMap<String, BTreeMap<Tweak, String>>,
+}
+
+impl DeviceLedger {
+    pub fn load() -> Self {
+        persist::read_json(persist::data(DEVICE_STATE_PATH))
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to load {}: {}", DEVICE_STATE_PATH, e);
+                None
+            })
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(data) => {
+                if let Err(e) = persist::write_atomic(persist::data(DEVICE_STATE_PATH), data) {
+                    eprintln!("Failed to save {}: {}", DEVICE_STATE_PATH, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize device state: {}", e),
+        }
+    }
+
+    pub fn is_dirty(&self, serial: &str) -> bool {
+        self.dirty.get(serial).is_some_and(|t| !t.is_empty())
+    }
+
+    pub fn originals(&self, serial: &str) -> BTreeMap<Tweak, String> {
+        self.dirty.get(serial).cloned().unwrap_or_default()
+    }
+
+    /// Keeps the first original only, so repeated changes still restore the
+    /// value the device had before the app touched it.
+    pub fn record(&mut self, serial: &str, tweak: Tweak, original: String) {
+        self.dirty.entry(serial.to_string()).or_default().entry(tweak).or_insert(original);
+        self.save();
+    }
+
+    pub fn clear(&mut self, serial: &str) {
+        if self.dirty.remove(serial).is_some() {
+            self.save();
+        }
+    }
+}
+
+/// Result of a background change or restore for one device.
+pub enum Outcome {
+    Changed(Tweak, String),
+    Restored,
+}
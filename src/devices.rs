@@ -0,0 +1,169 @@
+use crate::adb;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// Properties read from `adb shell getprop` for one device.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceInfo {
+    pub model: String,
+    pub manufacturer: String,
+    pub android_version: String,
+    pub sdk: Option<u32>,
+    /// Display size in pixels from `wm size`, the override if one is set.
+    pub screen_size: Option<(u32, u32)>,
+    pub props: HashMap<String, String>,
+}
+
+impl DeviceInfo {
+    fn from_props(props: HashMap<String, String>) -> Self {
+        let get = |key: &str| props.get(key).cloned().unwrap_or_default();
+        Self {
+            model: get("ro.product.model"),
+            manufacturer: get("ro.product.manufacturer"),
+            android_version: get("ro.build.version.release"),
+            sdk: props.get("ro.build.version.sdk").and_then(|v| v.parse().ok()),
+            screen_size: None,
+            props,
+        }
+    }
+}
+
+/// Serials of the devices `adb devices` lists as ready, leaving out
+/// unauthorized and offline ones.
+pub fn parse_device_list(text: &str) -> Vec<String> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [serial, "device"] => Some(serial.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Devices ready for mirroring; empty when adb can't be run.
+pub fn list() -> Vec<String> {
+    match adb::run(&["devices"], adb::SHORT, None) {
+        Ok(output) => parse_device_list(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Parses `getprop` output lines of the form `[key]: [value]`.
+fn parse_getprop(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once("]: [")?;
+            let key = key.strip_prefix('[')?;
+            let value = value.trim_end().strip_suffix(']')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Parses `wm size` output; an "Override size" line wins over the physical one.
+fn parse_wm_size(text: &str) -> Option<(u32, u32)> {
+    let size = |line: &str| {
+        let (w, h) = line.split_once(':')?.1.trim().split_once('x')?;
+        Some((w.parse().ok()?, h.parse().ok()?))
+    };
+    text.lines()
+        .find(|l| l.starts_with("Override size"))
+        .or_else(|| text.lines().find(|l| l.starts_with("Physical size")))
+        .and_then(size)
+}
+
+fn fetch_screen_size(serial: &str) -> Option<(u32, u32)> {
+    let output = adb::run(&["-s", serial, "shell", "wm", "size"], adb::SHORT, None).ok()?;
+    parse_wm_size(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Runs `getprop` for a device. Returns `None` if `cancel` was raised before
+/// adb finished, in which case the adb process is killed.
+pub fn fetch_device_info(serial: &str, cancel: &AtomicBool) -> Option<DeviceInfo> {
+    let unknown = || DeviceInfo {
+        model: "Unknown".to_string(),
+        ..Default::default()
+    };
+    match adb::run(&["-s", serial, "shell", "getprop"], adb::SHORT, Some(cancel)) {
+        _ if cancel.load(Ordering::Relaxed) => None,
+        Ok(output) => {
+            let mut info = DeviceInfo::from_props(parse_getprop(&String::from_utf8_lossy(&output.stdout)));
+            info.screen_size = fetch_screen_size(serial);
+            Some(info)
+        }
+        Err(_) => Some(unknown()),
+    }
+}
+
+/// Fetches device properties for all connected devices on background threads
+/// and keeps the results, so switching the selected device needs no adb call.
+pub struct DevicePropsCache {
+    info: HashMap<String, DeviceInfo>,
+    pending: HashMap<String, Arc<AtomicBool>>,
+    tx: Sender<(String, DeviceInfo)>,
+    rx: Receiver<(String, DeviceInfo)>,
+}
+
+impl Default for DevicePropsCache {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            info: HashMap::new(),
+            pending: HashMap::new(),
+            tx,
+            rx,
+        }
+    }
+}
+
+impl DevicePropsCache {
+    pub fn get(&self, serial: &str) -> Option<&DeviceInfo> {
+        self.info.get(serial)
+    }
+
+    pub fn is_pending(&self, serial: &str) -> bool {
+        self.pending.contains_key(serial)
+    }
+
+    /// Aborts an in-flight fetch; the device is fetched again on the next request.
+    pub fn cancel(&mut self, serial: &str) {
+        if let Some(flag) = self.pending.remove(serial) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Starts a fetch for every serial that is neither cached nor in flight, and
+    /// forgets devices that are no longer connected.
+    pub fn request_all(&mut self, serials: &[String]) {
+        self.info.retain(|s, _| serials.contains(s));
+        for serial in serials {
+            if self.info.contains_key(serial) || self.pending.contains_key(serial) {
+                continue;
+            }
+            let cancel = Arc::new(AtomicBool::new(false));
+            self.pending.insert(serial.clone(), cancel.clone());
+            let tx = self.tx.clone();
+            let serial = serial.clone();
+            thread::spawn(move || {
+                if let Some(info) = fetch_device_info(&serial, &cancel) {
+                    let _ = tx.send((serial, info));
+                }
+            });
+        }
+    }
+
+    /// Drains finished fetches; returns the serials whose info just arrived.
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut updated = Vec::new();
+        while let Ok((serial, info)) = self.rx.try_recv() {
+            // A fetch cancelled after it already finished is still a valid result
+            self.pending.remove(&serial);
+            self.info.insert(serial.clone(), info);
+            updated.push(serial);
+        }
+        updated
+    }
+}
@@ -0,0 +1,100 @@
+use crate::rules::RULES_PATH;
+use crate::settings::Settings;
+use crate::{adb, changelog, logs, persist};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Session logs included in a bundle, newest first.
+const BUNDLED_LOGS: usize = 5;
+
+/// Strips query strings and user info from URLs, which may carry tokens.
+fn redact_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else { return url.to_string() };
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let rest = match rest.split_once('@') {
+        Some((_, host)) => format!("<redacted>@{}", host),
+        None => rest.to_string(),
+    };
+    format!("{}://{}", scheme, rest)
+}
+
+/// Settings as JSON with the PIN hash removed and URLs redacted.
+fn redacted_settings(settings: &Settings) -> String {
+    let mut settings = settings.clone();
+    if !settings.operator_pin_hash.is_empty() {
+        settings.operator_pin_hash = "<redacted>".to_string();
+    }
+    settings.config_url = redact_url(&settings.config_url);
+    if !settings.config_source.access_key.is_empty() {
+        settings.config_source.access_key = "<redacted>".to_string();
+    }
+    for mirrors in settings.tool_mirrors.values_mut() {
+        *mirrors = mirrors.lines().map(redact_url).collect::<Vec<_>>().join("\n");
+    }
+    serde_json::to_string_pretty(&settings).unwrap_or_default()
+}
+
+/// First output line of `program args`, or why it could not run.
+fn probe(program: &str, args: &[&str]) -> String {
+    match Command::new(program).args(args).stdout(Stdio::piped()).stderr(Stdio::piped()).output() {
+        Ok(out) => {
+            let text = String::from_utf8_lossy(&out.stdout).to_string() + &String::from_utf8_lossy(&out.stderr);
+            text.lines().next().unwrap_or("(no output)").trim().to_string()
+        }
+        Err(e) => format!("not available: {}", e),
+    }
+}
+
+fn environment_report(scrcpy_binary: &str) -> String {
+    let adb = adb::program();
+    let devices = probe_all(&adb, &["devices", "-l"]);
+    [
+        format!("scrcpy GUI: {}", changelog::APP_VERSION),
+        format!("OS: {} {}", std::env::consts::OS, std::env::consts::ARCH),
+        format!("scrcpy ({}): {}", scrcpy_binary, probe(scrcpy_binary, &["--version"])),
+        format!("adb ({}): {}", adb, probe(&adb, &["version"])),
+        format!("aapt: {}", probe("aapt", &["version"])),
+        format!("ffmpeg: {}", probe("ffmpeg", &["-version"])),
+        String::new(),
+        "adb devices -l:".to_string(),
+        devices,
+    ]
+    .join("\n")
+}
+
+fn probe_all(program: &str, args: &[&str]) -> String {
+    match Command::new(program).args(args).output() {
+        Ok(out) => String::from_utf8_lossy(&out.stdout).trim().to_string(),
+        Err(e) => format!("not available: {}", e),
+    }
+}
+
+/// Zips redacted settings, the device configs and rules, the most recent
+/// session logs and an environment report into `diagnostics_<timestamp>.zip`.
+pub fn export_bundle(settings: &Settings, scrcpy_binary: &str, config_paths: &[&str]) -> Result<PathBuf, String> {
+    let path = persist::data(format!("diagnostics_{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    let file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    let mut add = |name: &str, data: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(data).map_err(|e| e.to_string())
+    };
+    add("settings.json", redacted_settings(settings).as_bytes())?;
+    add("environment.txt", environment_report(scrcpy_binary).as_bytes())?;
+    for config in config_paths.iter().chain([&RULES_PATH]) {
+        if let Ok(data) = std::fs::read(persist::data(config)) {
+            add(config, &data)?;
+        }
+    }
+    let session_logs = logs::session_logs().map_err(|e| e.to_string())?;
+    for log in session_logs.iter().rev().take(BUNDLED_LOGS) {
+        let name = Path::new(logs::LOGS_DIR).join(log.file_name().unwrap_or_default());
+        if let Ok(data) = std::fs::read(log) {
+            add(&name.to_string_lossy().replace('\\', "/"), &data)?;
+        }
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(path)
+}
@@ -0,0 +1,377 @@
+use crate::adb;
+use crate::session::{self, SessionLog};
+use crate::tools::ManagedTool;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const DEVICE_SERVER_PATH: &str = "/data/local/tmp/scrcpy-server.jar";
+/// Longest side of the embedded stream, keeping decoding and upload cheap.
+const MAX_SIZE: u32 = 1280;
+const CODEC_H264: u32 = 0x6832_3634;
+/// scrcpy's pointer id for touches that are not tied to a real finger.
+const POINTER_ID_GENERIC_FINGER: u64 = u64::MAX - 1;
+
+const CONTROL_INJECT_KEYCODE: u8 = 0;
+const CONTROL_INJECT_TEXT: u8 = 1;
+const CONTROL_INJECT_TOUCH: u8 = 2;
+/// Longest text scrcpy accepts in a single inject-text message.
+const MAX_TEXT_BYTES: usize = 300;
+
+pub const KEYCODE_HOME: u32 = 3;
+pub const KEYCODE_BACK: u32 = 4;
+pub const KEYCODE_POWER: u32 = 26;
+pub const KEYCODE_ENTER: u32 = 66;
+pub const KEYCODE_DEL: u32 = 67;
+pub const KEYCODE_APP_SWITCH: u32 = 187;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchAction {
+    Down = 0,
+    Up = 1,
+    Move = 2,
+}
+
+/// Latest decoded picture; `seq` changes whenever a new frame arrived.
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+    pub seq: u64,
+}
+
+/// User input to forward to a device, kept as a value so compare mode can
+/// replay it on a second device.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    /// Position relative to the picture, `0.0..=1.0` on both axes.
+    Touch(TouchAction, f32, f32),
+    Key(u32),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmbeddedState {
+    Connecting(&'static str),
+    Streaming,
+    Failed(String),
+    Stopped,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<Option<EmbeddedState>>,
+    frame: Mutex<Option<Frame>>,
+    /// Video size as announced by the server, used for touch coordinates.
+    video_size: Mutex<Option<(u16, u16)>>,
+    device_name: Mutex<String>,
+    video: Mutex<Option<TcpStream>>,
+    control: Mutex<Option<TcpStream>>,
+    children: Mutex<Vec<Child>>,
+    stop: AtomicBool,
+}
+
+impl Shared {
+    fn set_state(&self, state: EmbeddedState) {
+        *self.state.lock().unwrap() = Some(state);
+    }
+}
+
+/// Mirroring rendered inside the GUI: talks to scrcpy-server directly over an
+/// adb tunnel, decodes the H.264 stream with ffmpeg and forwards input over
+/// scrcpy's control socket.
+pub struct EmbeddedSession {
+    pub serial: String,
+    /// Output of the device server and the decoder.
+    pub log: SessionLog,
+    port: u16,
+    shared: Arc<Shared>,
+}
+
+/// Looks for a binary on PATH the way a shell would.
+fn resolve_in_path(binary: &str) -> Option<PathBuf> {
+    let path = Path::new(binary);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let name = format!("{}{}", binary, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(&std::env::var_os("PATH")?).map(|dir| dir.join(&name)).find(|p| p.is_file())
+}
+
+/// The `scrcpy-server` file shipped with a scrcpy installation.
+pub fn find_server(binary: &str) -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("SCRCPY_SERVER_PATH").map(PathBuf::from).filter(|p| p.is_file()) {
+        return Some(path);
+    }
+    let mut candidates = Vec::new();
+    if let Some(exe) = resolve_in_path(binary) {
+        let exe = std::fs::canonicalize(&exe).unwrap_or(exe);
+        if let Some(dir) = exe.parent() {
+            candidates.push(dir.join("scrcpy-server"));
+            candidates.push(dir.join("../share/scrcpy/scrcpy-server"));
+        }
+    }
+    candidates.extend(ManagedTool::Scrcpy.installed_file("scrcpy-server"));
+    for prefix in ["/usr/local/share", "/usr/share", "/opt/homebrew/share"] {
+        candidates.push(Path::new(prefix).join("scrcpy/scrcpy-server"));
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+/// The server must be started with exactly the version of the client it ships with.
+fn server_version(binary: &str) -> Result<String, String> {
+    let output = Command::new(binary).arg("--version").output().map_err(|e| format!("{}: {}", binary, e))?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .ok_or_else(|| format!("could not read the version of {}", binary))
+}
+
+fn adb(serial: &str, args: &[&str]) -> Result<(), String> {
+    let args: Vec<&str> = ["-s", serial].into_iter().chain(args.iter().copied()).collect();
+    let output = crate::adb::run(&args, crate::adb::LONG, None)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn read_u32(stream: &mut TcpStream) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    stream.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+impl EmbeddedSession {
+    /// Starts connecting in the background; progress shows up in `state`.
+    pub fn start(binary: &str, serial: &str) -> Self {
+        let port = TcpListener::bind(("127.0.0.1", 0)).and_then(|l| l.local_addr()).map(|a| a.port()).unwrap_or(27183);
+        let session = Self {
+            serial: serial.to_string(),
+            log: SessionLog::default(),
+            port,
+            shared: Arc::new(Shared::default()),
+        };
+        session.shared.set_state(EmbeddedState::Connecting("Starting"));
+        let (binary, serial, log, shared) = (binary.to_string(), serial.to_string(), session.log.clone(), session.shared.clone());
+        thread::spawn(move || {
+            let result = run(&binary, &serial, port, &log, &shared);
+            if !shared.stop.load(Ordering::Relaxed) {
+                shared.set_state(match result {
+                    Ok(()) => EmbeddedState::Failed("the stream ended".to_string()),
+                    Err(e) => EmbeddedState::Failed(e),
+                });
+            }
+        });
+        session
+    }
+
+    pub fn state(&self) -> EmbeddedState {
+        self.shared.state.lock().unwrap().clone().unwrap_or(EmbeddedState::Stopped)
+    }
+
+    pub fn device_name(&self) -> String {
+        self.shared.device_name.lock().unwrap().clone()
+    }
+
+    /// Runs `f` on the latest frame if it is newer than `seen`.
+    pub fn with_new_frame<R>(&self, seen: Option<u64>, f: impl FnOnce(&Frame) -> R) -> Option<R> {
+        let frame = self.shared.frame.lock().unwrap();
+        frame.as_ref().filter(|fr| Some(fr.seq) != seen).map(f)
+    }
+
+    fn send_control(&self, msg: &[u8]) {
+        if let Some(stream) = self.shared.control.lock().unwrap().as_mut() {
+            let _ = stream.write_all(msg);
+        }
+    }
+
+    pub fn send(&self, input: &Input) {
+        match input {
+            Input::Touch(action, x, y) => self.touch(*action, *x, *y),
+            Input::Key(keycode) => self.key(*keycode),
+            Input::Text(text) => self.text(text),
+        }
+    }
+
+    /// Touch at a position relative to the picture, `0.0..=1.0` on both axes.
+    fn touch(&self, action: TouchAction, x: f32, y: f32) {
+        let Some((w, h)) = *self.shared.video_size.lock().unwrap() else { return };
+        let mut msg = vec![CONTROL_INJECT_TOUCH, action as u8];
+        msg.extend(POINTER_ID_GENERIC_FINGER.to_be_bytes());
+        msg.extend(((x.clamp(0.0, 1.0) * w as f32) as i32).to_be_bytes());
+        msg.extend(((y.clamp(0.0, 1.0) * h as f32) as i32).to_be_bytes());
+        msg.extend(w.to_be_bytes());
+        msg.extend(h.to_be_bytes());
+        let pressure: u16 = if action == TouchAction::Up { 0 } else { 0xffff };
+        msg.extend(pressure.to_be_bytes());
+        msg.extend(0u32.to_be_bytes()); // action button
+        msg.extend(0u32.to_be_bytes()); // buttons
+        self.send_control(&msg);
+    }
+
+    /// Presses and releases an Android key.
+    fn key(&self, keycode: u32) {
+        for action in [TouchAction::Down, TouchAction::Up] {
+            let mut msg = vec![CONTROL_INJECT_KEYCODE, action as u8];
+            msg.extend(keycode.to_be_bytes());
+            msg.extend(0u32.to_be_bytes()); // repeat
+            msg.extend(0u32.to_be_bytes()); // meta state
+            self.send_control(&msg);
+        }
+    }
+
+    fn text(&self, text: &str) {
+        let mut end = text.len().min(MAX_TEXT_BYTES);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let bytes = &text.as_bytes()[..end];
+        let mut msg = vec![CONTROL_INJECT_TEXT];
+        msg.extend((bytes.len() as u32).to_be_bytes());
+        msg.extend(bytes);
+        self.send_control(&msg);
+    }
+
+    pub fn stop(&mut self) {
+        if self.shared.stop.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        for stream in [&self.shared.video, &self.shared.control] {
+            if let Some(stream) = stream.lock().unwrap().take() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+        for mut child in self.shared.children.lock().unwrap().drain(..) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        let _ = adb(&self.serial, &["forward", "--remove", &format!("tcp:{}", self.port)]);
+        self.shared.set_state(EmbeddedState::Stopped);
+    }
+}
+
+impl Drop for EmbeddedSession {
+    /// The picture lives in this window, so the device side goes with it.
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Connects to the server with retries until it sends its dummy byte.
+fn connect_video(port: u16, shared: &Shared) -> Result<TcpStream, String> {
+    for _ in 0..100 {
+        if shared.stop.load(Ordering::Relaxed) {
+            return Err("stopped".to_string());
+        }
+        // adb accepts the connection even before the server listens, then closes it
+        if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+            let mut dummy = [0u8; 1];
+            if stream.read_exact(&mut dummy).is_ok() {
+                return Ok(stream);
+            }
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    Err("the scrcpy server did not respond".to_string())
+}
+
+/// Frames keep the size announced at connection time; a rotated device is
+/// scaled into it until the view is restarted.
+fn run(binary: &str, serial: &str, port: u16, log: &SessionLog, shared: &Shared) -> Result<(), String> {
+    let server = find_server(binary).ok_or("scrcpy-server not found; set SCRCPY_SERVER_PATH or install scrcpy")?;
+    let version = server_version(binary)?;
+    shared.set_state(EmbeddedState::Connecting("Pushing server"));
+    adb(serial, &["push", &server.to_string_lossy(), DEVICE_SERVER_PATH]).map_err(|e| format!("push failed: {}", e))?;
+    let scid = (std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().subsec_nanos() ^ std::process::id()) & 0x7fff_ffff;
+    let scid = format!("{:08x}", scid);
+    adb(serial, &["forward", &format!("tcp:{}", port), &format!("localabstract:scrcpy_{}", scid)]).map_err(|e| format!("forward failed: {}", e))?;
+
+    shared.set_state(EmbeddedState::Connecting("Starting server"));
+    let mut child = Command::new(adb::program())
+        .arg("-s").arg(serial)
+        .arg("shell")
+        .arg(format!("CLASSPATH={}", DEVICE_SERVER_PATH))
+        .args(["app_process", "/", "com.genymobile.scrcpy.Server", &version])
+        .arg(format!("scid={}", scid))
+        .args(["log_level=info", "tunnel_forward=true", "audio=false", "control=true", "cleanup=true", "video_codec=h264"])
+        // ffmpeg reads a raw H.264 stream, without the 12-byte pts/size header per packet
+        .arg("send_frame_meta=false")
+        .arg(format!("max_size={}", MAX_SIZE))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("adb failed: {}", e))?;
+    if let Some(out) = child.stdout.take() {
+        session::capture_output(out, log.clone(), Some("server".to_string()));
+    }
+    if let Some(err) = child.stderr.take() {
+        session::capture_output(err, log.clone(), Some("server".to_string()));
+    }
+    shared.children.lock().unwrap().push(child);
+
+    shared.set_state(EmbeddedState::Connecting("Connecting"));
+    let mut video = connect_video(port, shared)?;
+    let control = TcpStream::connect(("127.0.0.1", port)).map_err(|e| format!("control socket: {}", e))?;
+    let mut name = [0u8; 64];
+    video.read_exact(&mut name).map_err(|e| format!("device meta: {}", e))?;
+    *shared.device_name.lock().unwrap() = String::from_utf8_lossy(&name).trim_end_matches('\0').to_string();
+    let codec = read_u32(&mut video).map_err(|e| e.to_string())?;
+    if codec != CODEC_H264 {
+        return Err(format!("unexpected video codec {:#x}", codec));
+    }
+    let width = read_u32(&mut video).map_err(|e| e.to_string())? as usize;
+    let height = read_u32(&mut video).map_err(|e| e.to_string())? as usize;
+    *shared.video_size.lock().unwrap() = Some((width as u16, height as u16));
+
+    // Device messages (clipboard changes etc.) are not used but must be drained
+    let mut control_reader = control.try_clone().map_err(|e| e.to_string())?;
+    thread::spawn(move || {
+        let mut buf = [0u8; 1024];
+        while matches!(control_reader.read(&mut buf), Ok(n) if n > 0) {}
+    });
+    *shared.control.lock().unwrap() = Some(control);
+
+    shared.set_state(EmbeddedState::Connecting("Starting decoder"));
+    let mut decoder = Command::new("ffmpeg")
+        .args(["-loglevel", "error", "-fflags", "nobuffer", "-flags", "low_delay", "-probesize", "32"])
+        .args(["-f", "h264", "-i", "pipe:0", "-f", "rawvideo", "-pix_fmt", "rgba"])
+        .arg("-s").arg(format!("{}x{}", width, height))
+        .arg("pipe:1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("ffmpeg is needed for the embedded view: {}", e))?;
+    let mut stdin = decoder.stdin.take().ok_or("ffmpeg stdin")?;
+    let mut stdout = decoder.stdout.take().ok_or("ffmpeg stdout")?;
+    if let Some(err) = decoder.stderr.take() {
+        session::capture_output(err, log.clone(), Some("ffmpeg".to_string()));
+    }
+    shared.children.lock().unwrap().push(decoder);
+    let mut video_reader = video.try_clone().map_err(|e| e.to_string())?;
+    *shared.video.lock().unwrap() = Some(video);
+    thread::spawn(move || {
+        let _ = std::io::copy(&mut video_reader, &mut stdin);
+    });
+
+    shared.set_state(EmbeddedState::Streaming);
+    let mut seq = 0;
+    loop {
+        let mut rgba = vec![0u8; width * height * 4];
+        if stdout.read_exact(&mut rgba).is_err() {
+            return Ok(());
+        }
+        seq += 1;
+        *shared.frame.lock().unwrap() = Some(Frame { width, height, rgba, seq });
+    }
+}
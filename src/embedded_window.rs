@@ -0,0 +1,370 @@
+use crate::input_echo::InputEcho;
+use crate::job::Job;
+use crate::{ScrcpyGuiApp, recordings};
+use crate::embedded::{self, EmbeddedSession, EmbeddedState, Input, TouchAction};
+use crate::screen_mask::ScreenMask;
+use eframe::egui;
+use std::fs;
+use std::path::PathBuf;
+
+/// Initial width of the picture-in-picture viewport; it can be resized.
+const PIP_WIDTH: f32 = 240.0;
+
+/// An embedded session with the UI state needed to draw it.
+pub struct EmbeddedView {
+    pub session: EmbeddedSession,
+    /// Uploaded frame, whether it was masked, and the texture.
+    texture: Option<(u64, bool, egui::TextureHandle)>,
+    /// Last touch position while the pointer is held down on the picture.
+    pointer: Option<(f32, f32)>,
+    /// PNG of a captured frame being written.
+    capture: Option<Job<Result<PathBuf, String>>>,
+    /// Also shown in a small always-on-top viewport.
+    pub pip: bool,
+}
+
+impl EmbeddedView {
+    pub fn start(binary: &str, serial: &str) -> Self {
+        Self { session: EmbeddedSession::start(binary, serial), texture: None, pointer: None, capture: None, pip: false }
+    }
+
+    pub fn title(&self) -> String {
+        match self.session.device_name() {
+            name if name.is_empty() => self.session.serial.clone(),
+            name => format!("{} ({})", name, self.session.serial),
+        }
+    }
+
+    /// Uploads the latest frame if a new one was decoded, or the current one
+    /// again when masking was switched.
+    fn update_texture(&mut self, ctx: &egui::Context, mask: Option<&DeviceMask>) {
+        let seen = self.texture.as_ref().filter(|(_, masked, _)| *masked == mask.is_some()).map(|(seq, _, _)| *seq);
+        let Some((seq, image)) = self.session.with_new_frame(seen, |frame| {
+            let image = match mask {
+                Some(mask) => egui::ColorImage::from_rgba_unmultiplied([frame.width, frame.height], &mask.apply(frame)),
+                None => egui::ColorImage::from_rgba_unmultiplied([frame.width, frame.height], &frame.rgba),
+            };
+            (frame.seq, image)
+        }) else {
+            return;
+        };
+        match &mut self.texture {
+            Some((current, masked, texture)) => {
+                texture.set(image, egui::TextureOptions::LINEAR);
+                *current = seq;
+                *masked = mask.is_some();
+            }
+            None => {
+                let name = format!("embedded_{}", self.session.serial);
+                self.texture = Some((seq, mask.is_some(), ctx.load_texture(name, image, egui::TextureOptions::LINEAR)));
+            }
+        }
+    }
+
+    /// Saves the latest frame at its decoded size as a PNG in `dir`, masked
+    /// like the picture. Encoding runs in the background.
+    fn capture_frame(&mut self, dir: &str, mask: Option<&DeviceMask>) -> Result<(), String> {
+        let path = recordings::recording_path(dir, &self.session.serial, "png");
+        let (width, height, rgba) = self
+            .session
+            .with_new_frame(None, |frame| (frame.width as u32, frame.height as u32, mask.map_or_else(|| frame.rgba.clone(), |m| m.apply(frame))))
+            .ok_or("no frame decoded yet")?;
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir, e))?;
+        self.capture = Some(Job::spawn(move || {
+            let image = image::RgbaImage::from_raw(width, height, rgba).ok_or("frame size mismatch")?;
+            image.save_with_format(&path, image::ImageFormat::Png).map_err(|e| e.to_string())?;
+            Ok(path)
+        }));
+        Ok(())
+    }
+
+    /// The picture alone in a frameless always-on-top viewport, until it is
+    /// double-clicked or closed. Dragging moves the viewport.
+    fn show_pip(&mut self, ctx: &egui::Context) {
+        let Some((_, _, texture)) = &self.texture else { return };
+        let (texture, size) = (texture.clone(), texture.size_vec2());
+        let builder = egui::ViewportBuilder::default()
+            .with_title(format!("📌 {}", self.title()))
+            .with_inner_size([PIP_WIDTH, PIP_WIDTH * size.y / size.x])
+            .with_always_on_top()
+            .with_decorations(false)
+            .with_resizable(true);
+        let mut close = false;
+        ctx.show_viewport_immediate(egui::ViewportId::from_hash_of(("pip", &self.session.serial)), builder, |ctx, _| {
+            egui::CentralPanel::default().frame(egui::Frame::NONE.fill(egui::Color32::BLACK)).show(ctx, |ui| {
+                let available = ui.available_size();
+                let scale = (available.x / size.x).min(available.y / size.y);
+                let image = egui::Image::new(&texture).fit_to_exact_size(size * scale).sense(egui::Sense::click_and_drag());
+                let response = ui.centered_and_justified(|ui| ui.add(image)).inner;
+                if response.drag_started() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+                }
+                close = response.on_hover_text("Drag to move, double-click to close").double_clicked();
+            });
+            close |= ctx.input(|i| i.viewport().close_requested());
+        });
+        if close {
+            self.pip = false;
+        }
+    }
+
+    fn picture_size(&self) -> Option<egui::Vec2> {
+        self.texture.as_ref().map(|(_, _, t)| t.size_vec2())
+    }
+
+    /// Draws state, navigation buttons and the picture at `scale`, forwarding
+    /// input to the device, with `echo` touches drawn on top. Returns the
+    /// input so it can be mirrored elsewhere.
+    fn show(&mut self, ui: &mut egui::Ui, scale: f32, echo: Option<&InputEcho>) -> Vec<Input> {
+        let mut inputs = Vec::new();
+        let state = self.session.state();
+        match &state {
+            EmbeddedState::Connecting(stage) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(*stage);
+                });
+            }
+            EmbeddedState::Failed(e) => {
+                ui.colored_label(egui::Color32::RED, format!("Embedded mirroring failed: {}", e));
+            }
+            EmbeddedState::Stopped => {
+                ui.label("Stopped.");
+            }
+            EmbeddedState::Streaming => {}
+        }
+        ui.horizontal(|ui| {
+            for (label, hint, keycode) in [
+                ("◀", "Back", embedded::KEYCODE_BACK),
+                ("●", "Home", embedded::KEYCODE_HOME),
+                ("▣", "Recent apps", embedded::KEYCODE_APP_SWITCH),
+                ("⏻", "Power", embedded::KEYCODE_POWER),
+            ] {
+                if ui.add_enabled(state == EmbeddedState::Streaming, egui::Button::new(label)).on_hover_text(hint).clicked() {
+                    inputs.push(Input::Key(keycode));
+                }
+            }
+        });
+        if let Some((_, _, texture)) = &self.texture {
+            let response = ui.add(egui::Image::new(texture).fit_to_exact_size(texture.size_vec2() * scale).sense(egui::Sense::click_and_drag()));
+            let rect = response.rect;
+            if let Some(echo) = echo {
+                echo.paint_touches(ui.painter(), rect, &self.session.serial);
+            }
+            let relative = |pos: egui::Pos2| ((pos.x - rect.min.x) / rect.width(), (pos.y - rect.min.y) / rect.height());
+            if response.is_pointer_button_down_on()
+                && let Some(pos) = response.interact_pointer_pos()
+            {
+                let (x, y) = relative(pos);
+                let action = if self.pointer.is_some() { TouchAction::Move } else { TouchAction::Down };
+                if self.pointer != Some((x, y)) {
+                    inputs.push(Input::Touch(action, x, y));
+                }
+                self.pointer = Some((x, y));
+                response.request_focus();
+            } else if let Some((x, y)) = self.pointer.take() {
+                inputs.push(Input::Touch(TouchAction::Up, x, y));
+            }
+            if response.has_focus() {
+                for event in ui.input(|i| i.events.clone()) {
+                    match event {
+                        egui::Event::Text(text) => inputs.push(Input::Text(text)),
+                        egui::Event::Key { key: egui::Key::Backspace, pressed: true, .. } => inputs.push(Input::Key(embedded::KEYCODE_DEL)),
+                        egui::Event::Key { key: egui::Key::Enter, pressed: true, .. } => inputs.push(Input::Key(embedded::KEYCODE_ENTER)),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        for input in &inputs {
+            self.session.send(input);
+        }
+        inputs
+    }
+}
+
+/// The screen mask of a device with its native screen size to scale from.
+pub struct DeviceMask {
+    mask: ScreenMask,
+    native: Option<(u32, u32)>,
+}
+
+impl DeviceMask {
+    /// A masked copy of the RGBA pixels of `frame`.
+    pub fn apply(&self, frame: &embedded::Frame) -> Vec<u8> {
+        let mut rgba = frame.rgba.clone();
+        self.mask.apply(&mut rgba, frame.width, frame.height, self.native);
+        rgba
+    }
+}
+
+/// Two embedded streams side by side.
+pub struct CompareView {
+    pub views: [EmbeddedView; 2],
+    /// Replay input on the other device as well.
+    pub broadcast: bool,
+    pub zoom: f32,
+}
+
+impl ScrcpyGuiApp {
+    /// The mask for `serial` when masking is on and its device config has one.
+    pub(crate) fn device_mask(&self, serial: &str) -> Option<DeviceMask> {
+        let mask = self.device_config_for(serial)?.screen_mask.clone().filter(|_| self.settings.mask_embedded)?;
+        Some(DeviceMask { mask, native: self.device_props.get(serial).and_then(|i| i.screen_size) })
+    }
+
+    pub(crate) fn start_embedded(&mut self, serial: &str) {
+        self.embedded = Some(EmbeddedView::start(&self.scrcpy_binary_for(serial), serial));
+    }
+
+    pub(crate) fn start_compare(&mut self, left: &str, right: &str) {
+        let views = [
+            EmbeddedView::start(&self.scrcpy_binary_for(left), left),
+            EmbeddedView::start(&self.scrcpy_binary_for(right), right),
+        ];
+        self.compare = Some(CompareView { views, broadcast: false, zoom: 0.5 });
+    }
+
+    pub(crate) fn show_embedded_window(&mut self, ctx: &egui::Context) {
+        let Some(serial) = self.embedded.as_ref().map(|v| v.session.serial.clone()) else { return };
+        let mask = self.device_mask(&serial);
+        let has_mask = self.device_config_for(&serial).is_some_and(|c| c.screen_mask.is_some());
+        let locked = self.locked();
+        let Some(view) = &mut self.embedded else { return };
+        view.update_texture(ctx, mask.as_ref());
+        let echo = self.settings.echo_input.then_some(&mut self.input_echo);
+        let mask_setting = &mut self.settings.mask_embedded;
+        let (mut mask_changed, mut capture) = (false, false);
+        let capturing = view.capture.is_some();
+        if let Some(result) = view.capture.as_ref().and_then(Job::poll) {
+            view.capture = None;
+            match result {
+                Ok(path) => self.notices.device(&serial, format!("✅ Frame saved to {}", path.display())),
+                Err(e) => self.notices.device(&serial, format!("⚠️ Frame capture failed: {}", e)),
+            }
+        }
+        let mut open = true;
+        egui::Window::new(format!("📺 {}", view.title()))
+            .id(egui::Id::new("embedded_view"))
+            .open(&mut open)
+            .default_size([360.0, 720.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                // Fit the picture into the window while keeping the aspect ratio
+                let scale = view.picture_size().map_or(1.0, |size| {
+                    // Room for the navigation and capture rows
+                    let available = ui.available_size() - egui::vec2(0.0, 60.0);
+                    (available.x / size.x).min(available.y / size.y).max(0.05)
+                });
+                let inputs = view.show(ui, scale, echo.as_deref());
+                if let Some(echo) = echo {
+                    inputs.iter().for_each(|input| echo.push_embedded(&view.session.serial, input));
+                }
+                ui.horizontal(|ui| {
+                    capture = ui
+                        .add_enabled(view.picture_size().is_some() && !capturing, egui::Button::new("📷 Capture frame"))
+                        .on_hover_text("Save the displayed frame as PNG at full decoded resolution to the recordings folder")
+                        .clicked();
+                    ui.checkbox(&mut view.pip, "📌 Picture-in-picture")
+                        .on_hover_text("Keep a small always-on-top copy of this stream while working in other windows");
+                    mask_changed = ui
+                        .add_enabled(has_mask && !locked, egui::Checkbox::new(mask_setting, "Mask cutout and corners"))
+                        .on_hover_text("Round the corners and black out the camera cutout, for clean screenshots and demo videos")
+                        .on_disabled_hover_text("The device config has no screen_mask for this device type")
+                        .changed();
+                });
+            });
+        if let Some(view) = self.embedded.as_mut().filter(|v| v.pip) {
+            view.show_pip(ctx);
+        }
+        if capture
+            && let Some(view) = &mut self.embedded
+            && let Err(e) = view.capture_frame(&self.settings.recordings_folder(), mask.as_ref())
+        {
+            self.notices.device(&serial, format!("⚠️ Frame capture failed: {}", e));
+        }
+        if mask_changed {
+            let _ = self.settings.save();
+        }
+        if !open {
+            self.embedded = None;
+        }
+    }
+
+    pub(crate) fn show_compare_window(&mut self, ctx: &egui::Context) {
+        if !self.show_compare {
+            return;
+        }
+        let mut open = true;
+        let mut start = None;
+        let mut stop = false;
+        let masks: Vec<Option<DeviceMask>> = self.compare.iter().flat_map(|c| &c.views).map(|v| self.device_mask(&v.session.serial)).collect();
+        egui::Window::new("🆚 Compare devices")
+            .open(&mut open)
+            .default_size([760.0, 720.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(compare) = &mut self.compare else {
+                    if self.devices.len() < 2 {
+                        ui.label("Connect two devices to compare them side by side.");
+                        return;
+                    }
+                    for (side, pick) in ["Left", "Right"].iter().zip(self.compare_pick.iter_mut()) {
+                        *pick = (*pick).min(self.devices.len() - 1);
+                        egui::ComboBox::from_label(*side).selected_text(&self.devices[*pick]).show_ui(ui, |ui| {
+                            for (i, serial) in self.devices.iter().enumerate() {
+                                ui.selectable_value(pick, i, serial);
+                            }
+                        });
+                    }
+                    let [left, right] = self.compare_pick;
+                    if ui.add_enabled(left != right, egui::Button::new("▶ Start compare")).clicked() {
+                        start = Some((self.devices[left].clone(), self.devices[right].clone()));
+                    }
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut compare.broadcast, "Broadcast input to both devices")
+                        .on_hover_text("Taps, keys and text on one side are replayed at the same relative position on the other");
+                    ui.add(egui::Slider::new(&mut compare.zoom, 0.2..=1.5).text("Zoom"));
+                    stop = ui.button("⏹ Stop").clicked();
+                });
+                for (view, mask) in compare.views.iter_mut().zip(&masks) {
+                    view.update_texture(ctx, mask.as_ref());
+                }
+                // A single scroll area keeps both pictures scrolled together
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.horizontal_top(|ui| {
+                        let [left, right] = &mut compare.views;
+                        let echo = self.settings.echo_input.then_some(&mut self.input_echo);
+                        let mut from_left = Vec::new();
+                        let mut from_right = Vec::new();
+                        ui.vertical(|ui| {
+                            ui.label(egui::RichText::new(left.title()).strong());
+                            from_left = left.show(ui, compare.zoom, echo.as_deref());
+                        });
+                        ui.separator();
+                        ui.vertical(|ui| {
+                            ui.label(egui::RichText::new(right.title()).strong());
+                            from_right = right.show(ui, compare.zoom, echo.as_deref());
+                        });
+                        if let Some(echo) = echo {
+                            from_left.iter().for_each(|input| echo.push_embedded(&left.session.serial, input));
+                            from_right.iter().for_each(|input| echo.push_embedded(&right.session.serial, input));
+                        }
+                        if compare.broadcast {
+                            from_left.iter().for_each(|input| right.session.send(input));
+                            from_right.iter().for_each(|input| left.session.send(input));
+                        }
+                    });
+                });
+            });
+        if let Some((left, right)) = start {
+            self.start_compare(&left, &right);
+        }
+        if stop || !open {
+            self.compare = None;
+        }
+        self.show_compare = open;
+    }
+}
@@ -0,0 +1,139 @@
+use crate::job::Job;
+use crate::{ScrcpyGuiApp, cmdline};
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::process::{Command, Stdio};
+
+/// Option names listed by `<binary> --help`, e.g. `-m` and `--max-size`.
+/// `None` when the help can't be read.
+fn help_flags(binary: &str) -> Option<HashSet<String>> {
+    let output = Command::new(binary).arg("--help").stdout(Stdio::piped()).stderr(Stdio::null()).output().ok()?;
+    let flags = parse_help(&String::from_utf8_lossy(&output.stdout));
+    (!flags.is_empty()).then_some(flags)
+}
+
+/// Option lines of the help start with the flags, e.g.
+/// `    -m, --max-size=value`.
+fn parse_help(text: &str) -> HashSet<String> {
+    text.lines()
+        .map(str::trim_start)
+        .filter(|l| l.starts_with('-'))
+        .flat_map(|l| l.split(", "))
+        .filter_map(|flag| {
+            let name = flag.split(['=', ' ', '[']).next().unwrap_or_default();
+            name.starts_with('-').then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// The name of the option in `arg`, `None` for values and negative numbers.
+fn flag_name(arg: &str) -> Option<&str> {
+    if let Some(long) = arg.strip_prefix("--") {
+        return (!long.is_empty()).then(|| arg.split('=').next().unwrap_or(arg));
+    }
+    // Short options may carry their value, e.g. -m1024
+    let mut chars = arg.chars();
+    match (chars.next(), chars.next()) {
+        (Some('-'), Some(c)) if !c.is_ascii_digit() => Some(&arg[..1 + c.len_utf8()]),
+        _ => None,
+    }
+}
+
+/// Options in `args` that the installed scrcpy doesn't list.
+pub fn unknown_flags(args: &[String], known: &HashSet<String>) -> Vec<String> {
+    args.iter().filter_map(|a| flag_name(a)).filter(|f| !known.contains(*f)).map(str::to_string).collect()
+}
+
+/// Options accepted by each scrcpy binary, read from its help in the
+/// background.
+#[derive(Default)]
+pub struct KnownFlags {
+    by_binary: HashMap<String, Option<HashSet<String>>>,
+    jobs: Vec<Job<(String, Option<HashSet<String>>)>>,
+}
+
+impl KnownFlags {
+    pub fn request(&mut self, binary: &str) {
+        if self.by_binary.contains_key(binary) {
+            return;
+        }
+        self.by_binary.insert(binary.to_string(), None);
+        let binary = binary.to_string();
+        self.jobs.push(Job::spawn(move || {
+            let flags = help_flags(&binary);
+            (binary, flags)
+        }));
+    }
+
+    pub fn poll(&mut self) {
+        let mut read = Vec::new();
+        self.jobs.retain(|job| match job.poll() {
+            Some(result) => {
+                read.push(result);
+                false
+            }
+            None => true,
+        });
+        self.by_binary.extend(read);
+    }
+
+    /// `None` until the help was read, or when it couldn't be.
+    pub fn get(&self, binary: &str) -> Option<&HashSet<String>> {
+        self.by_binary.get(binary)?.as_ref()
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// The extra arguments of `serial`; an error for unbalanced quotes.
+    pub(crate) fn extra_args_for(&self, serial: &str) -> Result<Vec<String>, String> {
+        match self.settings.extra_args.get(serial) {
+            Some(extra) => cmdline::split(extra),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Extra options of `serial` unknown to its scrcpy binary; empty while
+    /// its help is still being read.
+    pub(crate) fn unknown_extra_flags(&self, serial: &str) -> Vec<String> {
+        let (Ok(args), Some(known)) = (self.extra_args_for(serial), self.known_flags.get(&self.scrcpy_binary_for(serial))) else {
+            return Vec::new();
+        };
+        unknown_flags(&args, known)
+    }
+
+    pub(crate) fn show_extra_args(&mut self, ui: &mut egui::Ui, serial: &str) {
+        self.known_flags.request(&self.scrcpy_binary_for(serial));
+        ui.horizontal(|ui| {
+            ui.label("Extra arguments:");
+            let mut extra = self.settings.extra_args.get(serial).cloned().unwrap_or_default();
+            let edit = egui::TextEdit::singleline(&mut extra)
+                .hint_text("appended after the device config, e.g. --max-fps=30")
+                .desired_width(320.0)
+                .font(egui::TextStyle::Monospace);
+            let response = ui.add(edit);
+            if response.changed() {
+                if extra.trim().is_empty() {
+                    self.settings.extra_args.remove(serial);
+                } else {
+                    self.settings.extra_args.insert(serial.to_string(), extra);
+                }
+            }
+            if response.lost_focus() {
+                let _ = self.settings.save();
+            }
+            match self.extra_args_for(serial) {
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, "❌").on_hover_text(format!("{}; scrcpy won't start until this is fixed", e));
+                }
+                Ok(_) => {
+                    let unknown = self.unknown_extra_flags(serial);
+                    if !unknown.is_empty() {
+                        let version = self.scrcpy_versions.get(&self.scrcpy_binary_for(serial)).cloned().unwrap_or_default();
+                        ui.colored_label(egui::Color32::ORANGE, "⚠")
+                            .on_hover_text(format!("Not an option of scrcpy {}: {}", version, unknown.join(", ")));
+                    }
+                }
+            }
+        });
+    }
+}
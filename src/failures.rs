@@ -0,0 +1,112 @@
+use crate::ScrcpyGuiApp;
+use eframe::egui;
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Failures further apart than this start a new count.
+const WINDOW: Duration = Duration::from_secs(10 * 60);
+/// From this many failures on the notice is shown as an error.
+const SEVERE: u32 = 5;
+
+/// Repeated failures of one device's automation, folded into one notice.
+pub struct FailureNotice {
+    /// What failed, e.g. "Auto-start rule" or "Surveillance restart".
+    pub source: &'static str,
+    pub count: u32,
+    pub first: Instant,
+    pub last: Instant,
+    pub error: String,
+    /// Count at which the notice was dismissed; it comes back once the count
+    /// has doubled.
+    dismissed_at: Option<u32>,
+}
+
+impl FailureNotice {
+    fn visible(&self) -> bool {
+        self.dismissed_at.is_none_or(|at| self.count >= at * 2)
+    }
+}
+
+/// Failures of unattended features (auto-start rules, surveillance restarts)
+/// per device, and the devices whose automation the user paused.
+#[derive(Default)]
+pub struct AutomationFailures {
+    notices: BTreeMap<String, FailureNotice>,
+    paused: HashSet<String>,
+}
+
+impl AutomationFailures {
+    pub fn record(&mut self, serial: &str, source: &'static str, error: impl Into<String>) {
+        let now = Instant::now();
+        let notice = self.notices.entry(serial.to_string()).or_insert_with(|| FailureNotice {
+            source,
+            count: 0,
+            first: now,
+            last: now,
+            error: String::new(),
+            dismissed_at: None,
+        });
+        if now - notice.last > WINDOW {
+            notice.count = 0;
+            notice.first = now;
+            notice.dismissed_at = None;
+        }
+        notice.source = source;
+        notice.count += 1;
+        notice.last = now;
+        notice.error = error.into();
+    }
+
+    /// Consecutive failures of a device within the window.
+    pub fn count(&self, serial: &str) -> u32 {
+        self.notices.get(serial).filter(|n| n.last.elapsed() <= WINDOW).map_or(0, |n| n.count)
+    }
+
+    pub fn is_paused(&self, serial: &str) -> bool {
+        self.paused.contains(serial)
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// One line per device with failing automation, with pause/resume.
+    pub(crate) fn show_automation_failures(&mut self, ui: &mut egui::Ui) {
+        let mut pause = None;
+        let mut resume = None;
+        let mut dismiss = None;
+        let failures = &self.automation_failures;
+        for (serial, notice) in failures.notices.iter().filter(|(s, n)| n.visible() || failures.paused.contains(*s)) {
+            let paused = failures.paused.contains(serial);
+            let color = if notice.count >= SEVERE { egui::Color32::RED } else { egui::Color32::ORANGE };
+            let minutes = (notice.last - notice.first).as_secs() / 60;
+            ui.horizontal(|ui| {
+                let times = if notice.count == 1 { "once".to_string() } else { format!("{} times in {} min", notice.count, minutes.max(1)) };
+                ui.colored_label(color, format!("⚠ {} for {} failed {}", notice.source, self.device_display_name(serial), times))
+                    .on_hover_text(&notice.error);
+                if paused {
+                    ui.label("(automation paused)");
+                    if ui.small_button("Resume").clicked() {
+                        resume = Some(serial.clone());
+                    }
+                } else if ui.small_button("⏸ Pause automation").on_hover_text("Stop auto-start rules and surveillance restarts for this device").clicked() {
+                    pause = Some(serial.clone());
+                }
+                if ui.small_button("Dismiss").clicked() {
+                    dismiss = Some(serial.clone());
+                }
+            });
+        }
+        let failures = &mut self.automation_failures;
+        if let Some(serial) = pause {
+            failures.paused.insert(serial);
+        }
+        if let Some(serial) = resume {
+            failures.paused.remove(&serial);
+            failures.notices.remove(&serial);
+        }
+        if let Some(serial) = dismiss
+            && let Some(notice) = failures.notices.get_mut(&serial)
+        {
+            notice.dismissed_at = Some(notice.count);
+        }
+    }
+}
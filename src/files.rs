@@ -0,0 +1,362 @@
+use crate::ScrcpyGuiApp;
+use crate::adb;
+use crate::job::Job;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Directory shown when the browser opens.
+const START_DIR: &str = "/sdcard/";
+/// How often the transferred size is measured.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One line of a device directory listing.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+/// `path` in single quotes for the device shell.
+fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', r"'\''"))
+}
+
+fn join(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+/// Parses toybox `ls -la` lines such as
+/// `-rw-rw---- 1 u0_a123 media_rw 1048576 2024-05-01 10:20 My video.mp4`.
+/// Symlinks count as directories, as most of them point to one (e.g. `/sdcard`).
+fn parse_listing(output: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = output
+        .lines()
+        .filter_map(|line| {
+            let mut rest = line;
+            let mut fields = Vec::new();
+            // The name is whatever follows the 7th field, spaces included
+            for _ in 0..7 {
+                let trimmed = rest.trim_start();
+                let end = trimmed.find(char::is_whitespace)?;
+                fields.push(&trimmed[..end]);
+                rest = &trimmed[end..];
+            }
+            let mode = fields[0];
+            if !matches!(mode.chars().next(), Some('-' | 'd' | 'l')) {
+                return None;
+            }
+            let name = rest.trim_start().split(" -> ").next()?.to_string();
+            if name.is_empty() || name == "." || name == ".." {
+                return None;
+            }
+            Some(Entry { name, is_dir: !mode.starts_with('-'), size: fields[4].parse().unwrap_or(0) })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    entries
+}
+
+fn list(serial: &str, dir: &str) -> Result<Vec<Entry>, String> {
+    adb::shell(serial, &format!("ls -la {}", shell_quote(&format!("{}/", dir.trim_end_matches('/')))))
+        .map(|output| parse_listing(&output))
+}
+
+/// Size of a device file, for push progress.
+fn remote_size(serial: &str, path: &str) -> Option<u64> {
+    adb::shell(serial, &format!("stat -c %s {}", shell_quote(path))).ok()?.trim().parse().ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Push,
+    Pull,
+}
+
+/// An `adb push` or `adb pull` running in the background. These run outside
+/// the adb queue and its timeouts, since large files take as long as they take.
+pub struct Transfer {
+    direction: Direction,
+    pub label: String,
+    pub total: u64,
+    done: Arc<AtomicU64>,
+    job: Option<Job<Result<(), String>>>,
+    pub result: Option<Result<(), String>>,
+}
+
+impl Transfer {
+    /// Pushes `local` into the device directory `dir`.
+    pub fn push(serial: &str, local: &Path, dir: &str) -> Self {
+        let name = local.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let total = std::fs::metadata(local).map(|m| m.len()).unwrap_or(0);
+        Self::start(serial, Direction::Push, local, &join(dir, &name), total)
+    }
+
+    fn start(serial: &str, direction: Direction, local: &Path, remote: &str, total: u64) -> Self {
+        let done = Arc::new(AtomicU64::new(0));
+        let (serial, local, remote, progress) = (serial.to_string(), local.to_path_buf(), remote.to_string(), done.clone());
+        let label = match direction {
+            Direction::Push => format!("⬆ {} → {}", local.display(), remote),
+            Direction::Pull => format!("⬇ {} → {}", remote, local.display()),
+        };
+        let job = Job::spawn(move || {
+            let action = if direction == Direction::Push { "push" } else { "pull" };
+            let mut child = Command::new(adb::program())
+                .args(["-s", &serial, action])
+                .args(match direction {
+                    Direction::Push => [local.to_string_lossy().to_string(), remote.clone()],
+                    Direction::Pull => [remote.clone(), local.to_string_lossy().to_string()],
+                })
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("adb failed: {}", e))?;
+            let status = loop {
+                if let Some(status) = child.try_wait().map_err(|e| format!("adb failed: {}", e))? {
+                    break status;
+                }
+                let size = match direction {
+                    Direction::Pull => std::fs::metadata(&local).map(|m| m.len()).ok(),
+                    Direction::Push => remote_size(&serial, &remote),
+                };
+                if let Some(size) = size {
+                    progress.store(size, Ordering::Relaxed);
+                }
+                thread::sleep(PROGRESS_INTERVAL);
+            };
+            if status.success() {
+                return Ok(());
+            }
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                let _ = std::io::Read::read_to_string(&mut pipe, &mut stderr);
+            }
+            Err(stderr.trim().lines().last().unwrap_or("adb failed").to_string())
+        });
+        Self { direction, label, total, done, job: Some(job), result: None }
+    }
+
+    /// Whether the transfer finished since the last call.
+    pub fn poll(&mut self) -> bool {
+        let Some(result) = self.job.as_ref().and_then(Job::poll) else { return false };
+        self.job = None;
+        if result.is_ok() {
+            self.done.store(self.total, Ordering::Relaxed);
+        }
+        self.result = Some(result);
+        true
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return if self.is_running() { 0.0 } else { 1.0 };
+        }
+        (self.done.load(Ordering::Relaxed) as f32 / self.total as f32).min(1.0)
+    }
+
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+}
+
+/// Directory browser of one device with push and pull.
+pub struct FileBrowser {
+    pub serial: String,
+    pub dir: String,
+    /// Path being typed into the location field.
+    pub dir_input: String,
+    pub listing: Option<Result<Vec<Entry>, String>>,
+    list_job: Option<Job<Result<Vec<Entry>, String>>>,
+    pub transfers: Vec<Transfer>,
+}
+
+impl FileBrowser {
+    pub fn new(serial: &str) -> Self {
+        let mut browser = Self {
+            serial: serial.to_string(),
+            dir: START_DIR.to_string(),
+            dir_input: START_DIR.to_string(),
+            listing: None,
+            list_job: None,
+            transfers: Vec::new(),
+        };
+        browser.refresh();
+        browser
+    }
+
+    pub fn refresh(&mut self) {
+        let (serial, dir) = (self.serial.clone(), self.dir.clone());
+        self.list_job = Some(Job::spawn(move || list(&serial, &dir)));
+    }
+
+    pub fn open(&mut self, dir: String) {
+        self.dir_input = dir.clone();
+        self.dir = dir;
+        self.listing = None;
+        self.refresh();
+    }
+
+    pub fn up(&mut self) {
+        let trimmed = self.dir.trim_end_matches('/');
+        let parent = match trimmed.rfind('/') {
+            Some(0) | None => "/".to_string(),
+            Some(i) => trimmed[..i].to_string(),
+        };
+        self.open(parent);
+    }
+
+    pub fn is_listing(&self) -> bool {
+        self.list_job.is_some()
+    }
+
+    pub fn poll(&mut self) {
+        if let Some(listing) = self.list_job.as_ref().and_then(Job::poll) {
+            self.list_job = None;
+            self.listing = Some(listing);
+        }
+        let mut pushed = false;
+        for transfer in &mut self.transfers {
+            pushed |= transfer.poll() && transfer.direction == Direction::Push;
+        }
+        // Pushed files show up in the listing once they are there
+        if pushed && !self.is_listing() {
+            self.refresh();
+        }
+    }
+
+    pub fn pull(&mut self, entry: &Entry, local: PathBuf) {
+        let remote = join(&self.dir, &entry.name);
+        self.transfers.push(Transfer::start(&self.serial, Direction::Pull, &local, &remote, entry.size));
+    }
+
+    pub fn push(&mut self, local: PathBuf) {
+        self.transfers.push(Transfer::push(&self.serial, &local, &self.dir));
+    }
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn open_file_browser(&mut self, serial: &str) {
+        if self.file_browser.as_ref().is_none_or(|b| b.serial != serial) {
+            self.file_browser = Some(FileBrowser::new(serial));
+        }
+    }
+
+    /// Device directory listing with pull and push, and the progress of
+    /// the transfers started from it.
+    pub(crate) fn show_file_browser(&mut self, ctx: &egui::Context) {
+        let Some(serial) = self.file_browser.as_ref().map(|b| b.serial.clone()) else { return };
+        let name = self.device_display_name(&serial);
+        let formats = self.formats();
+        let browser = self.file_browser.as_mut().unwrap();
+        browser.poll();
+        let mut open = true;
+        let mut finished = Vec::new();
+        egui::Window::new(format!("📁 Files – {}", name)).open(&mut open).default_size([560.0, 480.0]).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("⬆").on_hover_text("Parent directory").clicked() {
+                    browser.up();
+                }
+                let response = ui.add(egui::TextEdit::singleline(&mut browser.dir_input).desired_width(300.0));
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    let dir = browser.dir_input.trim().to_string();
+                    browser.open(dir);
+                }
+                if ui.button("🔄").on_hover_text("Refresh").clicked() {
+                    browser.refresh();
+                }
+                if ui.button("⬆ Push file…").on_hover_text("Copy a file from this computer into this directory").clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_file()
+                {
+                    browser.push(path);
+                }
+                if browser.is_listing() {
+                    ui.spinner();
+                }
+            });
+            ui.separator();
+            let mut navigate = None;
+            let mut pull = None;
+            egui::ScrollArea::vertical().max_height(300.0).auto_shrink([false, true]).show(ui, |ui| match &browser.listing {
+                None => {
+                    ui.weak("Loading…");
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, format!("⚠️ {}", e));
+                }
+                Some(Ok(entries)) if entries.is_empty() => {
+                    ui.weak("Empty directory.");
+                }
+                Some(Ok(entries)) => {
+                    egui::Grid::new("file_entries").striped(true).num_columns(3).show(ui, |ui| {
+                        for entry in entries {
+                            if entry.is_dir {
+                                if ui.link(format!("📁 {}", entry.name)).clicked() {
+                                    navigate = Some(join(&browser.dir, &entry.name));
+                                }
+                                ui.label("");
+                                ui.label("");
+                            } else {
+                                ui.label(format!("📄 {}", entry.name));
+                                ui.label(formats.bytes(entry.size));
+                                if ui.small_button("⬇ Pull…").on_hover_text("Copy to this computer").clicked() {
+                                    pull = Some(entry.clone());
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                }
+            });
+            if let Some(dir) = navigate {
+                browser.open(dir);
+            }
+            if let Some(entry) = pull
+                && let Some(local) = rfd::FileDialog::new().set_file_name(&entry.name).save_file()
+            {
+                browser.pull(&entry, local);
+            }
+            if !browser.transfers.is_empty() {
+                ui.separator();
+                ui.strong("Transfers");
+                for (i, transfer) in browser.transfers.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(&transfer.label);
+                        match &transfer.result {
+                            None => {
+                                let text = format!("{} / {}", formats.bytes(transfer.done()), formats.bytes(transfer.total));
+                                ui.add(egui::ProgressBar::new(transfer.progress()).text(text).desired_width(200.0));
+                            }
+                            Some(Ok(())) => {
+                                ui.colored_label(egui::Color32::GREEN, "✅ Done");
+                            }
+                            Some(Err(e)) => {
+                                ui.colored_label(egui::Color32::RED, format!("⚠️ {}", e));
+                            }
+                        }
+                        if !transfer.is_running() && ui.small_button("✖").on_hover_text("Remove from the list").clicked() {
+                            finished.push(i);
+                        }
+                    });
+                }
+            }
+        });
+        for i in finished.into_iter().rev() {
+            browser.transfers.remove(i);
+        }
+        if !open && browser.transfers.iter().all(|t| !t.is_running()) {
+            self.file_browser = None;
+        } else if !open {
+            self.notices.device(&serial, "⚠️ Close the file window once its transfers finish.");
+        }
+    }
+}
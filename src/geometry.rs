@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Suggested scrcpy window size relative to the mirrored video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowSizing {
+    /// Leave the size to scrcpy.
+    Auto,
+    /// One device pixel per screen pixel.
+    OneToOne,
+    /// Two device pixels per screen pixel on each axis.
+    OneToTwo,
+}
+
+impl WindowSizing {
+    pub const ALL: [WindowSizing; 3] = [WindowSizing::Auto, WindowSizing::OneToOne, WindowSizing::OneToTwo];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowSizing::Auto => "scrcpy default",
+            WindowSizing::OneToOne => "1:1 device pixels",
+            WindowSizing::OneToTwo => "1:2 device pixels",
+        }
+    }
+
+    fn divisor(self) -> Option<f32> {
+        match self {
+            WindowSizing::Auto => None,
+            WindowSizing::OneToOne => Some(1.0),
+            WindowSizing::OneToTwo => Some(2.0),
+        }
+    }
+}
+
+/// Value of `--name=value`, `--name value` or the short `-x value` form.
+fn option<'a>(args: &'a [String], long: &str, short: Option<&str>) -> Option<&'a str> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if let Some(value) = arg.strip_prefix(long).and_then(|v| v.strip_prefix('=')) {
+            return Some(value);
+        }
+        (arg == long || Some(arg.as_str()) == short).then(|| args.get(i + 1).map(String::as_str)).flatten()
+    })
+}
+
+/// Size of the video scrcpy will produce: the crop if any, scaled down so
+/// the longer side fits `--max-size`.
+pub fn video_size(screen: (u32, u32), args: &[String]) -> (f32, f32) {
+    let (mut w, mut h) = (screen.0 as f32, screen.1 as f32);
+    if let Some(crop) = option(args, "--crop", None) {
+        let mut parts = crop.split(':').filter_map(|p| p.parse::<f32>().ok());
+        if let (Some(cw), Some(ch)) = (parts.next(), parts.next()) {
+            (w, h) = (cw, ch);
+        }
+    }
+    if let Some(max) = option(args, "--max-size", Some("-m")).and_then(|m| m.parse::<f32>().ok())
+        && max > 0.0
+        && w.max(h) > max
+    {
+        let scale = max / w.max(h);
+        (w, h) = ((w * scale).round(), (h * scale).round());
+    }
+    (w, h)
+}
+
+/// Window size in logical units for the monitor's `scale_factor` (physical
+/// pixels per point), keeping the device aspect ratio.
+pub fn window_size(sizing: WindowSizing, video: (f32, f32), scale_factor: f32) -> Option<(u32, u32)> {
+    let divisor = sizing.divisor()? * scale_factor.max(0.1);
+    Some(((video.0 / divisor).round() as u32, (video.1 / divisor).round() as u32))
+}
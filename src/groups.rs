@@ -0,0 +1,45 @@
+use crate::persist;
+use serde::{Deserialize, Serialize};
+
+pub const GROUPS_PATH: &str = "groups.json";
+
+/// A device in a group, with what it does differently from the group.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupMember {
+    pub serial: String,
+    /// Device config entry used instead of the group's; empty keeps it.
+    pub profile: String,
+    /// scrcpy options replacing the same options of the profile, e.g. a crop.
+    pub args: String,
+}
+
+/// Devices started together with a shared base profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceGroup {
+    pub name: String,
+    /// Device config entry for every member; empty uses each device's detected one.
+    pub profile: String,
+    pub members: Vec<GroupMember>,
+}
+
+impl Default for DeviceGroup {
+    fn default() -> Self {
+        Self { name: "New group".to_string(), profile: String::new(), members: Vec::new() }
+    }
+}
+
+pub fn load() -> Vec<DeviceGroup> {
+    persist::read_json(persist::data(GROUPS_PATH))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load {}: {}", GROUPS_PATH, e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+pub fn save(groups: &[DeviceGroup]) -> std::io::Result<()> {
+    let data = serde_json::to_string_pretty(groups).map_err(std::io::Error::other)?;
+    persist::write_atomic(persist::data(GROUPS_PATH), data)
+}
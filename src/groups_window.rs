@@ -0,0 +1,157 @@
+use crate::groups::{self, DeviceGroup, GroupMember};
+use crate::{ScrcpyGuiApp, cmdline, parse_config_args};
+use eframe::egui;
+
+impl ScrcpyGuiApp {
+    /// Config entry name a group member starts with: its own override, the
+    /// group's profile, or the one detected for the device.
+    fn member_profile(&self, group: &DeviceGroup, member: &GroupMember) -> Option<String> {
+        [member.profile.trim(), group.profile.trim()]
+            .into_iter()
+            .find(|p| !p.is_empty())
+            .map(str::to_string)
+            .or_else(|| {
+                let model = self.device_props.get(&member.serial).map(|i| i.model.clone()).unwrap_or_default();
+                [model.as_str(), "default"].into_iter().find(|k| self.device_config.contains_key(*k)).map(str::to_string)
+            })
+    }
+
+    /// Starts every connected member of a group with its merged settings.
+    pub(crate) fn start_group(&mut self, index: usize) {
+        let Some(group) = self.groups.get(index).cloned() else { return };
+        let mut started = 0;
+        let mut problems = Vec::new();
+        for member in &group.members {
+            if !self.devices.contains(&member.serial) {
+                problems.push(format!("{}: not connected", member.serial));
+                continue;
+            }
+            if self.sessions.is_running(&member.serial) {
+                problems.push(format!("{}: already mirroring", member.serial));
+                continue;
+            }
+            let base = match self.member_profile(&group, member) {
+                Some(profile) => match self.device_config.get(&profile) {
+                    Some(config) => config.scrcpy_args.clone(),
+                    None => {
+                        problems.push(format!("{}: no profile named {}", member.serial, profile));
+                        continue;
+                    }
+                },
+                None => String::new(),
+            };
+            let merged = cmdline::merge_args(&base, &member.args);
+            match self.launch_session_with(&member.serial, None, parse_config_args(&merged)) {
+                Ok(()) => started += 1,
+                Err(e) => problems.push(format!("{}: {}", member.serial, e)),
+            }
+        }
+        self.notices.global(if problems.is_empty() {
+            format!("✅ Started {} devices of {}.", started, group.name)
+        } else {
+            format!("⚠️ Started {} devices of {}; {}", started, group.name, problems.join("; "))
+        });
+    }
+
+    pub(crate) fn show_groups_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_groups;
+        let mut profiles: Vec<String> = self.device_config.keys().cloned().collect();
+        profiles.sort();
+        let mut start = None;
+        egui::Window::new("👥 Device groups")
+            .open(&mut open)
+            .default_size([600.0, 420.0])
+            .show(ctx, |ui| {
+                ui.label("\"Start group\" launches every connected member with the group profile, merged with the member's overrides.");
+                let mut changed = false;
+                let mut remove = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, group) in self.groups.iter_mut().enumerate() {
+                        ui.push_id(i, |ui| {
+                            egui::Frame::group(ui.style()).show(ui, |ui| {
+                                changed |= group_editor(ui, group, &profiles, &self.devices);
+                                ui.horizontal(|ui| {
+                                    if ui.add_enabled(!group.members.is_empty(), egui::Button::new("▶ Start group")).clicked() {
+                                        start = Some(i);
+                                    }
+                                    if ui.small_button("🗑 Remove group").clicked() {
+                                        remove = Some(i);
+                                    }
+                                });
+                            });
+                        });
+                    }
+                });
+                if let Some(i) = remove {
+                    self.groups.remove(i);
+                    changed = true;
+                }
+                if ui.button("➕ Add group").clicked() {
+                    self.groups.push(DeviceGroup::default());
+                    changed = true;
+                }
+                if changed && let Err(e) = groups::save(&self.groups) {
+                    self.notices.global(format!("⚠️ Failed to save groups: {}", e));
+                }
+            });
+        if let Some(i) = start {
+            self.start_group(i);
+        }
+        self.show_groups = open;
+    }
+}
+
+pub(crate) fn profile_combo(ui: &mut egui::Ui, id: &str, value: &mut String, profiles: &[String], empty: &str) -> bool {
+    let mut changed = false;
+    let selected = if value.is_empty() { empty.to_string() } else { value.clone() };
+    egui::ComboBox::from_id_salt(id).selected_text(selected).show_ui(ui, |ui| {
+        changed |= ui.selectable_value(value, String::new(), empty).changed();
+        for profile in profiles {
+            changed |= ui.selectable_value(value, profile.clone(), profile).changed();
+        }
+    });
+    changed
+}
+
+/// Edits one group in place; returns whether anything changed.
+fn group_editor(ui: &mut egui::Ui, group: &mut DeviceGroup, profiles: &[String], devices: &[String]) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui.text_edit_singleline(&mut group.name).changed();
+        ui.label("profile");
+        changed |= profile_combo(ui, "group_profile", &mut group.profile, profiles, "(detected)");
+    });
+    let mut remove = None;
+    for (i, member) in group.members.iter_mut().enumerate() {
+        ui.push_id(i, |ui| {
+            ui.horizontal(|ui| {
+                let connected = devices.contains(&member.serial);
+                ui.label(if connected { "●" } else { "○" }).on_hover_text(if connected { "Connected" } else { "Not connected" });
+                egui::ComboBox::from_id_salt("member_serial").selected_text(&member.serial).show_ui(ui, |ui| {
+                    for serial in devices {
+                        changed |= ui.selectable_value(&mut member.serial, serial.clone(), serial).changed();
+                    }
+                });
+                changed |= profile_combo(ui, "member_profile", &mut member.profile, profiles, "(group profile)");
+                changed |= ui.add(egui::TextEdit::singleline(&mut member.args).hint_text("overrides, e.g. --crop=…").desired_width(180.0))
+                    .on_hover_text("Options here replace the same options of the profile")
+                    .changed();
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        });
+    }
+    if let Some(i) = remove {
+        group.members.remove(i);
+        changed = true;
+    }
+    let next = devices.iter().find(|d| !group.members.iter().any(|m| &m.serial == *d));
+    if ui.add_enabled(next.is_some(), egui::Button::new("➕ Add member")).clicked()
+        && let Some(serial) = next
+    {
+        group.members.push(GroupMember { serial: serial.clone(), ..Default::default() });
+        changed = true;
+    }
+    changed
+}
@@ -0,0 +1,97 @@
+use crate::persist;
+use crate::session::CompanionSpec;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Running sessions written for the next run of the GUI to adopt.
+pub const HANDOFF_PATH: &str = "sessions.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanionRecord {
+    pub spec: CompanionSpec,
+    pub pid: u32,
+}
+
+/// A scrcpy process launched by the GUI, as persisted for hand-off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub serial: String,
+    pub binary: String,
+    pub args: Vec<String>,
+    pub pid: u32,
+    pub started_at: DateTime<Local>,
+    #[serde(default)]
+    pub companions: Vec<CompanionRecord>,
+}
+
+pub fn load() -> Vec<SessionRecord> {
+    persist::read_json(persist::data(HANDOFF_PATH))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load {}: {}", HANDOFF_PATH, e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+pub fn save(records: &[SessionRecord]) {
+    match serde_json::to_string_pretty(records) {
+        Ok(data) => {
+            if let Err(e) = persist::write_atomic(persist::data(HANDOFF_PATH), data) {
+                eprintln!("Failed to save {}: {}", HANDOFF_PATH, e);
+            }
+        }
+        Err(e) => eprintln!("Failed to serialize sessions: {}", e),
+    }
+}
+
+/// Executable name of a running process, without directory or `.exe`.
+fn process_name(pid: u32) -> Option<String> {
+    let output = if cfg!(windows) {
+        Command::new("tasklist").args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"]).stderr(Stdio::null()).output()
+    } else {
+        Command::new("ps").args(["-p", &pid.to_string(), "-o", "comm="]).stderr(Stdio::null()).output()
+    }
+    .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // tasklist prints `"scrcpy.exe","1234",...`, ps just the command
+    let name = text.lines().next()?.split(',').next()?.trim().trim_matches('"');
+    let name = Path::new(name).file_name()?.to_string_lossy().into_owned();
+    Some(name.strip_suffix(".exe").unwrap_or(&name).to_string()).filter(|n| !n.is_empty())
+}
+
+/// Whether `pid` is still running `program`, so a pid reused by another
+/// process is not mistaken for the session.
+pub fn is_alive(pid: u32, program: &str) -> bool {
+    let Some(expected) = Path::new(program).file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+        return false;
+    };
+    // Linux truncates the command name to 15 characters, so only a name of
+    // that length may be a prefix
+    process_name(pid).is_some_and(|name| name == expected || (name.len() == 15 && expected.starts_with(&name)))
+}
+
+/// Asks `pid` to quit the way closing its window or Ctrl+C would: SIGINT, or
+/// `taskkill` without `/F` (a close request to its windows) on Windows.
+pub fn interrupt(pid: u32) {
+    let status = if cfg!(windows) {
+        Command::new("taskkill").args(["/PID", &pid.to_string()]).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    } else {
+        Command::new("kill").args(["-INT", &pid.to_string()]).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    };
+    if let Err(e) = status {
+        eprintln!("Failed to interrupt process {}: {}", pid, e);
+    }
+}
+
+pub fn kill(pid: u32) {
+    let status = if cfg!(windows) {
+        Command::new("taskkill").args(["/PID", &pid.to_string(), "/F"]).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    } else {
+        Command::new("kill").arg(pid.to_string()).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    };
+    if let Err(e) = status {
+        eprintln!("Failed to stop process {}: {}", pid, e);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::persist;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+pub const HISTORY_PATH: &str = "connection_history.json";
+/// Devices remembered; the least recently used are dropped.
+const MAX_DEVICES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsedDevice {
+    pub serial: String,
+    /// `ro.product.model` when it was known.
+    #[serde(default)]
+    pub model: String,
+    pub last_used: DateTime<Local>,
+}
+
+/// Devices mirrored from this machine, most recent first, for the "Recently
+/// used" part of the device selector.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionHistory {
+    pub devices: Vec<UsedDevice>,
+}
+
+impl ConnectionHistory {
+    /// Empty when the file is missing; `Err` when it can't be parsed.
+    pub fn load() -> Result<Self, String> {
+        persist::read_json(persist::data(HISTORY_PATH)).map(Option::unwrap_or_default)
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(data) => {
+                if let Err(e) = persist::write_atomic(persist::data(HISTORY_PATH), data) {
+                    eprintln!("Failed to save {}: {}", HISTORY_PATH, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize connection history: {}", e),
+        }
+    }
+
+    /// Moves the device to the front; an empty `model` keeps the known one.
+    pub fn record_use(&mut self, serial: &str, model: &str) {
+        let previous = self.devices.iter().position(|d| d.serial == serial).map(|i| self.devices.remove(i));
+        let model = if model.is_empty() { previous.map(|d| d.model).unwrap_or_default() } else { model.to_string() };
+        self.devices.insert(0, UsedDevice { serial: serial.to_string(), model, last_used: Local::now() });
+        self.devices.truncate(MAX_DEVICES);
+        self.save();
+    }
+
+    pub fn recent(&self, count: usize) -> &[UsedDevice] {
+        &self.devices[..self.devices.len().min(count)]
+    }
+}
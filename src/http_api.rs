@@ -0,0 +1,82 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Health state served on `GET /health`: whether everything is fine, and the
+/// JSON body.
+type Snapshot = Arc<Mutex<(bool, String)>>;
+
+/// Minimal HTTP server on localhost for monitoring tools. Stops when dropped,
+/// and the port is free again once the drop returns.
+pub struct HttpApi {
+    pub port: u16,
+    snapshot: Snapshot,
+    stop: Arc<AtomicBool>,
+    /// The accept loop, which owns the listener.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HttpApi {
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        // Non-blocking accept so the thread notices `stop`
+        listener.set_nonblocking(true)?;
+        let snapshot = Snapshot::new(Mutex::new((true, "{}".to_string())));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (served, stopped) = (snapshot.clone(), stop.clone());
+        let thread = thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let snapshot = served.clone();
+                        thread::spawn(move || {
+                            let _ = respond(stream, &snapshot);
+                        });
+                    }
+                    Err(_) => thread::sleep(Duration::from_millis(100)),
+                }
+            }
+        });
+        Ok(Self { port, snapshot, stop, thread: Some(thread) })
+    }
+
+    pub fn set_health(&self, healthy: bool, body: String) {
+        *self.snapshot.lock().unwrap() = (healthy, body);
+    }
+}
+
+impl Drop for HttpApi {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, snapshot: &Snapshot) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    // The request line is all that is needed
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf)?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let path = request.lines().next().and_then(|line| line.strip_prefix("GET ")).and_then(|rest| rest.split_whitespace().next());
+    let (status, body) = match path {
+        Some("/health") => {
+            let (healthy, body) = snapshot.lock().unwrap().clone();
+            (if healthy { "200 OK" } else { "503 Service Unavailable" }, body)
+        }
+        _ => ("404 Not Found", "{\"error\":\"not found\"}".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
@@ -0,0 +1,43 @@
+use crate::adb::{self, shell as adb_shell};
+use base64::Engine;
+use std::thread;
+use std::time::Duration;
+
+const ADB_KEYBOARD_PACKAGE: &str = "com.android.adbkeyboard";
+const ADB_KEYBOARD_IME: &str = "com.android.adbkeyboard/.AdbIME";
+
+pub fn is_adb_keyboard_installed(serial: &str) -> bool {
+    adb_shell(serial, &format!("pm list packages {}", ADB_KEYBOARD_PACKAGE))
+        .is_ok_and(|out| out.lines().any(|l| l.trim() == format!("package:{}", ADB_KEYBOARD_PACKAGE)))
+}
+
+pub fn install_adb_keyboard(serial: &str, apk: &str) -> Result<(), String> {
+    let output = adb::run(&["-s", serial, "install", "-r", apk], adb::LONG, None)?;
+    let text = String::from_utf8_lossy(&output.stdout).to_string() + &String::from_utf8_lossy(&output.stderr);
+    if output.status.success() && text.contains("Success") {
+        Ok(())
+    } else {
+        Err(text.trim().to_string())
+    }
+}
+
+/// Types arbitrary Unicode text through ADBKeyBoard, which unlike
+/// `input text` handles non-ASCII characters and spaces. The previously
+/// active IME is restored afterwards, even if sending failed.
+pub fn type_text(serial: &str, text: &str) -> Result<(), String> {
+    if !is_adb_keyboard_installed(serial) {
+        return Err("ADBKeyBoard is not installed on the device".to_string());
+    }
+    let previous = adb_shell(serial, "settings get secure default_input_method")?;
+    adb_shell(serial, &format!("ime enable {}", ADB_KEYBOARD_IME))?;
+    adb_shell(serial, &format!("ime set {}", ADB_KEYBOARD_IME))?;
+    // Base64 keeps the text clear of any shell quoting issues
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let sent = adb_shell(serial, &format!("am broadcast -a ADB_INPUT_B64 --es msg {}", encoded));
+    // The broadcast is handled asynchronously; give the IME time to commit the text
+    thread::sleep(Duration::from_millis(300));
+    if !previous.is_empty() && previous != "null" && previous != ADB_KEYBOARD_IME {
+        adb_shell(serial, &format!("ime set {}", previous))?;
+    }
+    sent.map(|_| ())
+}
@@ -0,0 +1,99 @@
+use crate::ScrcpyGuiApp;
+use crate::embedded::{self, Input, TouchAction};
+use eframe::egui;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long an event stays in the on-screen log.
+const SHOW_FOR: Duration = Duration::from_secs(3);
+/// How long the ring at a tap location stays visible.
+const RING_FOR: Duration = Duration::from_millis(700);
+const MAX_EVENTS: usize = 8;
+
+/// Input the GUI sent to a device.
+pub struct InputEvent {
+    at: Instant,
+    serial: String,
+    label: String,
+    /// Where a touch landed, relative to the screen (`0.0..=1.0`).
+    position: Option<(f32, f32)>,
+}
+
+/// Recent taps and keys sent from the touch pad, the embedded view and text
+/// input, for audiences of demos and screencasts to follow along.
+#[derive(Default)]
+pub struct InputEcho {
+    events: VecDeque<InputEvent>,
+}
+
+pub fn key_name(keycode: u32) -> String {
+    match keycode {
+        embedded::KEYCODE_HOME => "Home".to_string(),
+        embedded::KEYCODE_BACK => "Back".to_string(),
+        embedded::KEYCODE_POWER => "Power".to_string(),
+        embedded::KEYCODE_ENTER => "Enter".to_string(),
+        embedded::KEYCODE_DEL => "Backspace".to_string(),
+        embedded::KEYCODE_APP_SWITCH => "Recent apps".to_string(),
+        other => format!("key {}", other),
+    }
+}
+
+impl InputEcho {
+    pub fn push(&mut self, serial: &str, label: impl Into<String>, position: Option<(f32, f32)>) {
+        self.events.push_back(InputEvent { at: Instant::now(), serial: serial.to_string(), label: label.into(), position });
+        if self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    /// Records embedded view input; moves are left out so a drag shows as
+    /// its start and end.
+    pub fn push_embedded(&mut self, serial: &str, input: &Input) {
+        match input {
+            Input::Touch(TouchAction::Down, x, y) => self.push(serial, "👆 Touch", Some((*x, *y))),
+            Input::Touch(TouchAction::Up, x, y) => self.push(serial, "☝ Release", Some((*x, *y))),
+            Input::Touch(..) => {}
+            Input::Key(keycode) => self.push(serial, format!("⌨ {}", key_name(*keycode)), None),
+            Input::Text(text) => self.push(serial, format!("⌨ \"{}\"", text), None),
+        }
+    }
+
+    /// Rings at the recent touch locations of `serial` over its picture.
+    pub fn paint_touches(&self, painter: &egui::Painter, rect: egui::Rect, serial: &str) {
+        for event in self.events.iter().filter(|e| e.serial == serial) {
+            let Some((x, y)) = event.position else { continue };
+            let age = event.at.elapsed();
+            if age > RING_FOR {
+                continue;
+            }
+            let fade = 1.0 - age.as_secs_f32() / RING_FOR.as_secs_f32();
+            let center = rect.min + egui::vec2(x * rect.width(), y * rect.height());
+            let color = egui::Color32::from_rgba_unmultiplied(255, 200, 0, (fade * 255.0) as u8);
+            painter.circle_stroke(center, 10.0 + 14.0 * (1.0 - fade), egui::Stroke::new(3.0, color));
+        }
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// The last input events in the bottom left corner, while "Echo input"
+    /// is on.
+    pub(crate) fn show_input_echo(&mut self, ctx: &egui::Context) {
+        self.input_echo.events.retain(|e| e.at.elapsed() < SHOW_FOR);
+        if !self.settings.echo_input || self.input_echo.events.is_empty() {
+            return;
+        }
+        egui::Area::new(egui::Id::new("input_echo"))
+            .anchor(egui::Align2::LEFT_BOTTOM, [12.0, -12.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for event in &self.input_echo.events {
+                        let position = event.position.map(|(x, y)| format!(" at {:.0}%, {:.0}%", x * 100.0, y * 100.0)).unwrap_or_default();
+                        let device = self.device_display_name(&event.serial);
+                        ui.label(egui::RichText::new(format!("{}{}  ·  {}", event.label, position, device)).size(16.0));
+                    }
+                });
+            });
+    }
+}
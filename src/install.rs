@@ -0,0 +1,162 @@
+use crate::files::Transfer;
+use crate::job::Job;
+use crate::{ScrcpyGuiApp, adb, packages, persist};
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Split APK bundles (SAI `.apks`, APKMirror `.apkm`), zips of the base and
+/// split APKs that go to `adb install-multiple`.
+const BUNDLE_EXTENSIONS: [&str; 2] = ["apks", "apkm"];
+
+pub fn is_installable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("apk") || BUNDLE_EXTENSIONS.iter().any(|b| e.eq_ignore_ascii_case(b)))
+}
+
+/// The APKs of a bundle, extracted to a fresh directory under the cache.
+fn extract_bundle(bundle: &Path) -> Result<(PathBuf, Vec<PathBuf>), String> {
+    let file = fs::File::open(bundle).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("not a valid bundle: {}", e))?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+    let dir = persist::data(packages::CACHE_DIR).join("install").join(stamp.to_string());
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let mut apks = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        // Only top-level APKs; nested paths could point outside `dir`
+        let Some(name) = entry.enclosed_name().filter(|n| n.components().count() == 1 && n.extension().is_some_and(|e| e == "apk")) else {
+            continue;
+        };
+        let path = dir.join(name);
+        let mut out = fs::File::create(&path).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        apks.push(path);
+    }
+    if apks.is_empty() {
+        return Err("the bundle contains no APK".to_string());
+    }
+    Ok((dir, apks))
+}
+
+/// `adb install -r`, or `adb install-multiple -r` for bundles. The error is
+/// adb's failure reason, e.g. `INSTALL_FAILED_VERSION_DOWNGRADE`.
+pub fn install(serial: &str, path: &Path) -> Result<(), String> {
+    let is_bundle = path.extension().and_then(|e| e.to_str()).is_some_and(|e| BUNDLE_EXTENSIONS.iter().any(|b| e.eq_ignore_ascii_case(b)));
+    let (extracted, apks) = if is_bundle {
+        let (dir, apks) = extract_bundle(path)?;
+        (Some(dir), apks)
+    } else {
+        (None, vec![path.to_path_buf()])
+    };
+    let command = if is_bundle { "install-multiple" } else { "install" };
+    let apks: Vec<String> = apks.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    let mut args = vec!["-s", serial, command, "-r"];
+    args.extend(apks.iter().map(String::as_str));
+    let result = adb::run(&args, adb::LONG, None);
+    if let Some(dir) = extracted {
+        let _ = fs::remove_dir_all(dir);
+    }
+    let output = result?;
+    let text = String::from_utf8_lossy(&output.stdout).to_string() + &String::from_utf8_lossy(&output.stderr);
+    if output.status.success() && text.contains("Success") {
+        return Ok(());
+    }
+    let reason = text
+        .lines()
+        .find_map(|l| l.split_once("Failure [").map(|(_, r)| r.trim_end_matches(']').to_string()))
+        .unwrap_or_else(|| text.trim().lines().last().unwrap_or("adb install failed").to_string());
+    Err(reason)
+}
+
+/// An install started by dropping a file on the window.
+pub struct Install {
+    pub serial: String,
+    pub name: String,
+    pub started: Instant,
+    job: Job<Result<(), String>>,
+}
+
+impl ScrcpyGuiApp {
+    /// Installs APKs dropped on the window on the selected device and pushes
+    /// other files to its drop folder, like scrcpy's own drag and drop but
+    /// without a mirror. The window is highlighted while files are dragged
+    /// over it.
+    pub(crate) fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let (hovering, dropped) = ctx.input(|i| (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone()));
+        let target = self.selected_serial().filter(|_| !self.locked());
+        if hovering {
+            let text = match &target {
+                Some(serial) => format!("📦 Drop to install APKs on {} or copy files to {}", self.device_display_name(serial), self.settings.drop_folder),
+                None => "📦 Select a device to drop files on".to_string(),
+            };
+            let screen = ctx.screen_rect();
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop_target")));
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(screen.center(), egui::Align2::CENTER_CENTER, text, egui::FontId::proportional(22.0), egui::Color32::WHITE);
+        }
+        let Some(serial) = target else { return };
+        for file in dropped {
+            let Some(path) = file.path else { continue };
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if path.is_dir() {
+                self.notices.device(&serial, format!("⚠️ {} is a folder; drop files instead.", name));
+                continue;
+            }
+            if !is_installable(&path) {
+                self.pushes.push((serial.clone(), Transfer::push(&serial, &path, &self.settings.drop_folder)));
+                continue;
+            }
+            let job_serial = serial.clone();
+            let job = Job::spawn(move || install(&job_serial, &path));
+            self.installs.push(Install { serial: serial.clone(), name, started: Instant::now(), job });
+        }
+    }
+
+    pub(crate) fn poll_installs(&mut self) {
+        let mut finished = Vec::new();
+        self.installs.retain(|install| match install.job.poll() {
+            Some(result) => {
+                finished.push((install.serial.clone(), install.name.clone(), result));
+                false
+            }
+            None => true,
+        });
+        for (serial, name, result) in finished {
+            match result {
+                Ok(()) => self.notices.device(&serial, format!("✅ Installed {}.", name)),
+                Err(e) => self.notices.device(&serial, format!("⚠️ Failed to install {}: {}", name, e)),
+            }
+        }
+        for (serial, push) in &mut self.pushes {
+            if push.poll() {
+                match &push.result {
+                    Some(Err(e)) => self.notices.device(serial, format!("⚠️ Failed to copy {}: {}", push.label, e)),
+                    _ => self.notices.device(serial, format!("✅ Copied {}", push.label)),
+                }
+            }
+        }
+        self.pushes.retain(|(_, push)| push.is_running());
+    }
+
+    /// Running installs and copies of the device, with how long or how far
+    /// they've gone.
+    pub(crate) fn show_installs(&self, ui: &mut egui::Ui, serial: &str) {
+        let formats = self.formats();
+        for (_, push) in self.pushes.iter().filter(|(s, _)| s == serial) {
+            ui.horizontal(|ui| {
+                ui.label(&push.label);
+                let text = format!("{} / {}", formats.bytes(push.done()), formats.bytes(push.total));
+                ui.add(egui::ProgressBar::new(push.progress()).text(text).desired_width(200.0));
+            });
+        }
+        for install in self.installs.iter().filter(|i| i.serial == serial) {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("Installing {}… {}", install.name, self.formats().duration(install.started.elapsed().as_secs())));
+            });
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// A one-shot background task whose result the UI picks up with `poll`.
+pub struct Job<T> {
+    rx: Receiver<T>,
+}
+
+impl<T: Send + 'static> Job<T> {
+    pub fn spawn(f: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(f());
+        });
+        Self { rx }
+    }
+
+    /// The result once the task finished; `None` while it is still running.
+    pub fn poll(&self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Blocks until the task finished, e.g. when the GUI quits.
+    pub fn wait(self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{cmdline, persist};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Every scrcpy launch as a command line, for turning GUI setups into scripts.
+pub const JOURNAL_PATH: &str = "command_journal.txt";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Applies the "Log launches to the command journal" setting.
+pub fn configure(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The shell line running `binary` with `args`.
+pub fn command_line(binary: &str, args: &[String]) -> String {
+    std::iter::once(binary).chain(args.iter().map(String::as_str)).map(cmdline::quote).collect::<Vec<_>>().join(" ")
+}
+
+/// Appends a launch with a timestamp comment, when the journal is on.
+pub fn record(binary: &str, args: &[String]) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let entry = format!("# {}\n{}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), command_line(binary, args));
+    let written = OpenOptions::new().create(true).append(true).open(persist::data(JOURNAL_PATH)).and_then(|mut f| f.write_all(entry.as_bytes()));
+    if let Err(e) = written {
+        eprintln!("Failed to write {}: {}", JOURNAL_PATH, e);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::session::{Session, SessionState};
+use crate::{ScrcpyGuiApp, journal, persist};
+use chrono::{DateTime, Local};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+pub const LAUNCHES_PATH: &str = "launch_history.json";
+
+/// How a launch ended.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    Exited(Option<i32>),
+    Disconnected,
+    /// Stopped from the GUI.
+    Stopped,
+}
+
+impl Outcome {
+    pub fn from_state(state: &SessionState) -> Option<Self> {
+        match state {
+            SessionState::Running => None,
+            SessionState::Exited(code) => Some(Outcome::Exited(*code)),
+            SessionState::Disconnected => Some(Outcome::Disconnected),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Outcome::Exited(Some(0)) => "✅ closed".to_string(),
+            Outcome::Exited(Some(code)) => format!("⚠️ exit code {}", code),
+            Outcome::Exited(None) => "⚠️ killed".to_string(),
+            Outcome::Disconnected => "⚠️ disconnected".to_string(),
+            Outcome::Stopped => "⏹ stopped".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Launch {
+    pub serial: String,
+    /// Display name of the device at launch time.
+    pub device: String,
+    pub binary: String,
+    pub args: Vec<String>,
+    pub started_at: DateTime<Local>,
+    /// `None` while the session runs, or when the GUI quit before it ended.
+    pub outcome: Option<Outcome>,
+}
+
+/// scrcpy launches, most recent first, so a combination of arguments that
+/// worked can be run again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchHistory {
+    pub launches: Vec<Launch>,
+}
+
+impl LaunchHistory {
+    /// Empty when the file is missing; `Err` when it can't be parsed.
+    pub fn load() -> Result<Self, String> {
+        persist::read_json(persist::data(LAUNCHES_PATH)).map(Option::unwrap_or_default)
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(data) => {
+                if let Err(e) = persist::write_atomic(persist::data(LAUNCHES_PATH), data) {
+                    eprintln!("Failed to save {}: {}", LAUNCHES_PATH, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize launch history: {}", e),
+        }
+    }
+
+    /// Adds a launch of `session`, keeping the last `limit`.
+    pub fn record(&mut self, session: &Session, device: &str, limit: usize) {
+        let launch = Launch {
+            serial: session.serial.clone(),
+            device: device.to_string(),
+            binary: session.binary.clone(),
+            args: session.args.clone(),
+            started_at: Local::now(),
+            outcome: None,
+        };
+        self.launches.insert(0, launch);
+        self.launches.truncate(limit);
+        self.save();
+    }
+
+    /// Sets the outcome of the latest launch on `serial` that has none yet.
+    pub fn finish(&mut self, serial: &str, outcome: Outcome) {
+        let Some(launch) = self.launches.iter_mut().find(|l| l.serial == serial) else { return };
+        if launch.outcome.is_none() {
+            launch.outcome = Some(outcome);
+            self.save();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.launches.clear();
+        self.save();
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// Starts the exact command line of a past launch on its device.
+    fn rerun_launch(&mut self, index: usize) {
+        let Some(launch) = self.launches.launches.get(index).cloned() else { return };
+        if !self.devices.contains(&launch.serial) {
+            self.notices.global(format!("⚠️ {} is not connected.", launch.device));
+            return;
+        }
+        self.end_session(&launch.serial);
+        match Session::spawn(&launch.binary, &launch.serial, launch.args) {
+            Ok(session) => {
+                // The stored arguments may not match any device config
+                self.stats.record_launch(&launch.serial, "");
+                self.launches.record(&session, &launch.device, self.settings.launch_history_size);
+                self.sessions.insert(session);
+            }
+            Err(e) => self.notices.device(&launch.serial, format!("⚠️ Failed to start scrcpy: {}", e)),
+        }
+    }
+
+    pub(crate) fn show_launch_history_window(&mut self, ctx: &egui::Context) {
+        if !self.show_launch_history {
+            return;
+        }
+        let mut open = true;
+        let (mut rerun, mut clear) = (None, false);
+        let formats = self.formats();
+        egui::Window::new("🕘 Launch history").open(&mut open).default_size([620.0, 360.0]).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Keep last launches:");
+                if ui.add(egui::DragValue::new(&mut self.settings.launch_history_size).range(1..=1000)).changed() {
+                    let _ = self.settings.save();
+                }
+                if ui.add_enabled(!self.launches.launches.is_empty(), egui::Button::new("Clear")).clicked() {
+                    clear = true;
+                }
+            });
+            ui.separator();
+            if self.launches.launches.is_empty() {
+                ui.weak("Nothing launched yet.");
+                return;
+            }
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                egui::Grid::new("launch_history").num_columns(4).striped(true).show(ui, |ui| {
+                    for (i, launch) in self.launches.launches.iter().enumerate() {
+                        ui.label(formats.date_time(&launch.started_at));
+                        ui.label(&launch.device).on_hover_text(&launch.serial);
+                        // Only the latest launch on a device can still be running
+                        let latest = self.launches.launches[..i].iter().all(|l| l.serial != launch.serial);
+                        let outcome = match launch.outcome {
+                            Some(outcome) => outcome.label(),
+                            None if latest && self.sessions.is_running(&launch.serial) => "● running".to_string(),
+                            None => "unknown".to_string(),
+                        };
+                        ui.label(outcome);
+                        ui.horizontal(|ui| {
+                            let connected = self.devices.contains(&launch.serial);
+                            if ui.add_enabled(connected, egui::Button::new("▶ Run again")).on_disabled_hover_text("Device not connected").clicked() {
+                                rerun = Some(i);
+                            }
+                            let line = journal::command_line(&launch.binary, &launch.args);
+                            if ui.button("📋").on_hover_text("Copy the command line").clicked() {
+                                ui.ctx().copy_text(line.clone());
+                            }
+                            ui.add(egui::Label::new(egui::RichText::new(launch.args.join(" ")).monospace()).truncate()).on_hover_text(line);
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        if let Some(index) = rerun {
+            self.rerun_launch(index);
+        }
+        if clear {
+            self.launches.clear();
+        }
+        self.show_launch_history = open;
+    }
+}
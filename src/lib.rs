@@ -0,0 +1,12 @@
+//! The parts of the GUI that don't need egui: the adb queue, device
+//! listing, command lines and scrcpy sessions. Split out of the binary so the
+//! integration tests in `tests/` can drive them against fake tools.
+
+pub mod adb;
+pub mod cmdline;
+pub mod devices;
+pub mod handoff;
+pub mod job;
+pub mod journal;
+pub mod persist;
+pub mod session;
@@ -0,0 +1,174 @@
+use std::process::{Command, Stdio};
+
+/// Options and the scrcpy release that introduced them.
+const MIN_SCRCPY: [(&str, (u32, u32)); 8] = [
+    ("--audio-", (2, 0)),
+    ("--video-codec", (2, 0)),
+    ("--video-source", (2, 2)),
+    ("--camera-", (2, 2)),
+    ("--keyboard", (2, 4)),
+    ("--gamepad", (2, 7)),
+    ("--new-display", (3, 0)),
+    ("--start-app", (3, 0)),
+];
+
+/// A camera listed by `--list-camera-sizes`.
+#[derive(Debug, Clone, Default)]
+pub struct Camera {
+    pub id: String,
+    /// Facing and active size, e.g. `back, 4000x3000, fps=[15, 24, 30]`.
+    pub description: String,
+    pub sizes: Vec<String>,
+    pub fps: Vec<u32>,
+}
+
+/// What a device reports about its encoders, cameras and displays, via
+/// scrcpy's `--list-encoders`, `--list-camera-sizes` and `--list-displays`. Empty lists mean the query
+/// failed and the related checks are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    /// `(codec, encoder)` pairs.
+    pub video_encoders: Vec<(String, String)>,
+    pub audio_encoders: Vec<(String, String)>,
+    pub camera_sizes: Vec<String>,
+    pub cameras: Vec<Camera>,
+    /// `(display id, description)` pairs, e.g. `(0, "1080x2340")`.
+    pub displays: Vec<(u32, String)>,
+}
+
+fn scrcpy_output(binary: &str, serial: &str, flag: &str) -> String {
+    Command::new(binary)
+        .arg("--serial").arg(serial)
+        .arg(flag)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).to_string() + &String::from_utf8_lossy(&out.stderr))
+        .unwrap_or_default()
+}
+
+/// Value of `--name=value` in a line of scrcpy's listing, unquoted.
+fn listed_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    line.split_whitespace()
+        .find_map(|word| word.strip_prefix(name)?.strip_prefix('='))
+        .map(|v| v.trim_matches('\''))
+}
+
+pub fn fetch_capabilities(binary: &str, serial: &str) -> Capabilities {
+    let mut caps = Capabilities::default();
+    for line in scrcpy_output(binary, serial, "--list-encoders").lines() {
+        if let (Some(codec), Some(encoder)) = (listed_value(line, "--video-codec"), listed_value(line, "--video-encoder")) {
+            caps.video_encoders.push((codec.to_string(), encoder.to_string()));
+        }
+        if let (Some(codec), Some(encoder)) = (listed_value(line, "--audio-codec"), listed_value(line, "--audio-encoder")) {
+            caps.audio_encoders.push((codec.to_string(), encoder.to_string()));
+        }
+    }
+    let is_size = |size: &str| size.split_once('x').is_some_and(|(w, h)| w.parse::<u32>().is_ok() && h.parse::<u32>().is_ok());
+    let listing = scrcpy_output(binary, serial, "--list-camera-sizes");
+    caps.camera_sizes = listing
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("- "))
+        .filter(|size| is_size(size))
+        .map(str::to_string)
+        .collect();
+    // Sizes are listed indented under the camera they belong to
+    for line in listing.lines() {
+        if let Some(id) = listed_value(line, "--camera-id") {
+            let description = line.split_once('(').and_then(|(_, rest)| rest.rsplit_once(')')).map(|(d, _)| d.to_string()).unwrap_or_default();
+            let fps = description
+                .split_once("fps=[")
+                .and_then(|(_, rest)| rest.split_once(']'))
+                .map(|(list, _)| list.split(',').filter_map(|f| f.trim().parse().ok()).collect())
+                .unwrap_or_default();
+            caps.cameras.push(Camera { id: id.to_string(), description, sizes: Vec::new(), fps });
+        } else if let Some(size) = line.trim().strip_prefix("- ").filter(|s| is_size(s))
+            && let Some(camera) = caps.cameras.last_mut()
+        {
+            camera.sizes.push(size.to_string());
+        }
+    }
+    caps.displays = scrcpy_output(binary, serial, "--list-displays")
+        .lines()
+        .filter_map(|line| {
+            let id = listed_value(line, "--display-id")?.parse().ok()?;
+            let description = line.split_once('(').and_then(|(_, rest)| rest.split_once(')')).map(|(d, _)| d.to_string());
+            Some((id, description.unwrap_or_default()))
+        })
+        .collect();
+    caps
+}
+
+/// Value of an option given as `--name=value` or `--name value`.
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix(name) {
+        Some(value) if value.starts_with('=') => Some(&value[1..]),
+        Some("") => args.get(i + 1).map(String::as_str),
+        _ => None,
+    })
+}
+
+fn has_flag(args: &[String], prefix: &str) -> bool {
+    args.iter().any(|a| a.starts_with(prefix))
+}
+
+/// `(major, minor)` of a version such as `3.3.1`.
+pub fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// Options in `args` that cannot work on a device with `sdk` and `caps`, or
+/// with the installed `scrcpy` version.
+pub fn lint(args: &[String], sdk: Option<u32>, caps: &Capabilities, scrcpy: Option<(u32, u32)>) -> Vec<String> {
+    let mut problems = Vec::new();
+    if let Some(installed) = scrcpy {
+        for (prefix, (major, minor)) in MIN_SCRCPY {
+            if installed < (major, minor) && has_flag(args, prefix) {
+                problems.push(format!("{} needs scrcpy {}.{}, installed is {}.{}", prefix.trim_end_matches('-'), major, minor, installed.0, installed.1));
+            }
+        }
+    }
+    if let Some(sdk) = sdk {
+        let camera = option(args, "--video-source") == Some("camera") || has_flag(args, "--camera-");
+        if camera && sdk < 31 {
+            problems.push(format!("camera mirroring needs Android 12 (SDK 31), device has SDK {}", sdk));
+        }
+        let audio = has_flag(args, "--audio-") || has_flag(args, "--require-audio");
+        if audio && !has_flag(args, "--no-audio") && sdk < 30 {
+            problems.push(format!("audio forwarding needs Android 11 (SDK 30), device has SDK {}", sdk));
+        }
+        let uhid = ["--keyboard", "--mouse", "--gamepad"].iter().any(|o| option(args, o) == Some("uhid"));
+        if uhid && sdk < 28 {
+            problems.push(format!("UHID input needs Android 9 (SDK 28), device has SDK {}", sdk));
+        }
+    }
+    for (kind, encoders) in [("video", &caps.video_encoders), ("audio", &caps.audio_encoders)] {
+        if encoders.is_empty() {
+            continue;
+        }
+        if let Some(codec) = option(args, &format!("--{}-codec", kind))
+            && !encoders.iter().any(|(c, _)| c == codec)
+        {
+            problems.push(format!("no {} encoder for codec {}", kind, codec));
+        }
+        if let Some(encoder) = option(args, &format!("--{}-encoder", kind))
+            && !encoders.iter().any(|(_, e)| e == encoder)
+        {
+            problems.push(format!("{} encoder {} is not available", kind, encoder));
+        }
+    }
+    if !caps.displays.is_empty()
+        && let Some(id) = option(args, "--display-id")
+        && !caps.displays.iter().any(|(d, _)| d.to_string() == id)
+    {
+        problems.push(format!("display {} does not exist", id));
+    }
+    if !caps.camera_sizes.is_empty()
+        && let Some(size) = option(args, "--camera-size")
+        && !caps.camera_sizes.iter().any(|s| s == size)
+    {
+        problems.push(format!("camera size {} is not supported", size));
+    }
+    problems
+}
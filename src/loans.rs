@@ -0,0 +1,153 @@
+use crate::{ScrcpyGuiApp, persist};
+use chrono::{Local, NaiveDate};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Who has a lab device and until when.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Loan {
+    pub user: String,
+    pub due: Option<NaiveDate>,
+}
+
+impl Loan {
+    pub fn is_overdue(&self) -> bool {
+        self.due.is_some_and(|due| due < Local::now().date_naive())
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+impl ScrcpyGuiApp {
+    fn loan(&self, serial: &str) -> Loan {
+        self.settings.loans.get(serial).cloned().unwrap_or_default()
+    }
+
+    /// Current user and due date of a device, in the device panel.
+    pub(crate) fn show_loan_editor(&mut self, ui: &mut egui::Ui, serial: &str) {
+        let current = self.loan(serial);
+        let mut loan = current.clone();
+        if self.loan_due_input.0 != serial {
+            self.loan_due_input = (serial.to_string(), loan.due.map(|d| d.to_string()).unwrap_or_default());
+        }
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Loaned to:").strong());
+            ui.add(egui::TextEdit::singleline(&mut loan.user).hint_text("nobody").desired_width(120.0));
+            ui.label("due");
+            let due = ui.add(egui::TextEdit::singleline(&mut self.loan_due_input.1).hint_text("YYYY-MM-DD").desired_width(90.0));
+            if due.lost_focus() {
+                let text = self.loan_due_input.1.trim();
+                match NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+                    Ok(date) => loan.due = Some(date),
+                    Err(_) if text.is_empty() => loan.due = None,
+                    Err(_) => self.loan_due_input.1 = loan.due.map(|d| d.to_string()).unwrap_or_default(),
+                }
+            }
+            if loan.is_overdue() {
+                ui.colored_label(egui::Color32::RED, "⏰ overdue");
+            }
+            if loan != Loan::default() && ui.small_button("Returned").on_hover_text("Clear user and due date").clicked() {
+                loan = Loan::default();
+                self.loan_due_input.1.clear();
+            }
+        });
+        if loan != current {
+            if loan == Loan::default() {
+                self.settings.loans.remove(serial);
+            } else {
+                self.settings.loans.insert(serial.to_string(), loan);
+            }
+            let _ = self.settings.save();
+        }
+    }
+
+    pub(crate) fn show_loans_window(&mut self, ctx: &egui::Context) {
+        if !self.show_loans {
+            return;
+        }
+        let mut open = true;
+        let mut export = false;
+        let serials: BTreeSet<String> = self.devices.iter().chain(self.settings.loans.keys()).cloned().collect();
+        egui::Window::new("📋 Device loans")
+            .open(&mut open)
+            .default_size([460.0, 320.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.loan_filter).hint_text("Filter by user or device…"));
+                    ui.checkbox(&mut self.loans_overdue_only, "Overdue only");
+                    export = ui.button("💾 Export report").on_hover_text("Write device_report_<timestamp>.csv").clicked();
+                });
+                let query = self.loan_filter.to_lowercase();
+                let formats = self.formats();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("loans_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.label(egui::RichText::new("Device").strong());
+                        ui.label(egui::RichText::new("User").strong());
+                        ui.label(egui::RichText::new("Due").strong());
+                        ui.label(egui::RichText::new("Connected").strong());
+                        ui.end_row();
+                        for serial in &serials {
+                            let loan = self.loan(serial);
+                            let name = self.device_display_name(serial);
+                            let matches = query.is_empty() || [serial, &name, &loan.user].iter().any(|t| t.to_lowercase().contains(&query));
+                            if !matches || (self.loans_overdue_only && !loan.is_overdue()) {
+                                continue;
+                            }
+                            ui.label(&name).on_hover_text(serial);
+                            ui.label(if loan.user.is_empty() { "–" } else { loan.user.as_str() });
+                            let due = loan.due.map(|d| formats.date(d)).unwrap_or_else(|| "–".to_string());
+                            if loan.is_overdue() {
+                                ui.colored_label(egui::Color32::RED, format!("⏰ {}", due));
+                            } else {
+                                ui.label(due);
+                            }
+                            ui.label(if self.devices.contains(serial) { "yes" } else { "no" });
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        if export {
+            self.notices.global(match self.export_device_report(&serials) {
+                Ok(path) => format!("✅ Device report saved to {}", path.display()),
+                Err(e) => format!("⚠️ Failed to export device report: {}", e),
+            });
+        }
+        self.show_loans = open;
+    }
+
+    /// CSV of the devices with their properties, loan and notes.
+    fn export_device_report(&self, serials: &BTreeSet<String>) -> std::io::Result<PathBuf> {
+        let path = persist::data(format!("device_report_{}.csv", Local::now().format("%Y%m%d-%H%M%S")));
+        let mut csv = String::from("serial,model,manufacturer,android,connected,user,due,overdue,notes\n");
+        for serial in serials {
+            let info = self.device_props.get(serial).cloned().unwrap_or_default();
+            let loan = self.loan(serial);
+            let notes = self.settings.device_notes.get(serial).cloned().unwrap_or_default();
+            let row = [
+                serial.clone(),
+                info.model,
+                info.manufacturer,
+                info.android_version,
+                self.devices.contains(serial).to_string(),
+                loan.user.clone(),
+                loan.due.map(|d| d.to_string()).unwrap_or_default(),
+                loan.is_overdue().to_string(),
+                notes,
+            ];
+            csv.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+            csv.push('\n');
+        }
+        std::fs::write(&path, csv)?;
+        Ok(path)
+    }
+}
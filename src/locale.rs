@@ -0,0 +1,88 @@
+use chrono::{DateTime, Locale, NaiveDate, TimeZone};
+use std::fmt::Display;
+
+/// Locale named by `LC_ALL`, `LC_TIME` or `LANG` (e.g. `de_DE.UTF-8`),
+/// else `en_US`.
+fn system_locale() -> Locale {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|value| value.split(['.', '@']).next().and_then(|name| Locale::try_from(name).ok()))
+        .unwrap_or(Locale::en_US)
+}
+
+/// Dates, times and numbers for the history, library and stats views, in
+/// the locale from settings.
+pub struct LocaleFormat {
+    locale: Locale,
+    clock_24h: bool,
+}
+
+impl LocaleFormat {
+    /// `name` is a locale such as `fr_FR`; empty or unknown uses the system's.
+    pub fn new(name: &str, clock_24h: bool) -> Self {
+        let locale = Locale::try_from(name.trim()).unwrap_or_else(|_| system_locale());
+        Self { locale, clock_24h }
+    }
+
+    pub fn is_known(name: &str) -> bool {
+        name.trim().is_empty() || Locale::try_from(name.trim()).is_ok()
+    }
+
+    fn time_format(&self, seconds: bool) -> &'static str {
+        match (self.clock_24h, seconds) {
+            (true, false) => "%H:%M",
+            (true, true) => "%H:%M:%S",
+            (false, false) => "%I:%M %p",
+            (false, true) => "%I:%M:%S %p",
+        }
+    }
+
+    pub fn date(&self, date: NaiveDate) -> String {
+        date.format_localized("%x", self.locale).to_string()
+    }
+
+    pub fn date_time<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        at.format_localized(&format!("%x {}", self.time_format(false)), self.locale).to_string()
+    }
+
+    pub fn time<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        at.format_localized(self.time_format(true), self.locale).to_string()
+    }
+
+    /// `m:ss`, or `h:mm:ss` from an hour on.
+    pub fn duration(&self, secs: u64) -> String {
+        if secs >= 3600 {
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        } else {
+            format!("{}:{:02}", secs / 60, secs % 60)
+        }
+    }
+
+    /// Whole number with the locale's thousands separator.
+    pub fn count(&self, n: u64) -> String {
+        let separator = pure_rust_locales::locale_match!(self.locale => LC_NUMERIC::THOUSANDS_SEP);
+        let digits = n.to_string();
+        let mut out = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push_str(separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+
+    /// Size in MB (or GB from 1000 MB on) with one decimal.
+    pub fn bytes(&self, bytes: u64) -> String {
+        let point = pure_rust_locales::locale_match!(self.locale => LC_NUMERIC::DECIMAL_POINT);
+        let (value, unit) = if bytes >= 1_000_000_000 { (bytes as f64 / 1e9, "GB") } else { (bytes as f64 / 1e6, "MB") };
+        format!("{:.1} {}", value, unit).replacen('.', point, 1)
+    }
+}
@@ -0,0 +1,211 @@
+use crate::session::{self, SessionLog};
+use crate::{ScrcpyGuiApp, adb, logs};
+use eframe::egui;
+use std::process::{Child, Command, Stdio};
+
+/// Android log priorities, lowest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    #[default]
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl Level {
+    pub const ALL: [Level; 6] = [Level::Verbose, Level::Debug, Level::Info, Level::Warn, Level::Error, Level::Fatal];
+
+    fn from_letter(letter: &str) -> Option<Self> {
+        Some(match letter {
+            "V" => Level::Verbose,
+            "D" => Level::Debug,
+            "I" => Level::Info,
+            "W" => Level::Warn,
+            "E" => Level::Error,
+            "F" | "A" => Level::Fatal,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Verbose => "Verbose",
+            Level::Debug => "Debug",
+            Level::Info => "Info",
+            Level::Warn => "Warning",
+            Level::Error => "Error",
+            Level::Fatal => "Fatal",
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Level::Verbose | Level::Debug => visuals.weak_text_color(),
+            Level::Info => visuals.text_color(),
+            Level::Warn => egui::Color32::ORANGE,
+            Level::Error | Level::Fatal => egui::Color32::RED,
+        }
+    }
+}
+
+/// Level and tag of a `-v threadtime` line such as
+/// `10-14 12:34:56.789  1234  5678 I ActivityManager: Start proc`.
+fn parse(line: &str) -> Option<(Level, &str)> {
+    let mut fields = line.split_whitespace();
+    let level = Level::from_letter(fields.nth(4)?)?;
+    let tag = fields.next()?.trim_end_matches(':');
+    Some((level, tag))
+}
+
+/// `adb logcat` of one device, streamed into a buffer while the viewer is open.
+pub struct Logcat {
+    pub serial: String,
+    pub min_level: Level,
+    /// Matched against the tag and the whole line, case-insensitively.
+    pub search: String,
+    /// Lines frozen when the view was paused; streaming goes on meanwhile.
+    pub paused: Option<Vec<String>>,
+    /// Keeps the view scrolled to the newest line.
+    pub follow: bool,
+    log: SessionLog,
+    child: Child,
+}
+
+impl Logcat {
+    pub fn start(serial: &str) -> std::io::Result<Self> {
+        let mut child = Command::new(adb::program())
+            .args(["-s", serial, "logcat", "-v", "threadtime"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let log = SessionLog::default();
+        if let Some(stdout) = child.stdout.take() {
+            session::capture_output(stdout, log.clone(), None);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            session::capture_output(stderr, log.clone(), None);
+        }
+        Ok(Self { serial: serial.to_string(), min_level: Level::default(), search: String::new(), paused: None, follow: true, log, child })
+    }
+
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.log.lock().unwrap().iter().map(|l| l.text.clone()).collect()
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = match self.paused {
+            Some(_) => None,
+            None => Some(self.lines()),
+        };
+    }
+
+    pub fn clear(&mut self) {
+        self.log.lock().unwrap().clear();
+        if self.paused.is_some() {
+            self.paused = Some(Vec::new());
+        }
+    }
+
+    /// Lines passing the level filter and search, with their level. Lines
+    /// that don't parse (e.g. `--------- beginning of main`) only pass
+    /// without a level filter.
+    pub fn shown(&self) -> Vec<(Option<Level>, String)> {
+        let search = self.search.trim().to_lowercase();
+        let lines = match &self.paused {
+            Some(lines) => lines.clone(),
+            None => self.lines(),
+        };
+        lines
+            .into_iter()
+            .filter_map(|line| {
+                let parsed = parse(&line);
+                let level = parsed.map(|(level, _)| level);
+                if level.unwrap_or_default() < self.min_level {
+                    return None;
+                }
+                let tag_match = parsed.is_some_and(|(_, tag)| tag.to_lowercase().contains(&search));
+                (search.is_empty() || tag_match || line.to_lowercase().contains(&search)).then_some((level, line))
+            })
+            .collect()
+    }
+}
+
+impl Drop for Logcat {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn open_logcat(&mut self, serial: &str) {
+        if self.logcat.as_ref().is_some_and(|l| l.serial == serial) {
+            return;
+        }
+        match Logcat::start(serial) {
+            Ok(logcat) => self.logcat = Some(logcat),
+            Err(e) => self.notices.device(serial, format!("⚠️ Failed to start adb logcat: {}", e)),
+        }
+    }
+
+    /// Streaming logcat of one device with level filter, search, pause,
+    /// follow and export of the shown lines.
+    pub(crate) fn show_logcat_window(&mut self, ctx: &egui::Context) {
+        let Some(serial) = self.logcat.as_ref().map(|l| l.serial.clone()) else { return };
+        let name = self.device_display_name(&serial);
+        let logcat = self.logcat.as_mut().unwrap();
+        let running = logcat.is_running();
+        let shown = logcat.shown();
+        let mut open = true;
+        let mut export = false;
+        egui::Window::new(format!("📜 Logcat – {}", name)).open(&mut open).default_size([720.0, 420.0]).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("logcat_level").selected_text(logcat.min_level.label()).show_ui(ui, |ui| {
+                    for level in Level::ALL {
+                        ui.selectable_value(&mut logcat.min_level, level, level.label());
+                    }
+                });
+                ui.add(egui::TextEdit::singleline(&mut logcat.search).hint_text("Tag or text").desired_width(180.0));
+                let pause = if logcat.paused.is_some() { "▶ Resume" } else { "⏸ Pause" };
+                if ui.button(pause).clicked() {
+                    logcat.toggle_pause();
+                }
+                ui.checkbox(&mut logcat.follow, "Follow").on_hover_text("Keep scrolled to the newest line");
+                if ui.button("🗑 Clear").clicked() {
+                    logcat.clear();
+                }
+                export = ui.add_enabled(!shown.is_empty(), egui::Button::new("💾 Export")).on_hover_text("Saves the shown lines to the logs folder").clicked();
+            });
+            if !running {
+                ui.colored_label(egui::Color32::RED, "⚠️ adb logcat stopped (device disconnected?)");
+            }
+            ui.weak(format!("{} lines", shown.len()));
+            ui.separator();
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            egui::ScrollArea::both().auto_shrink(false).stick_to_bottom(logcat.follow).show_rows(ui, row_height, shown.len(), |ui, rows| {
+                for (level, line) in &shown[rows] {
+                    let color = level.map_or(ui.visuals().weak_text_color(), |l| l.color(ui.visuals()));
+                    ui.add(egui::Label::new(egui::RichText::new(line).monospace().color(color)).extend());
+                }
+            });
+        });
+        if export {
+            let lines: Vec<String> = shown.into_iter().map(|(_, line)| line).collect();
+            match logs::write_logcat(&serial, &lines) {
+                Ok(path) => self.notices.global(format!("✅ Logcat exported to {}", path.display())),
+                Err(e) => self.notices.global(format!("⚠️ Failed to export logcat: {}", e)),
+            }
+        }
+        if !open {
+            self.logcat = None;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub const LOGS_DIR: &str = "logs";
+
+/// Writes `lines` to a new `logs/<serial>_<timestamp>.<extension>`.
+fn write_lines(serial: &str, extension: &str, lines: &[String]) -> io::Result<PathBuf> {
+    let dir = crate::persist::data(LOGS_DIR);
+    fs::create_dir_all(&dir)?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    // Serials of wireless devices contain ':' which is not valid in Windows file names
+    let safe_serial: String = serial
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    let mut path = dir.join(format!("{}_{}.{}", safe_serial, stamp, extension));
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}_{}-{}.{}", safe_serial, stamp, n, extension));
+        n += 1;
+    }
+    let mut data = lines.join("\n");
+    data.push('\n');
+    crate::persist::write_atomic(&path, data)?;
+    Ok(path)
+}
+
+/// Writes one session log to `logs/<serial>_<timestamp>.log` and returns its path.
+pub fn write_session_log(serial: &str, lines: &[String]) -> io::Result<PathBuf> {
+    write_lines(serial, "log", lines)
+}
+
+/// Writes exported logcat lines to `logs/<serial>_<timestamp>.logcat`, which
+/// session log pruning leaves alone.
+pub fn write_logcat(serial: &str, lines: &[String]) -> io::Result<PathBuf> {
+    write_lines(serial, "logcat", lines)
+}
+
+/// Session logs in the logs directory, oldest first.
+pub fn session_logs() -> io::Result<Vec<PathBuf>> {
+    let mut logs: Vec<_> = match fs::read_dir(crate::persist::data(LOGS_DIR)) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+            .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    logs.sort();
+    Ok(logs.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Deletes the oldest session logs so that at most `keep` remain.
+pub fn prune_session_logs(keep: usize) -> io::Result<()> {
+    let logs = session_logs()?;
+    if logs.len() <= keep {
+        return Ok(());
+    }
+    for path in &logs[..logs.len() - keep] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -1,209 +1,2678 @@
+mod advanced;
+mod audio;
+mod battery;
+mod calibrate;
+mod changelog;
+mod config_source;
+mod confirm;
+mod console;
+mod device_state;
+mod diagnostics;
+mod embedded;
+mod embedded_window;
+mod extra_args;
+mod failures;
+mod files;
+mod geometry;
+mod groups;
+mod groups_window;
+mod history;
+mod launches;
+mod http_api;
+mod ime;
+mod input_echo;
+mod install;
+mod lint;
+mod loans;
+mod locale;
+mod logcat;
+mod logs;
+mod migrate;
+mod notices;
+mod operator;
+mod packages;
+mod palette;
+mod paths;
+mod pipeline;
+mod power;
+mod preflight;
+mod presets;
+mod profile_compare;
+mod recordings;
+mod rules;
+mod rules_window;
+mod safe_mode;
+mod screen_mask;
+mod screencap;
+mod secrets;
+mod settings;
+mod settings_window;
+mod stats;
+mod surveillance;
+mod tether;
+mod tools;
+mod touchpad;
+mod updater;
+mod usage;
+mod watchdog;
+mod wireless;
+
+use confirm::{Confirm, ConfirmedAction};
+use device_state::{DeviceLedger, Tweak};
+use devices::DevicePropsCache;
 use eframe::egui;
-use serde::Deserialize;
-use std::collections::HashMap;
+use job::Job;
+use packages::PackageCatalog;
+use palette::CommandPalette;
+use rules::RuleSet;
+use recordings::{ActiveRecording, Bookmark, AudioFormat, GroupRecording, RecordingKind, RecordingLibrary, VideoFormat};
+use scrpy_rust::{adb, cmdline, devices, handoff, job, journal, persist, session};
+use serde::{Deserialize, Serialize};
+use session::{Session, SessionManager, SessionState};
+use settings::{CloseBehavior, SETTINGS_PATH, Settings};
+use stats::UsageStats;
+use updater::Updater;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::process::{Child, Command, Stdio};
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Devices listed under "Recently used" in the device selector.
+const RECENT_DEVICES: usize = 5;
+const DEVICE_CONFIG_PATH: &str = "scrcpy_device_config.json";
+const DEFAULT_DEVICE_CONFIG_PATH: &str = "scrcpy_device_config.default.json";
 
-#[derive(Debug, Clone, Deserialize)]
+/// How long the device selection must stay unchanged before its config is applied.
+const SELECTION_DEBOUNCE: Duration = Duration::from_millis(300);
+/// A run that lasts this long counts as a completed startup for safe mode.
+const STARTUP_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct DeviceConfig {
     label: String,
     scrcpy_args: String,
+    /// Optional scrcpy executable for this device type (e.g. a legacy build).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scrcpy_binary: Option<String>,
+    /// Auxiliary relay processes run as part of the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pipeline: Option<pipeline::PipelineConfig>,
+    /// Recording path and container for this device type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recording: Option<recordings::RecordingTemplate>,
+    /// Overlay burned into this profile's session recordings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    watermark: Option<recordings::Watermark>,
+    /// Overrides the global "when a session ends" setting for this profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    close_behavior: Option<CloseBehavior>,
+    /// Rounded corners and camera cutout, masked in the embedded viewer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    screen_mask: Option<screen_mask::ScreenMask>,
 }
 
 struct ScrcpyGuiApp {
     devices: Vec<String>,
     selected_device: usize,
     last_selected_device: usize,
-    scrcpy_process: Option<Child>,
+    selection_changed_at: Option<Instant>, // pending debounced selection change
+    detection_target: Option<String>, // serial whose props are awaited for detection
+    sessions: SessionManager,
     device_type: String,
     crop_args: Option<String>,
     applied_config: String,
     last_refresh: Instant,
     device_config: HashMap<String, DeviceConfig>,
-    config_url: String,
-    auto_download_on_start: bool, // NEW: auto download config on start
-    status_message: String, // NEW: for visual feedback
+    device_props: DevicePropsCache,
+    settings: Settings,
+    scrcpy_binary_input: String, // per-device binary override being edited
+    notices: notices::Notices,
+    show_whats_new: bool,
+    show_version_history: bool,
+    updater: Updater,
+    recovery: Vec<RecoveryPrompt>, // corrupted files awaiting restore/reset
+    stats: UsageStats,
+    history: history::ConnectionHistory,
+    launches: launches::LaunchHistory,
+    show_launch_history: bool,
+    show_stats: bool,
+    show_settings: bool,
+    settings_category: settings_window::SettingsCategory,
+    /// Scale factor and size (in points) of the monitor showing the GUI,
+    /// taken as the one scrcpy windows will open on.
+    monitor: Option<(f32, egui::Vec2)>,
+    /// Output picked with "Save as…" for the next recorded session.
+    record_to: Option<PathBuf>,
+    presets: Vec<presets::Preset>,
+    capabilities: HashMap<String, lint::Capabilities>,
+    capabilities_job: Option<Job<(String, lint::Capabilities)>>,
+    /// Preset added to the next launch; empty for none.
+    selected_preset: String,
+    /// Device config entries being edited in Settings → Advanced, by model key.
+    config_draft: Option<Vec<(String, DeviceConfig)>>,
+    package_catalog: PackageCatalog,
+    show_app_picker: bool,
+    app_search: String,
+    icon_textures: HashMap<PathBuf, Option<egui::TextureHandle>>,
+    recordings: RecordingLibrary,
+    audio_recording: Option<ActiveRecording>,
+    show_recordings: bool,
+    group_recording: Option<GroupRecording>,
+    group_excluded: HashSet<String>, // devices unticked for the group recording
+    palette: CommandPalette,
+    ime_text: String,
+    ime_apk_path: String,
+    ime_job: Option<Job<Result<String, String>>>,
+    device_state: DeviceLedger,
+    device_state_jobs: Vec<Job<(String, Result<device_state::Outcome, String>)>>,
+    density_input: String,
+    screencaps: screencap::ScreencapService,
+    preview_texture: Option<(u64, egui::TextureHandle)>,
+    touch_pad: touchpad::TouchPad,
+    input_echo: input_echo::InputEcho,
+    /// Sessions started with a surveillance preset, by serial.
+    surveillance: BTreeMap<String, surveillance::Watch>,
+    /// Sessions relaunched when scrcpy exits unexpectedly, by serial.
+    keep_alive: BTreeMap<String, watchdog::KeepAlive>,
+    process_usage: usage::ProcessUsage,
+    /// Battery of the connected devices, refreshed on the device poll.
+    battery: HashMap<String, battery::Battery>,
+    battery_job: Option<Job<Vec<(String, battery::Battery)>>>,
+    calibration: Option<calibrate::Calibration>,
+    migration: Option<migrate::Migration>,
+    logcat: Option<logcat::Logcat>,
+    file_browser: Option<files::FileBrowser>,
+    installs: Vec<install::Install>,
+    /// Files dropped on the window being copied to a device.
+    pushes: Vec<(String, files::Transfer)>,
+    profile_compare: profile_compare::ProfileCompare,
+    show_profile_compare: bool,
+    http_api: Option<http_api::HttpApi>,
+    automation_failures: failures::AutomationFailures,
+    resolution_input: String,
+    wireless_address: String,
+    wireless_job: Option<Job<Result<String, String>>>,
+    /// `adb devices` in flight; the list is applied in `poll_device_list`.
+    devices_job: Option<Job<Vec<String>>>,
+    config_download_job: Option<Job<Result<(), String>>>,
+    /// Last seen modification time of a local directory config source.
+    config_watch: Option<SystemTime>,
+    /// Models whose entry was requested in per-model config mode.
+    config_models_requested: HashSet<String>,
+    config_token_input: String,
+    /// `--version` output per scrcpy binary, detected once; "…" while running.
+    scrcpy_versions: HashMap<String, String>,
+    version_jobs: Vec<Job<(String, String)>>,
+    known_flags: extra_args::KnownFlags,
+    /// A config download token is in the keychain.
+    config_token_stored: bool,
+    pairing_address: String,
+    pairing_code: String,
+    qr_pairing: Option<(wireless::QrPairing, egui::TextureHandle)>,
+    hotspot: wireless::Hotspot,
+    tool_downloads: HashMap<tools::ManagedTool, tools::ToolDownload>,
+    rules: RuleSet,
+    /// Newly connected devices the rules have not been evaluated for yet.
+    rule_pending: HashSet<String>,
+    safe_mode: Option<safe_mode::Reason>,
+    advanced: advanced::Advanced,
+    /// Why `advanced.toml` was ignored, if it was.
+    advanced_error: Option<String>,
+    /// Whether the startup marker was cleared after the first frames.
+    startup_finished: bool,
+    launched_at: Instant,
+    show_rules: bool,
+    groups: Vec<groups::DeviceGroup>,
+    show_groups: bool,
+    show_loans: bool,
+    loan_filter: String,
+    loans_overdue_only: bool,
+    /// Due date being typed for the device it belongs to.
+    loan_due_input: (String, String),
+    /// Bookmarks taken during the running recordings.
+    marks: Vec<Bookmark>,
+    mark_note: String,
+    chapters_job: Option<Job<Result<PathBuf, String>>>,
+    watermark_jobs: Vec<Job<Result<PathBuf, String>>>,
+    pending_confirm: Option<confirm::PendingConfirm>,
+    power_jobs: Vec<power::PowerJob>,
+    /// Stopped sessions and recordings waiting for scrcpy to quit before they
+    /// are finished.
+    stopping_sessions: Vec<Session>,
+    stopping_recordings: Vec<ActiveRecording>,
+    preflight: Option<preflight::Preflight>,
+    show_unlock: bool,
+    diagnostics_job: Option<Job<Result<PathBuf, String>>>,
+    /// Screenshot being saved from the command palette, with its device.
+    screenshot_job: Option<Job<(String, Result<PathBuf, String>)>>,
+    clipboard_text: String,
+    /// Mirroring rendered inside the GUI instead of scrcpy's own window.
+    embedded: Option<embedded_window::EmbeddedView>,
+    show_compare: bool,
+    compare: Option<embedded_window::CompareView>,
+    compare_pick: [usize; 2],
+    pin_input: String,
+    console: console::AdbConsole,
 }
 
-impl Default for ScrcpyGuiApp {
-    fn default() -> Self {
-        let config_url = "https://example.com/scrcpy_device_config.json".to_string();
-        // Load auto_download_on_start from settings.json
-        let settings_path = "settings.json";
-        let auto_download_on_start = match std::fs::read_to_string(settings_path) {
-            Ok(data) => {
-                serde_json::from_str::<serde_json::Value>(&data)
-                    .ok()
-                    .and_then(|v| v.get("auto_download_on_start").and_then(|b| b.as_bool()))
-                    .unwrap_or(true)
-            },
-            Err(_) => true,
+/// Everything reachable from the command palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PaletteAction {
+    StartSession,
+    StopSession,
+    StopAllSessions,
+    StartEmbedded,
+    OpenCompare,
+    RefreshDevices,
+    ConnectWireless,
+    TakeScreenshot,
+    PickStartApp,
+    ExportSessionLog,
+    RecordAudio,
+    StopAudio,
+    AddMark,
+    StartGroupRecording,
+    StopGroupRecording,
+    DownloadConfig,
+    OpenSettings,
+    OpenStats,
+    OpenRules,
+    OpenRecordings,
+    OpenVersionHistory,
+    OpenLaunchHistory,
+    ExportDiagnostics,
+}
+
+/// A persisted file that failed to parse at startup.
+struct RecoveryPrompt {
+    path: &'static str,
+    error: String,
+}
+
+impl ScrcpyGuiApp {
+    /// What `load` read, or `fallback` with a recovery prompt when the file
+    /// exists but can't be read; it is held read-only until then.
+    fn load_or_recover<T>(recovery: &mut Vec<RecoveryPrompt>, path: &'static str, load: impl FnOnce() -> Result<T, String>, fallback: impl FnOnce() -> T) -> T {
+        load().unwrap_or_else(|error| {
+            persist::hold(persist::data(path));
+            recovery.push(RecoveryPrompt { path, error });
+            fallback()
+        })
+    }
+
+    /// In safe mode the GUI starts with default settings and the bundled
+    /// device config, and skips the config download and automation rules.
+    fn new(safe_mode: Option<safe_mode::Reason>, migrated: Option<String>) -> Self {
+        let mut recovery = Vec::new();
+        let settings = if safe_mode.is_some() {
+            Settings { save_blocked: true, ..Settings::default() }
+        } else {
+            Settings::load().unwrap_or_else(|error| {
+                recovery.push(RecoveryPrompt { path: SETTINGS_PATH, error });
+                Settings { save_blocked: true, ..Settings::default() }
+            })
         };
-        let mut status_message = String::new();
-        if auto_download_on_start {
-            match ScrcpyGuiApp::download_and_update_device_config(&config_url, "scrcpy_device_config.json") {
-                Ok(_) => status_message = "Config downloaded successfully.".to_string(),
-                Err(e) => status_message = format!("Failed to download config: {}", e),
-            }
-        }
-        let devices = Self::get_adb_devices();
-        let config: HashMap<String, DeviceConfig> = {
-            let main_path = "scrcpy_device_config.json";
-            let default_path = "scrcpy_device_config.default.json";
-            let try_load = |path: &str| -> Option<HashMap<String, DeviceConfig>> {
-                match fs::read_to_string(path) {
-                    Ok(data) => match serde_json::from_str(&data) {
-                        Ok(cfg) => Some(cfg),
-                        Err(e) => {
-                            eprintln!("Failed to parse {}: {}", path, e);
-                            None
-                        }
-                    },
-                    Err(e) => {
-                        eprintln!("Failed to read {}: {}", path, e);
-                        None
-                    }
-                }
-            };
-            match try_load(main_path) {
-                Some(cfg) => cfg,
-                None => {
-                    status_message = "Loaded default config (fallback).".to_string();
-                    try_load(default_path).unwrap_or_else(HashMap::new)
-                }
-            }
+        let mut notices = notices::Notices::default();
+        let (config, config_error) = if safe_mode.is_some() {
+            (persist::read_json(persist::data(DEFAULT_DEVICE_CONFIG_PATH)).ok().flatten().unwrap_or_default(), None)
+        } else {
+            Self::load_device_config()
         };
+        if let Some(error) = config_error {
+            recovery.push(RecoveryPrompt { path: DEVICE_CONFIG_PATH, error });
+        }
+        if !persist::data(DEVICE_CONFIG_PATH).exists() || !recovery.is_empty() {
+            notices.global("Loaded default config (fallback).");
+        }
+        if let Some(migrated) = migrated {
+            notices.global(migrated);
+        }
+        let auto_download = settings.auto_download_on_start && safe_mode.is_none();
+        let (advanced, advanced_error) = if safe_mode.is_some() { Default::default() } else { advanced::Advanced::load() };
+        adb::configure(advanced.adb_short_timeout_secs, advanced.adb_long_timeout_secs, advanced.adb_max_parallel);
+        adb::set_program(&settings.adb_binary);
+        journal::configure(settings.command_journal);
+        let stats = Self::load_or_recover(&mut recovery, stats::STATS_PATH, UsageStats::load, UsageStats::default);
+        let recordings = Self::load_or_recover(&mut recovery, recordings::LIBRARY_PATH, RecordingLibrary::load, RecordingLibrary::default);
+        let rules = Self::load_or_recover(&mut recovery, rules::RULES_PATH, RuleSet::load, RuleSet::default);
+        let presets = Self::load_or_recover(&mut recovery, presets::PRESETS_PATH, presets::load, presets::builtin);
+        let history = Self::load_or_recover(&mut recovery, history::HISTORY_PATH, history::ConnectionHistory::load, Default::default);
+        let launches = Self::load_or_recover(&mut recovery, launches::LAUNCHES_PATH, launches::LaunchHistory::load, Default::default);
         let mut app = Self {
-            devices: devices.clone(),
+            devices: Vec::new(),
             selected_device: 0,
             last_selected_device: usize::MAX,
-            scrcpy_process: None,
+            selection_changed_at: None,
+            detection_target: None,
+            sessions: SessionManager::default(),
             device_type: String::new(),
             crop_args: None,
             applied_config: String::new(),
             last_refresh: Instant::now(),
             device_config: config,
-            config_url,
-            auto_download_on_start,
-            status_message,
+            device_props: DevicePropsCache::default(),
+            settings,
+            scrcpy_binary_input: String::new(),
+            notices,
+            show_whats_new: false,
+            show_version_history: false,
+            updater: Updater::default(),
+            recovery,
+            stats,
+            history,
+            launches,
+            show_launch_history: false,
+            show_stats: false,
+            show_settings: false,
+            settings_category: settings_window::SettingsCategory::General,
+            config_draft: None,
+            record_to: None,
+            monitor: None,
+            presets,
+            capabilities: HashMap::new(),
+            capabilities_job: None,
+            selected_preset: String::new(),
+            package_catalog: PackageCatalog::default(),
+            show_app_picker: false,
+            app_search: String::new(),
+            icon_textures: HashMap::new(),
+            recordings,
+            audio_recording: None,
+            show_recordings: false,
+            group_recording: None,
+            group_excluded: HashSet::new(),
+            palette: CommandPalette::default(),
+            ime_text: String::new(),
+            ime_apk_path: String::new(),
+            ime_job: None,
+            device_state: DeviceLedger::load(),
+            device_state_jobs: Vec::new(),
+            density_input: String::new(),
+            screencaps: screencap::ScreencapService::default(),
+            preview_texture: None,
+            touch_pad: touchpad::TouchPad::default(),
+            input_echo: input_echo::InputEcho::default(),
+            surveillance: BTreeMap::new(),
+            keep_alive: BTreeMap::new(),
+            process_usage: usage::ProcessUsage::default(),
+            battery: HashMap::new(),
+            battery_job: None,
+            calibration: None,
+            migration: None,
+            logcat: None,
+            file_browser: None,
+            installs: Vec::new(),
+            pushes: Vec::new(),
+            profile_compare: Default::default(),
+            show_profile_compare: false,
+            http_api: None,
+            automation_failures: failures::AutomationFailures::default(),
+            resolution_input: String::new(),
+            wireless_address: String::new(),
+            wireless_job: None,
+            devices_job: None,
+            config_download_job: None,
+            config_watch: None,
+            config_models_requested: HashSet::new(),
+            config_token_input: String::new(),
+            scrcpy_versions: HashMap::new(),
+            version_jobs: Vec::new(),
+            known_flags: extra_args::KnownFlags::default(),
+            config_token_stored: secrets::get(secrets::Secret::ConfigToken).is_ok_and(|t| t.is_some()),
+            pairing_address: String::new(),
+            pairing_code: String::new(),
+            qr_pairing: None,
+            hotspot: wireless::Hotspot::Computer,
+            tool_downloads: HashMap::new(),
+            rules,
+            rule_pending: HashSet::new(),
+            safe_mode,
+            advanced,
+            advanced_error,
+            startup_finished: false,
+            launched_at: Instant::now(),
+            show_rules: false,
+            groups: groups::load(),
+            show_groups: false,
+            show_loans: false,
+            loan_filter: String::new(),
+            loans_overdue_only: false,
+            loan_due_input: (String::new(), String::new()),
+            marks: Vec::new(),
+            mark_note: String::new(),
+            chapters_job: None,
+            watermark_jobs: Vec::new(),
+            pending_confirm: None,
+            power_jobs: Vec::new(),
+            stopping_sessions: Vec::new(),
+            stopping_recordings: Vec::new(),
+            preflight: None,
+            show_unlock: false,
+            diagnostics_job: None,
+            screenshot_job: None,
+            clipboard_text: String::new(),
+            embedded: None,
+            show_compare: false,
+            compare: None,
+            compare_pick: [0, 1],
+            pin_input: String::new(),
+            console: console::AdbConsole::default(),
         };
-        app.detect_and_apply_device_type();
+        if app.settings.check_for_updates {
+            app.updater.check();
+        }
+        app.show_whats_new = app.settings.last_seen_version.as_deref() != Some(changelog::APP_VERSION);
+        app.refresh_devices();
+        // In per-model mode entries are fetched as device properties arrive
+        if auto_download && !app.settings.config_per_model {
+            app.download_config();
+        }
+        app.restart_http_api();
+        let adopted = app.sessions.adopt_handoff();
+        if adopted > 0 {
+            app.notices.global(format!("✅ Re-attached to {} running scrcpy session(s).", adopted));
+        }
         app
     }
 }
 
 impl ScrcpyGuiApp {
-    fn get_adb_devices() -> Vec<String> {
-        let output = Command::new("adb")
-            .arg("devices")
+    fn get_scrcpy_version(binary: &str) -> String {
+        let output = Command::new(binary)
+            .arg("--version")
             .stdout(Stdio::piped())
             .output();
         if let Ok(output) = output {
             let text = String::from_utf8_lossy(&output.stdout);
-            text.lines()
-                .skip(1)
-                .filter_map(|line| {
-                    let parts: Vec<_> = line.split_whitespace().collect();
-                    if parts.len() == 2 && parts[1] == "device" {
-                        Some(parts[0].to_string())
-                    } else {
-                        None
-                    }
-                })
-                .collect()
+            // Extract version number (e.g., 'scrcpy 3.3.1 <...>' -> '3.3.1')
+            let first_line = text.lines().next().unwrap_or("Unknown");
+            let mut parts = first_line.split_whitespace();
+            if let (Some(_), Some(version)) = (parts.next(), parts.next()) {
+                version.to_string()
+            } else {
+                "Unknown".to_string()
+            }
+        } else {
+            "scrcpy not found".to_string()
+        }
+    }
+
+    fn detect_and_apply_device_type(&mut self) {
+        if self.devices.is_empty() { return; }
+        let serial = &self.devices[self.selected_device];
+        // Drop the detection still running for a device that is no longer selected
+        if let Some(prev) = self.detection_target.take()
+            && &prev != serial
+        {
+            self.device_props.cancel(&prev);
+        }
+        // Properties are fetched in the background; re-applied from poll_device_props once ready
+        let Some(info) = self.device_props.get(serial) else {
+            if !self.device_props.is_pending(serial) {
+                self.device_props.request_all(&self.devices);
+            }
+            self.detection_target = Some(serial.clone());
+            self.device_type = "Detecting…".to_string();
+            return;
+        };
+        let dev_type = info.model.clone();
+        // Avoid double borrow by splitting logic
+        let config = self.device_config.get(&dev_type).or_else(|| self.device_config.get("default")).cloned();
+        self.scrcpy_binary_input = self.settings.scrcpy_binary_override(serial).unwrap_or_default().to_string();
+        let serial = serial.clone();
+        if let Some(cfg) = config {
+            self.apply_crop(&cfg.scrcpy_args);
+            self.device_type = cfg.label;
+        } else {
+            self.apply_crop("");
+        }
+        if !self.capabilities.contains_key(&serial) {
+            self.request_capabilities(&serial);
+        }
+    }
+
+    /// Runs `<binary> --version` in the background unless it is known already.
+    fn request_scrcpy_version(&mut self, binary: &str) {
+        if self.scrcpy_versions.contains_key(binary) {
+            return;
+        }
+        self.scrcpy_versions.insert(binary.to_string(), "…".to_string());
+        let binary = binary.to_string();
+        self.version_jobs.push(Job::spawn(move || {
+            let version = Self::get_scrcpy_version(&binary);
+            (binary, version)
+        }));
+    }
+
+    fn poll_scrcpy_versions(&mut self) {
+        let mut detected = Vec::new();
+        self.version_jobs.retain(|job| match job.poll() {
+            Some(result) => {
+                detected.push(result);
+                false
+            }
+            None => true,
+        });
+        self.scrcpy_versions.extend(detected);
+    }
+
+    /// Lists encoders and cameras of `serial` in the background.
+    fn request_capabilities(&mut self, serial: &str) {
+        if self.capabilities_job.is_some() {
+            return;
+        }
+        let binary = self.scrcpy_binary_for(serial);
+        let serial = serial.to_string();
+        self.capabilities_job = Some(Job::spawn(move || {
+            let caps = lint::fetch_capabilities(&binary, &serial);
+            (serial, caps)
+        }));
+    }
+
+    fn suggested_window_size(&self, serial: &str, args: &[String]) -> Option<(u32, u32)> {
+        let screen = self.device_props.get(serial)?.screen_size?;
+        let (scale, _) = self.monitor?;
+        geometry::window_size(self.settings.window_sizing, geometry::video_size(screen, args), scale)
+    }
+
+    /// Launch options that cannot work on `serial`, judged from its Android
+    /// version and reported encoders and cameras.
+    fn profile_problems(&self, serial: &str) -> Vec<String> {
+        let sdk = self.device_props.get(serial).and_then(|i| i.sdk);
+        let caps = self.capabilities.get(serial).cloned().unwrap_or_default();
+        let scrcpy = self.scrcpy_versions.get(&self.scrcpy_binary_for(serial)).and_then(|v| lint::parse_version(v));
+        let mut problems = lint::lint(&self.build_scrcpy_args(serial), sdk, &caps, scrcpy);
+        problems.extend(self.unknown_extra_flags(serial).into_iter().map(|f| format!("{} is not a known scrcpy option", f)));
+        problems
+    }
+
+    /// Recording file for a session of `serial`: the device config's template,
+    /// else `<recordings dir>/<serial>_<timestamp>` in the format from settings.
+    pub(crate) fn record_path_for(&self, serial: &str) -> PathBuf {
+        let model = self.device_props.get(serial).map(|i| i.model.as_str()).unwrap_or_default();
+        let config = self.device_config_for(serial);
+        let label = config.map(|c| c.label.as_str()).unwrap_or_default();
+        config
+            .and_then(|c| c.recording.clone())
+            .unwrap_or_default()
+            .expand(&self.settings.recordings_folder(), serial, model, label, self.settings.video_format)
+    }
+
+    /// Device config entry matching a device's model, or the `default` entry.
+    fn device_config_for(&self, serial: &str) -> Option<&DeviceConfig> {
+        let model = self.device_props.get(serial).map(|i| i.model.as_str()).unwrap_or_default();
+        self.device_config.get(model).or_else(|| self.device_config.get("default"))
+    }
+
+    /// Label of the device config `serial` launches with, for usage stats.
+    fn profile_label(&self, serial: &str) -> String {
+        self.device_config_for(serial).map(|c| c.label.clone()).unwrap_or_default()
+    }
+
+    fn close_behavior_for(&self, serial: &str) -> CloseBehavior {
+        self.device_config_for(serial).and_then(|c| c.close_behavior).unwrap_or(self.settings.close_behavior)
+    }
+
+    /// Turns the screen off after a session ended, if the device is set up for it.
+    fn apply_close_behavior(&self, serial: &str) {
+        if self.close_behavior_for(serial) != CloseBehavior::ScreenOff {
+            return;
+        }
+        let serial = serial.to_string();
+        std::thread::spawn(move || {
+            let _ = adb::shell(&serial, "input keyevent KEYCODE_SLEEP");
+        });
+    }
+
+    fn config_scrcpy_binary(&self, serial: &str) -> Option<String> {
+        self.device_config_for(serial)
+            .and_then(|cfg| cfg.scrcpy_binary.clone())
+            .filter(|b| !b.trim().is_empty())
+    }
+
+    /// scrcpy executable for a device: per-serial setting, then device config, then
+    /// the default from settings, then PATH.
+    fn scrcpy_binary_for(&self, serial: &str) -> String {
+        self.settings
+            .scrcpy_binary_override(serial)
+            .map(str::to_string)
+            .or_else(|| self.config_scrcpy_binary(serial))
+            .or_else(|| Some(self.settings.default_scrcpy_binary.trim().to_string()).filter(|b| !b.is_empty()))
+            .unwrap_or_else(|| "scrcpy".to_string())
+    }
+
+    fn apply_crop(&mut self, crop: &str) {
+        self.applied_config = crop.to_string();
+        self.crop_args = parse_config_args(crop);
+    }
+
+    fn poll_device_props(&mut self) {
+        let updated = self.device_props.poll();
+        // Newly seen models fetch their entry in per-model config mode
+        if self.settings.config_per_model && self.settings.auto_download_on_start && self.safe_mode.is_none() && !updated.is_empty() {
+            self.download_config_entries(false);
+        }
+        // While a selection change is settling, the debounce applies the config instead
+        if self.selection_changed_at.is_none()
+            && let Some(serial) = self.devices.get(self.selected_device)
+            && updated.contains(serial)
+        {
+            self.detect_and_apply_device_type();
+        }
+    }
+
+    pub(crate) fn formats(&self) -> locale::LocaleFormat {
+        locale::LocaleFormat::new(&self.settings.locale, self.settings.clock_24h)
+    }
+
+    /// Human-readable name of a device for titles and labels: its alias,
+    /// else its model.
+    fn device_display_name(&self, serial: &str) -> String {
+        if let Some(alias) = self.settings.device_aliases.get(serial) {
+            return alias.clone();
+        }
+        match self.device_props.get(serial) {
+            Some(info) if !info.model.is_empty() => info.model.clone(),
+            _ => serial.to_string(),
+        }
+    }
+
+    fn window_title(&self, serial: &str) -> String {
+        let model = self.device_props.get(serial).map(|i| i.model.as_str()).unwrap_or_default();
+        self.settings
+            .window_title_template
+            .replace("{name}", &self.device_display_name(serial))
+            .replace("{model}", model)
+            .replace("{serial}", serial)
+            .replace("{label}", &self.device_type)
+    }
+
+    /// Arguments passed to scrcpy for the given device.
+    fn build_scrcpy_args(&self, serial: &str) -> Vec<String> {
+        self.build_scrcpy_args_with(serial, self.crop_args.as_deref())
+    }
+
+    /// Like `build_scrcpy_args`, with `config_args` in place of the applied config.
+    fn build_scrcpy_args_with(&self, serial: &str, config_args: Option<&str>) -> Vec<String> {
+        let mut args = vec!["--serial".to_string(), serial.to_string()];
+        let quality = self.settings.quality.get(serial).map(|q| q.args()).unwrap_or_default();
+        let config = cmdline::merge_args(config_args.unwrap_or_default(), &quality);
+        args.extend(config.split_whitespace().map(str::to_string));
+        // After the device config, so a preset option wins over the same option there
+        if let Some(preset) = self.presets.iter().find(|p| p.name == self.selected_preset) {
+            args.extend(preset.args.split_whitespace().map(str::to_string));
+        }
+        // Unbalanced extra arguments are left out; launch_session_with refuses them
+        args.extend(self.extra_args_for(serial).unwrap_or_default());
+        if let Some(choice) = self.settings.video_encoders.get(serial) {
+            let has = |name: &str| args.iter().any(|a| a.starts_with(name));
+            let mut video = Vec::new();
+            if !choice.codec.is_empty() && !has("--video-codec") {
+                video.push(format!("--video-codec={}", choice.codec));
+            }
+            if !choice.encoder.is_empty() && !has("--video-encoder") {
+                video.push(format!("--video-encoder={}", choice.encoder));
+            }
+            args.extend(video);
+        }
+        if let Some(camera) = self.settings.cameras.get(serial) {
+            // Camera options already in the config or preset win
+            let camera: Vec<String> = camera.args().into_iter().filter(|a| !args.iter().any(|b| b.split('=').next() == a.split('=').next())).collect();
+            args.extend(camera);
+        } else if let Some(display) = self.settings.virtual_displays.get(serial) {
+            if !args.iter().any(|a| a.starts_with("--new-display")) {
+                args.push(display.arg());
+            }
+        } else if let Some(id) = self.settings.display_ids.get(serial)
+            && !args.iter().any(|a| a.starts_with("--display-id"))
+        {
+            args.push(format!("--display-id={}", id));
+        }
+        for (on, flag) in [
+            (self.settings.turn_screen_off, "--turn-screen-off"),
+            (self.settings.stay_awake, "--stay-awake"),
+            (self.settings.show_touches, "--show-touches"),
+            (self.settings.view_only && !args.iter().any(|a| a == "-n"), "--no-control"),
+            (self.close_behavior_for(serial) == CloseBehavior::PowerOff, "--power-off-on-close"),
+        ] {
+            if on && !args.iter().any(|a| a == flag) {
+                args.push(flag.to_string());
+            }
+        }
+        let audio = audio::audio_args(self.settings.forward_audio, self.settings.audio_codec, self.settings.audio_source, &args);
+        args.extend(audio);
+        if let Some(package) = self.settings.start_apps.get(serial) {
+            args.push(format!("--start-app={}", package));
+        }
+        if let Some(pipeline) = self.device_config_for(serial).and_then(|c| c.pipeline.as_ref()) {
+            args.extend(pipeline.scrcpy_args());
+        }
+        // Before the size suggestion and the title template, so fixed values win
+        if let Some(placement) = self.settings.window_placements.get(serial) {
+            let placement: Vec<String> = placement
+                .args()
+                .into_iter()
+                .filter(|a| !args.iter().any(|b| b.split('=').next() == a.split('=').next()))
+                .collect();
+            args.extend(placement);
+        }
+        if let Some((width, height)) = self.suggested_window_size(serial, &args)
+            && !args.iter().any(|a| a.starts_with("--window-width") || a.starts_with("--window-height"))
+        {
+            args.push(format!("--window-width={}", width));
+            args.push(format!("--window-height={}", height));
+        }
+        // A title set by the device config wins over the template
+        if !self.settings.window_title_template.trim().is_empty()
+            && !args.iter().any(|a| a.starts_with("--window-title"))
+        {
+            args.push(format!("--window-title={}", self.window_title(serial)));
+        }
+        args
+    }
+
+    /// Starts mirroring the selected device, replacing a finished session of
+    /// it, after the pre-flight check when that is on.
+    fn start_session(&mut self) {
+        let serial = self.devices[self.selected_device].clone();
+        let segment_minutes = self.presets.iter().find(|p| p.name == self.selected_preset).and_then(|p| p.segment_minutes);
+        // Surveillance presets always record
+        let record = (self.settings.record_session || segment_minutes.is_some())
+            .then(|| self.record_to.take().unwrap_or_else(|| self.record_path_for(&serial)));
+        if self.settings.preflight_check {
+            self.start_preflight(serial, record, segment_minutes);
+        } else {
+            self.start_session_on(&serial, record, segment_minutes);
+        }
+    }
+
+    fn start_session_on(&mut self, serial: &str, record: Option<PathBuf>, segment_minutes: Option<u32>) {
+        let serial = serial.to_string();
+        self.end_session(&serial);
+        let problems = self.profile_problems(&serial);
+        if let Err(e) = self.launch_session(&serial, record) {
+            self.notices.device(&serial, format!("⚠️ {}", e));
+            return;
+        }
+        if let Some(minutes) = segment_minutes {
+            self.surveillance.insert(serial.clone(), surveillance::Watch::new(minutes));
+        }
+        if !problems.is_empty() {
+            self.notices.device(&serial, format!("⚠️ Started, but some options may fail on this device: {}", problems.join("; ")));
+        }
+    }
+
+    /// Starts `scrcpy --otg` for the selected USB device, or for the only USB
+    /// device if none is selected in adb (e.g. USB debugging is off).
+    fn start_otg_session(&mut self) {
+        let usb = self.selected_serial().filter(|s| !session::is_wireless_serial(s));
+        let key = session::otg_key(usb.as_deref());
+        self.end_session(&key);
+        let mut args = vec!["--otg".to_string()];
+        if let Some(serial) = &usb {
+            args.push(format!("--serial={}", serial));
+        }
+        let binary = self.scrcpy_binary_for(usb.as_deref().unwrap_or_default());
+        match Session::spawn(&binary, &key, args) {
+            Ok(session) => {
+                // No device config applies to OTG
+                self.stats.record_launch(&key, "");
+                self.sessions.insert(session);
+            }
+            Err(e) => self.notices.device(&key, format!("⚠️ Failed to start scrcpy: {}", e)),
+        }
+    }
+
+    /// Starts mirroring `serial` with the currently applied config, optionally
+    /// recording the mirrored video to `record`.
+    fn launch_session(&mut self, serial: &str, record: Option<PathBuf>) -> Result<(), String> {
+        self.launch_session_with(serial, record, self.crop_args.clone())
+    }
+
+    /// Like `launch_session`, with `config_args` in place of the applied config.
+    fn launch_session_with(&mut self, serial: &str, record: Option<PathBuf>, config_args: Option<String>) -> Result<(), String> {
+        self.extra_args_for(serial).map_err(|e| format!("Invalid extra arguments: {}", e))?;
+        let mut args = self.build_scrcpy_args_with(serial, config_args.as_deref());
+        if let Some(path) = &record {
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            args.push(format!("--record={}", path.display()));
+        }
+        let mut companions = match self.device_config_for(serial).and_then(|c| c.pipeline.as_ref()) {
+            Some(pipeline) => pipeline.companions(serial).map_err(|e| format!("Invalid pipeline command {}", e))?,
+            None => Vec::new(),
+        };
+        if self.settings.reverse_tethering.contains(serial) {
+            companions.push(tether::gnirehtet_companion(&self.settings.gnirehtet_binary, serial));
+        }
+        let mut session = Session::spawn(&self.scrcpy_binary_for(serial), serial, args)
+            .map_err(|e| format!("Failed to start scrcpy: {}", e))?;
+        for companion in companions {
+            let name = companion.name.clone();
+            if let Err(e) = session.attach_companion(companion) {
+                session.stop();
+                return Err(format!("Failed to start pipeline process {}: {}", name, e));
+            }
+        }
+        let profile = self.profile_label(serial);
+        self.stats.record_launch(serial, &profile);
+        let model = self.device_props.get(serial).map(|info| info.model.clone()).unwrap_or_default();
+        self.history.record_use(serial, &model);
+        self.launches.record(&session, &self.device_display_name(serial), self.settings.launch_history_size);
+        if let Some(mut old) = self.sessions.insert(session) {
+            old.stop();
+            self.stopping_sessions.push(old);
+        }
+        if self.settings.record_audio_with_session && self.audio_recording.is_none() {
+            self.start_audio_recording(serial, true);
+        }
+        Ok(())
+    }
+
+    /// Runs the automation rules for devices that connected since the last
+    /// check, once their properties are known so rules can match the model.
+    fn run_rules(&mut self) {
+        if self.safe_mode.is_some() {
+            self.rule_pending.clear();
+            return;
+        }
+        let ready: Vec<String> = self
+            .rule_pending
+            .iter()
+            .filter(|s| self.device_props.get(s).is_some() || !self.device_props.is_pending(s))
+            .cloned()
+            .collect();
+        for serial in ready {
+            self.rule_pending.remove(&serial);
+            let model = self.device_props.get(&serial).map(|i| i.model.clone()).unwrap_or_default();
+            let Some(rule) = self.rules.first_match(&serial, &model).cloned() else { continue };
+            if self.sessions.is_running(&serial) {
+                self.rules.log(&rule.name, &serial, "skipped: already mirroring");
+                continue;
+            }
+            if self.automation_failures.is_paused(&serial) {
+                self.rules.log(&rule.name, &serial, "skipped: automation paused");
+                continue;
+            }
+            let Some(index) = self.devices.iter().position(|d| d == &serial) else { continue };
+            self.selected_device = index;
+            self.last_selected_device = index;
+            self.selection_changed_at = None;
+            if rule.profile.trim().is_empty() {
+                self.detect_and_apply_device_type();
+            } else if let Some(cfg) = self.device_config.get(rule.profile.trim()).cloned() {
+                self.apply_crop(&cfg.scrcpy_args);
+                self.device_type = cfg.label;
+            } else {
+                self.rules.log(&rule.name, &serial, format!("failed: no profile named {}", rule.profile));
+                self.automation_failures.record(&serial, "Auto-start rule", format!("no profile named {}", rule.profile));
+                continue;
+            }
+            let record = rule.record.then(|| self.record_path_for(&serial));
+            let outcome = match self.launch_session(&serial, record) {
+                Ok(()) if rule.record => format!("started {} and recording", self.device_type),
+                Ok(()) => format!("started {}", self.device_type),
+                Err(e) => {
+                    self.automation_failures.record(&serial, "Auto-start rule", e.clone());
+                    format!("failed: {}", e)
+                }
+            };
+            self.rules.log(&rule.name, &serial, outcome);
+        }
+    }
+
+    fn restart_session(&mut self, serial: &str) {
+        let Some(old) = self.sessions.remove(serial) else { return };
+        match old.respawn() {
+            Ok(new_session) => {
+                let profile = self.profile_label(&old.serial);
+                self.stats.record_launch(&old.serial, &profile);
+                self.sessions.insert(new_session);
+            }
+            Err(e) => self.notices.device(serial, format!("⚠️ Failed to restart scrcpy: {}", e)),
+        }
+        self.finish_session(old);
+    }
+
+    /// Stops the session of a device and keeps its output in the logs directory.
+    fn end_session(&mut self, serial: &str) {
+        self.surveillance.remove(serial);
+        self.keep_alive.remove(serial);
+        if let Some(mut session) = self.sessions.remove(serial) {
+            if session.is_running() {
+                self.restore_device_state(serial);
+                if !session.is_otg() {
+                    self.apply_close_behavior(serial);
+                }
+            }
+            session.stop();
+            self.stopping_sessions.push(session);
+        }
+        if self.audio_recording.as_ref().is_some_and(|r| r.linked && r.session.serial == serial) {
+            self.stop_audio_recording();
+        }
+    }
+
+    /// Follow-up for sessions that ended by themselves, e.g. when the scrcpy
+    /// window was closed or the device went away.
+    fn sessions_stopped(&mut self, serials: Vec<String>) {
+        for serial in serials {
+            self.restore_device_state(&serial);
+            let Some(session) = self.sessions.get(&serial) else { continue };
+            let name = self.device_display_name(&serial);
+            let message = match session.state {
+                SessionState::Exited(Some(0)) => format!("scrcpy for {} was closed.", name),
+                SessionState::Exited(code) => {
+                    let code = code.map(|c| c.to_string()).unwrap_or_else(|| "unknown".to_string());
+                    let error = session.last_error().map(|e| format!(": {}", e)).unwrap_or_default();
+                    format!("⚠️ scrcpy for {} exited with code {}{}", name, code, error)
+                }
+                SessionState::Disconnected => format!("⚠️ {} disconnected, scrcpy stopped.", name),
+                SessionState::Running => continue,
+            };
+            if let Some(outcome) = launches::Outcome::from_state(&session.state) {
+                self.launches.finish(&serial, outcome);
+            }
+            let exited = !session.is_otg() && matches!(session.state, SessionState::Exited(_));
+            // Surveillance restarts the session right away
+            if !self.surveillance.contains_key(&serial) {
+                self.notices.device(&serial, message);
+                if exited {
+                    self.apply_close_behavior(&serial);
+                }
+            }
+        }
+    }
+
+    /// Stops every mirroring session, leaving recordings running.
+    fn end_all_sessions(&mut self) {
+        for serial in self.sessions.serials() {
+            self.end_session(&serial);
+        }
+    }
+
+    /// Captures device audio only, into its own file (`--no-video --no-playback`).
+    fn start_audio_recording(&mut self, serial: &str, linked: bool) {
+        let format = self.settings.audio_format;
+        let dir = self.settings.recordings_folder();
+        if let Err(e) = fs::create_dir_all(&dir) {
+            self.notices.global(format!("⚠️ Failed to create {}: {}", dir, e));
+            return;
+        }
+        let path = recordings::recording_path(&dir, serial, format.extension());
+        let args = vec![
+            "--serial".to_string(),
+            serial.to_string(),
+            "--no-video".to_string(),
+            "--no-playback".to_string(),
+            format!("--audio-codec={}", format.codec()),
+            format!("--record={}", path.display()),
+        ];
+        match Session::spawn(&self.scrcpy_binary_for(serial), serial, args) {
+            Ok(session) => {
+                self.audio_recording = Some(ActiveRecording {
+                    session,
+                    path,
+                    kind: RecordingKind::Audio,
+                    started_at: chrono::Local::now(),
+                    linked,
+                });
+            }
+            Err(e) => self.notices.device(serial, format!("⚠️ Failed to start audio recording: {}", e)),
+        }
+    }
+
+    fn stop_audio_recording(&mut self) {
+        if let Some(mut rec) = self.audio_recording.take() {
+            rec.session.stop();
+            self.stopping_recordings.push(rec);
+        }
+    }
+
+    /// Finishes the sessions and recordings whose scrcpy has quit since they
+    /// were stopped.
+    fn poll_stopping(&mut self) {
+        for mut session in std::mem::take(&mut self.stopping_sessions) {
+            if session.stop_finished() {
+                self.finish_session(session);
+            } else {
+                self.stopping_sessions.push(session);
+            }
+        }
+        for mut rec in std::mem::take(&mut self.stopping_recordings) {
+            if rec.session.stop_finished() {
+                self.finish_recording(rec);
+            } else {
+                self.stopping_recordings.push(rec);
+            }
+        }
+    }
+
+    /// Adds a finished recording to the library and the usage stats.
+    fn finish_recording(&mut self, rec: ActiveRecording) {
+        let serial = rec.session.serial.clone();
+        let duration = rec.session.duration().as_secs();
+        let bytes = self.recordings.add(rec.path.clone(), &serial, rec.kind, rec.started_at, duration, &self.marks);
+        self.stats.record_recording(&serial, bytes);
+        if bytes == 0 {
+            let last_line = rec.session.log_lines().pop().unwrap_or_default();
+            self.notices.device(&serial, format!("⚠️ Recording {} is empty. {}", rec.path.display(), last_line));
         } else {
-            vec![]
+            self.notices.device(&serial, format!("✅ Saved recording {}", rec.path.display()));
+        }
+    }
+
+    /// Records all ticked devices under one shared timestamp. Arguments are
+    /// prepared first so the processes are spawned back to back.
+    fn start_group_recording(&mut self) {
+        let dir = self.settings.recordings_folder();
+        if let Err(e) = fs::create_dir_all(&dir) {
+            self.notices.global(format!("⚠️ Failed to create {}: {}", dir, e));
+            return;
+        }
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let ext = self.settings.video_format.extension();
+        let plans: Vec<(String, String, PathBuf, Vec<String>)> = self
+            .devices
+            .iter()
+            .filter(|s| !self.group_excluded.contains(*s))
+            .map(|serial| {
+                let safe: String = serial.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+                let path = Path::new(&dir).join(format!("group_{}_{}.{}", stamp, safe, ext));
+                let mut args = vec!["--serial".to_string(), serial.clone(), "--no-playback".to_string()];
+                if let Some(extra) = self.device_config_for(serial).and_then(|cfg| parse_config_args(&cfg.scrcpy_args)) {
+                    args.extend(extra.split_whitespace().map(str::to_string));
+                }
+                args.push(format!("--record={}", path.display()));
+                (serial.clone(), self.scrcpy_binary_for(serial), path, args)
+            })
+            .collect();
+        if plans.is_empty() {
+            self.notices.global("⚠️ No devices selected for the group recording.".to_string());
+            return;
+        }
+        let started = Instant::now();
+        let started_at = chrono::Local::now();
+        let mut members = Vec::new();
+        let mut failures = Vec::new();
+        for (serial, binary, path, args) in plans {
+            match Session::spawn(&binary, &serial, args) {
+                Ok(session) => members.push(ActiveRecording {
+                    session,
+                    path,
+                    kind: RecordingKind::Video,
+                    started_at,
+                    linked: false,
+                }),
+                Err(e) => failures.push(format!("{}: {}", serial, e)),
+            }
+        }
+        if !failures.is_empty() {
+            self.notices.global(format!("⚠️ Some group recordings failed to start: {}", failures.join(", ")));
+        }
+        if !members.is_empty() {
+            self.group_recording = Some(GroupRecording { stamp, started_at, started, members });
+        }
+    }
+
+    fn stop_group_recording(&mut self) {
+        let Some(mut group) = self.group_recording.take() else { return };
+        for member in &mut group.members {
+            member.session.stop();
+        }
+        let dir = self.settings.recordings_folder();
+        let meta = group.write_metadata(&dir);
+        self.stopping_recordings.append(&mut group.members);
+        match meta {
+            Ok(path) => self.notices.global(format!("✅ Group recording saved, offsets in {}", path.display())),
+            Err(e) => self.notices.global(format!("⚠️ Failed to write group metadata: {}", e)),
+        }
+    }
+
+    fn show_recordings_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_recordings;
+        let mut remove = None;
+        let mut chapters = None;
+        egui::Window::new("🎞 Recordings")
+            .open(&mut open)
+            .default_size([460.0, 300.0])
+            .show(ctx, |ui| {
+                if self.recordings.entries.is_empty() {
+                    ui.label("No recordings yet.");
+                }
+                let formats = self.formats();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, entry) in self.recordings.entries.iter().enumerate().rev() {
+                        ui.horizontal(|ui| {
+                            ui.label(match entry.kind {
+                                RecordingKind::Video => "🎬",
+                                RecordingKind::Audio => "🎙",
+                            });
+                            ui.label(formats.date_time(&entry.started_at));
+                            ui.label(&entry.serial);
+                            ui.label(formats.duration(entry.duration_secs));
+                            ui.label(formats.bytes(entry.bytes));
+                            let missing = !entry.path.exists();
+                            let name = entry.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                            let label = if missing { egui::RichText::new(name).strikethrough() } else { egui::RichText::new(name) };
+                            if ui.link(label).on_hover_text(entry.path.display().to_string()).clicked() && !missing {
+                                ctx.open_url(egui::OpenUrl::new_tab(format!("file://{}", fs::canonicalize(&entry.path).unwrap_or(entry.path.clone()).display())));
+                            }
+                            if !entry.marks.is_empty() {
+                                let list: Vec<String> = entry.marks.iter().map(|(secs, note)| format!("{} {}", formats.duration(*secs), note)).collect();
+                                ui.label(format!("🔖 {}", entry.marks.len())).on_hover_text(list.join("\n"));
+                                if ui.add_enabled(self.chapters_job.is_none(), egui::Button::new("Chapters").small())
+                                    .on_hover_text("Write a copy with the bookmarks as chapters (needs ffmpeg)")
+                                    .clicked()
+                                {
+                                    chapters = Some(entry.clone());
+                                }
+                            }
+                            if ui.small_button("🗑").on_hover_text("Remove from library (keeps the file)").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    }
+                });
+            });
+        if let Some(i) = remove {
+            self.recordings.remove(i);
+        }
+        if let Some(entry) = chapters {
+            let out = entry.chapters_path();
+            if out.exists() {
+                let message = format!("{} already exists. Overwrite it?", out.display());
+                self.confirm_then(Confirm::OverwriteRecording, message, ConfirmedAction::EmbedChapters(entry));
+            } else {
+                self.embed_chapters(entry);
+            }
+        }
+        self.show_recordings = open;
+    }
+
+    pub(crate) fn embed_chapters(&mut self, entry: recordings::RecordingEntry) {
+        if self.chapters_job.is_none() {
+            self.chapters_job = Some(Job::spawn(move || entry.embed_chapters()));
+        }
+    }
+
+    /// Books a finished session into the usage stats and the logs directory.
+    fn finish_session(&mut self, session: Session) {
+        self.launches.finish(&session.serial, launches::Outcome::from_state(&session.state).unwrap_or(launches::Outcome::Stopped));
+        self.stats.record_session_end(&session.serial, session.duration());
+        if let Some(path) = session.record_path() {
+            let duration = session.duration();
+            let started_at = chrono::Local::now() - chrono::Duration::from_std(duration).unwrap_or_default();
+            if let Some(watermark) = self.device_config_for(&session.serial).and_then(|c| c.watermark.clone()) {
+                let (path, name) = (path.clone(), self.device_display_name(&session.serial));
+                self.watermark_jobs.push(Job::spawn(move || watermark.burn_in(&path, &name, started_at)));
+            }
+            let bytes = self.recordings.add(path, &session.serial, RecordingKind::Video, started_at, duration.as_secs(), &self.marks);
+            self.stats.record_recording(&session.serial, bytes);
+        }
+        let lines = session.log_lines();
+        if lines.is_empty() {
+            return;
+        }
+        if let Err(e) = logs::write_session_log(&session.serial, &lines) {
+            self.notices.device(&session.serial, format!("⚠️ Failed to save session log: {}", e));
+        }
+        let _ = logs::prune_session_logs(self.settings.session_log_retention);
+    }
+
+    /// Builds the diagnostic bundle in the background; see `diagnostics::export_bundle`.
+    fn export_diagnostics(&mut self) {
+        if self.diagnostics_job.is_some() {
+            return;
+        }
+        let settings = self.settings.clone();
+        let binary = match self.devices.get(self.selected_device) {
+            Some(serial) => self.scrcpy_binary_for(serial),
+            None => Some(settings.default_scrcpy_binary.trim().to_string()).filter(|b| !b.is_empty()).unwrap_or_else(|| "scrcpy".to_string()),
+        };
+        self.diagnostics_job = Some(Job::spawn(move || {
+            diagnostics::export_bundle(&settings, &binary, &[DEVICE_CONFIG_PATH, DEFAULT_DEVICE_CONFIG_PATH, presets::PRESETS_PATH, advanced::ADVANCED_PATH])
+        }));
+    }
+
+    /// Address for `adb connect`: the one typed in the Wireless section, or
+    /// the most recently used wireless device that is offline.
+    fn wireless_target(&self) -> Option<String> {
+        let typed = self.wireless_address.trim();
+        if !typed.is_empty() {
+            return Some(typed.to_string());
+        }
+        // mDNS serials can't be passed to adb connect
+        self.history
+            .recent(usize::MAX)
+            .iter()
+            .find(|used| used.serial.contains(':') && !used.serial.contains("._adb-tls-connect.") && !self.devices.contains(&used.serial))
+            .map(|used| used.serial.clone())
+    }
+
+    /// Saves a full-size screenshot of `serial` next to the recordings.
+    fn take_screenshot(&mut self, serial: &str) {
+        let dir = self.settings.recordings_folder();
+        let serial = serial.to_string();
+        self.screenshot_job = Some(Job::spawn(move || {
+            let path = recordings::recording_path(&dir, &serial, "png");
+            let result = fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir, e))
+                .and_then(|()| screencap::save_screenshot(&serial, &path))
+                .map(|()| path);
+            (serial, result)
+        }));
+    }
+
+    fn export_session_log(&mut self, serial: &str) {
+        let Some(session) = self.sessions.get(serial) else { return };
+        match logs::write_session_log(&session.serial, &session.log_lines()) {
+            Ok(path) => self.notices.device(serial, format!("✅ Session log exported to {}", path.display())),
+            Err(e) => self.notices.device(serial, format!("⚠️ Failed to export session log: {}", e)),
+        }
+        let _ = logs::prune_session_logs(self.settings.session_log_retention);
+    }
+
+    fn show_changelog_windows(&mut self, ctx: &egui::Context) {
+        if self.show_whats_new {
+            let releases = changelog::releases_since(self.settings.last_seen_version.as_deref());
+            let mut dismissed = false;
+            egui::Window::new(format!("What's new in v{}", changelog::APP_VERSION))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    for release in releases {
+                        ui.label(egui::RichText::new(format!("v{} – {}", release.version, release.date)).strong());
+                        for change in release.changes {
+                            ui.label(format!("• {}", change));
+                        }
+                        ui.add_space(4.0);
+                    }
+                    dismissed = ui.button("Got it").clicked();
+                });
+            if dismissed {
+                self.show_whats_new = false;
+                self.settings.last_seen_version = Some(changelog::APP_VERSION.to_string());
+                let _ = self.settings.save();
+            }
+        }
+        if self.show_version_history {
+            egui::Window::new("Version history")
+                .open(&mut self.show_version_history)
+                .default_height(300.0)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for release in changelog::CHANGELOG {
+                            ui.collapsing(format!("v{} – {}", release.version, release.date), |ui| {
+                                for change in release.changes {
+                                    ui.label(format!("• {}", change));
+                                }
+                            });
+                        }
+                    });
+                });
         }
     }
 
-    fn get_scrcpy_version() -> String {
-        let output = Command::new("scrcpy")
-            .arg("--version")
-            .stdout(Stdio::piped())
-            .output();
-        if let Ok(output) = output {
-            let text = String::from_utf8_lossy(&output.stdout);
-            // Extract version number (e.g., 'scrcpy 3.3.1 <...>' -> '3.3.1')
-            let first_line = text.lines().next().unwrap_or("Unknown");
-            let mut parts = first_line.split_whitespace();
-            if let (Some(_), Some(version)) = (parts.next(), parts.next()) {
-                version.to_string()
-            } else {
-                "Unknown".to_string()
+    fn show_stats_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_stats;
+        let mut reset = false;
+        egui::Window::new("📊 Usage stats")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Collected on this machine only, never sent anywhere.").weak());
+                ui.add_space(4.0);
+                let formats = self.formats();
+                let mut devices: Vec<_> = self.stats.devices.iter().collect();
+                devices.sort_by_key(|(_, d)| std::cmp::Reverse(d.mirroring_secs));
+                ui.label(egui::RichText::new("Mirroring time per device").strong());
+                let max_secs = devices.first().map(|(_, d)| d.mirroring_secs).unwrap_or(0);
+                for (serial, d) in &devices {
+                    let value = format!("{} · {} sessions", formats.duration(d.mirroring_secs), formats.count(d.sessions));
+                    bar_row(ui, serial, d.mirroring_secs as f32 / max_secs.max(1) as f32, &value);
+                }
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new("Most used profiles").strong());
+                let mut profiles: Vec<_> = self.stats.profiles.iter().collect();
+                profiles.sort_by_key(|(_, n)| std::cmp::Reverse(**n));
+                let max_launches = profiles.first().map(|(_, n)| **n).unwrap_or(0);
+                for (profile, n) in &profiles {
+                    bar_row(ui, profile, **n as f32 / max_launches.max(1) as f32, &format!("{} launches", formats.count(**n)));
+                }
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new("Recording volume").strong());
+                let max_bytes = devices.iter().map(|(_, d)| d.recorded_bytes).max().unwrap_or(0);
+                for (serial, d) in devices.iter().filter(|(_, d)| d.recorded_bytes > 0) {
+                    let value = formats.bytes(d.recorded_bytes);
+                    bar_row(ui, serial, d.recorded_bytes as f32 / max_bytes.max(1) as f32, &value);
+                }
+                ui.add_space(6.0);
+                reset = ui.button("Reset stats").clicked();
+            });
+        if reset {
+            self.stats.reset();
+        }
+        self.show_stats = open;
+    }
+
+    /// Texture for a cached app icon, decoded on first use.
+    fn icon_texture(&mut self, ctx: &egui::Context, path: &Path) -> Option<egui::TextureHandle> {
+        self.icon_textures
+            .entry(path.to_path_buf())
+            .or_insert_with(|| {
+                let img = image::open(path).ok()?.to_rgba8();
+                let size = [img.width() as usize, img.height() as usize];
+                let color = egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+                Some(ctx.load_texture(path.to_string_lossy(), color, egui::TextureOptions::LINEAR))
+            })
+            .clone()
+    }
+
+    fn show_app_picker_window(&mut self, ctx: &egui::Context) {
+        if !self.show_app_picker {
+            return;
+        }
+        let Some(serial) = self.devices.get(self.selected_device).cloned() else {
+            self.show_app_picker = false;
+            return;
+        };
+        self.package_catalog.request(&serial, false);
+        let entries = self.package_catalog.get(&serial).map(<[_]>::to_vec).unwrap_or_default();
+        let loading = self.package_catalog.is_loading(&serial);
+        let resolving = self.package_catalog.resolving(&serial);
+        let mut open = true;
+        // Rows on screen, the only ones whose label is worth an APK pull
+        let mut visible = Vec::new();
+        let mut picked = None;
+        let mut launch = false;
+        let mut refresh = false;
+        let running = self.sessions.is_running(&serial);
+        egui::Window::new(format!("Start app – {}", self.device_display_name(&serial)))
+            .open(&mut open)
+            .default_size([320.0, 400.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.app_search).hint_text("Search apps…"));
+                    refresh = ui.add_enabled(!loading, egui::Button::new("↻")).on_hover_text("Reload the package list from the device").clicked();
+                });
+                if ui.checkbox(&mut self.settings.user_apps_only, "User-installed apps only").on_hover_text("pm list packages -3").changed() {
+                    let _ = self.settings.save();
+                }
+                if loading || resolving > 0 {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(if loading { "Reading the package list…".to_string() } else { format!("Reading app labels… {} left", resolving) });
+                    });
+                }
+                let query = self.app_search.to_lowercase();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let user_apps_only = self.settings.user_apps_only;
+                    for entry in entries.iter().filter(|e| {
+                        // Entries from older caches are not classified yet and stay visible
+                        let kind_ok = !user_apps_only || e.third_party != Some(false);
+                        kind_ok && (query.is_empty() || e.package.to_lowercase().contains(&query) || e.display_name().to_lowercase().contains(&query))
+                    }) {
+                        let row = ui.horizontal(|ui| {
+                            match entry.icon.as_deref().and_then(|p| self.icon_texture(ctx, p)) {
+                                Some(tex) => { ui.add(egui::Image::new(&tex).fit_to_exact_size(egui::vec2(20.0, 20.0))); }
+                                None => { ui.add_sized([20.0, 20.0], egui::Label::new("📦")); }
+                            }
+                            let selected = self.settings.start_apps.get(&serial) == Some(&entry.package);
+                            if ui.selectable_label(selected, entry.display_name()).on_hover_text(&entry.package).clicked() {
+                                picked = Some(entry.package.clone());
+                            }
+                            if ui.add_enabled(!running, egui::Button::new("▶").small()).on_hover_text("Start mirroring with this app opened").clicked() {
+                                picked = Some(entry.package.clone());
+                                launch = true;
+                            }
+                            if entry.label.is_some() {
+                                ui.label(egui::RichText::new(&entry.package).weak().small());
+                            }
+                        });
+                        if !entry.resolved && ui.is_rect_visible(row.response.rect) {
+                            visible.push(entry.package.clone());
+                        }
+                    }
+                });
+            });
+        self.package_catalog.resolve(&serial, &visible);
+        if refresh {
+            self.package_catalog.request(&serial, true);
+        }
+        if let Some(package) = picked {
+            self.settings.start_apps.insert(serial, package);
+            let _ = self.settings.save();
+            open = false;
+            if launch {
+                self.start_session();
+            }
+        }
+        self.show_app_picker = open;
+    }
+
+    /// Start times of the recordings currently running.
+    fn recording_starts(&self) -> Vec<chrono::DateTime<chrono::Local>> {
+        let session = self
+            .sessions
+            .iter()
+            .filter(|s| s.is_running() && s.record_path().is_some())
+            .map(|s| chrono::Local::now() - chrono::Duration::from_std(s.duration()).unwrap_or_default());
+        self.audio_recording
+            .iter()
+            .map(|r| r.started_at)
+            .chain(self.group_recording.iter().map(|g| g.started_at))
+            .chain(session)
+            .collect()
+    }
+
+    /// Bookmarks the current moment in every running recording.
+    fn add_mark(&mut self) {
+        let Some(earliest) = self.recording_starts().into_iter().min() else { return };
+        // Marks from recordings that already finished are no longer needed
+        self.marks.retain(|m| m.at >= earliest);
+        let note = match self.mark_note.trim() {
+            "" => format!("Mark {}", self.marks.len() + 1),
+            note => note.to_string(),
+        };
+        self.mark_note.clear();
+        let at = chrono::Local::now();
+        self.notices.global(format!("✅ Bookmarked {} at {}", note, self.formats().time(&at)));
+        self.marks.push(Bookmark { at, note });
+    }
+
+    /// Every running scrcpy process: mirroring, audio and group recordings.
+    fn all_sessions(&self) -> impl Iterator<Item = &Session> {
+        self.sessions
+            .iter()
+            .chain(self.audio_recording.iter().map(|r| &r.session))
+            .chain(self.group_recording.iter().flat_map(|g| g.members.iter().map(|m| &m.session)))
+            .filter(|s| s.is_running())
+    }
+
+    fn show_bandwidth_meter(&self, ui: &mut egui::Ui) {
+        let wireless: Vec<(String, f32)> = self
+            .all_sessions()
+            .filter(|s| s.is_wireless())
+            .map(|s| (s.serial.clone(), s.estimated_bit_rate() as f32 / 1_000_000.0))
+            .collect();
+        if wireless.is_empty() {
+            return;
+        }
+        let total: f32 = wireless.iter().map(|(_, mbps)| mbps).sum();
+        // Wi-Fi rarely sustains more than ~80% of its nominal throughput
+        let over = total > self.settings.wifi_capacity_mbps * 0.8;
+        ui.horizontal(|ui| {
+            let color = if over { egui::Color32::ORANGE } else { egui::Color32::LIGHT_BLUE };
+            ui.colored_label(color, format!("📶 Wireless: ~{:.1} of {} Mbit/s", total, self.settings.wifi_capacity_mbps))
+                .on_hover_text(
+                    wireless
+                        .iter()
+                        .map(|(serial, mbps)| format!("{}: ~{:.1} Mbit/s", serial, mbps))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+            if over {
+                ui.colored_label(egui::Color32::ORANGE, "⚠ likely exceeds the Wi-Fi link, expect stutter");
+            }
+        });
+    }
+
+    /// Palette entries with their labels, restricted to what can run right now.
+    fn palette_actions(&self) -> Vec<(PaletteAction, &'static str)> {
+        let has_device = !self.devices.is_empty();
+        let selected = self.selected_serial().unwrap_or_default();
+        let mut actions = Vec::new();
+        if has_device && !self.sessions.is_running(&selected) {
+            actions.push((PaletteAction::StartSession, "Start scrcpy"));
+        }
+        if self.sessions.is_running(&selected) {
+            actions.push((PaletteAction::StopSession, "Stop scrcpy"));
+        }
+        if self.sessions.any_running() {
+            actions.push((PaletteAction::StopAllSessions, "Stop all sessions"));
+        }
+        if has_device && self.embedded.is_none() {
+            actions.push((PaletteAction::StartEmbedded, "Mirror inside the GUI"));
+        }
+        if self.devices.len() > 1 {
+            actions.push((PaletteAction::OpenCompare, "Compare two devices side by side"));
+        }
+        actions.push((PaletteAction::RefreshDevices, "Refresh device list"));
+        if self.wireless_job.is_none() && self.wireless_target().is_some() {
+            actions.push((PaletteAction::ConnectWireless, "Connect wireless device"));
+        }
+        if has_device && self.screenshot_job.is_none() {
+            actions.push((PaletteAction::TakeScreenshot, "Take screenshot"));
+        }
+        if has_device {
+            actions.push((PaletteAction::PickStartApp, "Pick start app"));
+        }
+        if self.sessions.get(&selected).is_some() {
+            actions.push((PaletteAction::ExportSessionLog, "Export session log"));
+        }
+        if self.audio_recording.is_some() {
+            actions.push((PaletteAction::StopAudio, "Stop audio recording"));
+        } else if has_device {
+            actions.push((PaletteAction::RecordAudio, "Record audio only"));
+        }
+        if !self.recording_starts().is_empty() {
+            actions.push((PaletteAction::AddMark, "Bookmark this moment in the recording"));
+        }
+        if self.group_recording.is_some() {
+            actions.push((PaletteAction::StopGroupRecording, "Stop group recording"));
+        } else if has_device {
+            actions.push((PaletteAction::StartGroupRecording, "Start synchronized group recording"));
+        }
+        actions.push((PaletteAction::DownloadConfig, "Download device config"));
+        actions.push((PaletteAction::OpenSettings, "Open settings"));
+        actions.push((PaletteAction::OpenStats, "Show usage stats"));
+        actions.push((PaletteAction::OpenRules, "Edit automation rules"));
+        actions.push((PaletteAction::OpenRecordings, "Show recordings"));
+        actions.push((PaletteAction::OpenVersionHistory, "Show version history"));
+        actions.push((PaletteAction::OpenLaunchHistory, "Show launch history"));
+        actions.push((PaletteAction::ExportDiagnostics, "Export diagnostic bundle"));
+        if self.locked() {
+            actions.retain(|(a, _)| {
+                matches!(
+                    a,
+                    PaletteAction::StartSession
+                        | PaletteAction::StopSession
+                        | PaletteAction::StopAllSessions
+                        | PaletteAction::RefreshDevices
+                )
+            });
+        }
+        actions
+    }
+
+    fn run_palette_action(&mut self, action: PaletteAction) {
+        match action {
+            PaletteAction::StartSession => self.start_session(),
+            PaletteAction::StopSession => {
+                if let Some(serial) = self.selected_serial() {
+                    self.end_session(&serial);
+                }
+            }
+            PaletteAction::StopAllSessions => self.end_all_sessions(),
+            PaletteAction::OpenCompare => self.show_compare = true,
+            PaletteAction::StartEmbedded => {
+                let serial = self.devices[self.selected_device].clone();
+                self.start_embedded(&serial);
+            }
+            PaletteAction::RefreshDevices => self.refresh_devices(),
+            PaletteAction::ConnectWireless => {
+                if let Some(address) = self.wireless_target() {
+                    self.wireless_job = Some(Job::spawn(move || wireless::connect(&address)));
+                }
+            }
+            PaletteAction::TakeScreenshot => {
+                let serial = self.devices[self.selected_device].clone();
+                self.take_screenshot(&serial);
+            }
+            PaletteAction::PickStartApp => {
+                self.app_search.clear();
+                self.show_app_picker = true;
+            }
+            PaletteAction::ExportSessionLog => {
+                if let Some(serial) = self.selected_serial() {
+                    self.export_session_log(&serial);
+                }
+            }
+            PaletteAction::RecordAudio => {
+                let serial = self.devices[self.selected_device].clone();
+                self.start_audio_recording(&serial, false);
+            }
+            PaletteAction::StopAudio => self.stop_audio_recording(),
+            PaletteAction::AddMark => self.add_mark(),
+            PaletteAction::StartGroupRecording => self.start_group_recording(),
+            PaletteAction::StopGroupRecording => self.stop_group_recording(),
+            PaletteAction::DownloadConfig => self.download_config(),
+            PaletteAction::OpenSettings => self.show_settings = true,
+            PaletteAction::OpenStats => self.show_stats = true,
+            PaletteAction::OpenRules => self.show_rules = true,
+            PaletteAction::ExportDiagnostics => self.export_diagnostics(),
+            PaletteAction::OpenRecordings => self.show_recordings = true,
+            PaletteAction::OpenVersionHistory => self.show_version_history = true,
+            PaletteAction::OpenLaunchHistory => self.show_launch_history = true,
+        }
+    }
+
+    fn show_command_palette(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::K)) {
+            self.palette.toggle();
+        }
+        if !self.palette.open {
+            return;
+        }
+        let mut matches: Vec<(i32, PaletteAction, &'static str)> = self
+            .palette_actions()
+            .into_iter()
+            .filter_map(|(action, label)| palette::fuzzy_score(&self.palette.query, label).map(|score| (score, action, label)))
+            .collect();
+        matches.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if escape {
+            self.palette.open = false;
+            return;
+        }
+        if down {
+            self.palette.selected += 1;
+        }
+        if up {
+            self.palette.selected = self.palette.selected.saturating_sub(1);
+        }
+        self.palette.selected = self.palette.selected.min(matches.len().saturating_sub(1));
+        let mut chosen = enter.then(|| matches.get(self.palette.selected).map(|m| m.1)).flatten();
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .fixed_size([360.0, 0.0])
+            .show(ctx, |ui| {
+                let edit = ui.add(egui::TextEdit::singleline(&mut self.palette.query).hint_text("Type a command…").desired_width(f32::INFINITY));
+                edit.request_focus();
+                if edit.changed() {
+                    self.palette.selected = 0;
+                }
+                ui.separator();
+                if matches.is_empty() {
+                    ui.label(egui::RichText::new("No matching command").weak());
+                }
+                for (i, (_, action, label)) in matches.iter().enumerate() {
+                    if ui.selectable_label(i == self.palette.selected, *label).clicked() {
+                        chosen = Some(*action);
+                    }
+                }
+            });
+        if let Some(action) = chosen {
+            self.palette.open = false;
+            match action {
+                PaletteAction::StopSession => self.confirm_then(Confirm::StopSession, "Stop mirroring?", ConfirmedAction::Run(action)),
+                PaletteAction::StopAllSessions => {
+                    self.confirm_then(Confirm::StopAllSessions, "Stop mirroring on all devices?", ConfirmedAction::Run(action))
+                }
+                PaletteAction::StopGroupRecording => {
+                    self.confirm_then(Confirm::StopAllSessions, "Stop recording on all devices?", ConfirmedAction::Run(action))
+                }
+                _ => self.run_palette_action(action),
+            }
+        }
+    }
+
+    fn show_text_input(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.devices.get(self.selected_device).cloned() else { return };
+        let busy = self.ime_job.is_some();
+        ui.collapsing("⌨ Type text on device", |ui| {
+            ui.label("Sends Unicode text (passwords, emoji, accents) through the ADBKeyBoard IME, then switches back to the previous keyboard.");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.ime_text).password(true).hint_text("Text to type"));
+                if ui.add_enabled(!busy && !self.ime_text.is_empty(), egui::Button::new("Send")).clicked() {
+                    let text = std::mem::take(&mut self.ime_text);
+                    self.input_echo.push(&serial, format!("⌨ Typed {} characters", text.chars().count()), None);
+                    self.ime_job = Some(Job::spawn(move || ime::type_text(&serial, &text).map(|_| "Text sent to device.".to_string())));
+                }
+                if busy {
+                    ui.spinner();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("ADBKeyBoard APK:");
+                ui.add(egui::TextEdit::singleline(&mut self.ime_apk_path).hint_text("path/to/ADBKeyboard.apk"));
+                if ui.add_enabled(!busy && !self.ime_apk_path.trim().is_empty(), egui::Button::new("Install")).clicked() {
+                    let serial = self.devices[self.selected_device].clone();
+                    let apk = self.ime_apk_path.trim().to_string();
+                    self.ime_job = Some(Job::spawn(move || ime::install_adb_keyboard(&serial, &apk).map(|_| "ADBKeyBoard installed.".to_string())));
+                }
+            });
+        });
+    }
+
+    fn show_adb_console(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.devices.get(self.selected_device).cloned() else { return };
+        ui.collapsing("🖥 Run command", |ui| {
+            ui.horizontal(|ui| {
+                let mut submit = false;
+                if self.console.free_form {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.console.input)
+                            .hint_text("adb shell command (↑/↓ for history)")
+                            .desired_width(260.0),
+                    );
+                    if response.has_focus() {
+                        let (up, down) = ui.input(|i| (i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown)));
+                        if up || down {
+                            self.console.recall(&self.settings.shell_history, up);
+                        }
+                    }
+                    submit = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if submit {
+                        response.request_focus();
+                    }
+                    ui.add_enabled_ui(!self.settings.shell_history.is_empty(), |ui| {
+                        ui.menu_button("🕘", |ui| {
+                            for command in self.settings.shell_history.iter().rev() {
+                                if ui.button(command).clicked() {
+                                    self.console.input = command.clone();
+                                    ui.close_menu();
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text("Recent commands");
+                    });
+                } else {
+                    let selected = console::TEMPLATES.get(self.console.template).map(|(l, _)| *l).unwrap_or_default();
+                    egui::ComboBox::from_id_salt("console_template").selected_text(selected).show_ui(ui, |ui| {
+                        for (i, (label, command)) in console::TEMPLATES.iter().enumerate() {
+                            ui.selectable_value(&mut self.console.template, i, *label).on_hover_text(*command);
+                        }
+                    });
+                }
+                let busy = self.console.is_busy();
+                let run = ui
+                    .add_enabled(!busy && !self.console.command().is_empty(), egui::Button::new("▶ Run"))
+                    .on_hover_text(format!("adb -s {} shell {}", serial, self.console.command()))
+                    .clicked();
+                if (run || submit) && !busy && !self.console.command().is_empty() {
+                    if self.console.free_form {
+                        console::remember(&mut self.settings.shell_history, &self.console.command());
+                        let _ = self.settings.save();
+                    }
+                    self.console.run(&serial);
+                }
+                if busy {
+                    ui.spinner();
+                }
+            });
+            ui.checkbox(&mut self.console.free_form, "I know what I'm doing (free-form commands)")
+                .on_hover_text("Any shell command runs on the device as-is, including destructive ones");
+            let entries: Vec<_> = self.console.entries.iter().filter(|e| e.serial == serial).collect();
+            let formats = self.formats();
+            let mut clear = false;
+            if !entries.is_empty() {
+                clear = ui.small_button("🗑 Clear output").clicked();
+                egui::ScrollArea::vertical().max_height(200.0).stick_to_bottom(true).show(ui, |ui| {
+                    for entry in entries {
+                        let color = if entry.ok { ui.visuals().strong_text_color() } else { egui::Color32::RED };
+                        ui.colored_label(color, format!("{} $ {}", formats.time(&entry.at), entry.command));
+                        ui.label(egui::RichText::new(&entry.output).monospace());
+                    }
+                });
+            }
+            if clear {
+                self.console.clear(&serial);
+            }
+        });
+    }
+
+    fn show_wireless(&mut self, ui: &mut egui::Ui) {
+        let busy = self.wireless_job.is_some();
+        ui.collapsing("📶 Wireless", |ui| {
+            self.show_pairing(ui);
+            ui.separator();
+            self.show_hotspot_helper(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.wireless_address).hint_text("192.168.1.20:5555").desired_width(160.0));
+                if ui.add_enabled(!busy && !self.wireless_address.trim().is_empty(), egui::Button::new("Connect"))
+                    .on_hover_text(format!("adb connect (port {} if none is given)", wireless::TCPIP_PORT))
+                    .clicked()
+                {
+                    let address = self.wireless_address.trim().to_string();
+                    self.wireless_job = Some(Job::spawn(move || wireless::connect(&address)));
+                }
+                if busy {
+                    ui.spinner();
+                }
+            });
+            let Some(serial) = self.selected_serial() else { return };
+            if session::is_wireless_serial(&serial) {
+                if ui.add_enabled(!busy, egui::Button::new(format!("Disconnect {}", serial))).clicked() {
+                    if self.sessions.is_running(&serial) {
+                        self.end_session(&serial);
+                    }
+                    self.wireless_job = Some(Job::spawn(move || wireless::disconnect(&serial)));
+                }
+            } else if ui.add_enabled(!busy, egui::Button::new("Switch to Wi-Fi"))
+                .on_hover_text(format!("adb tcpip {}, then connect to the device's Wi-Fi address; keep USB plugged in until it shows up", wireless::TCPIP_PORT))
+                .clicked()
+            {
+                self.wireless_job = Some(Job::spawn(move || wireless::switch_to_wifi(&serial)));
+            }
+        });
+    }
+
+    fn change_device_setting(&mut self, serial: &str, tweak: Tweak, value: &str) {
+        let (serial, value) = (serial.to_string(), value.to_string());
+        self.device_state_jobs.push(Job::spawn(move || {
+            let result = device_state::change(&serial, tweak, &value).map(|original| device_state::Outcome::Changed(tweak, original));
+            (serial, result)
+        }));
+    }
+
+    /// Puts back everything the app changed on `serial`, if anything.
+    fn restore_device_state(&mut self, serial: &str) {
+        if !self.device_state.is_dirty(serial) {
+            return;
+        }
+        let serial = serial.to_string();
+        let originals = self.device_state.originals(&serial);
+        self.device_state_jobs.push(Job::spawn(move || {
+            let result = device_state::restore(&serial, &originals).map(|_| device_state::Outcome::Restored);
+            (serial, result)
+        }));
+    }
+
+    fn poll_device_state(&mut self) {
+        let mut finished = Vec::new();
+        self.device_state_jobs.retain(|job| match job.poll() {
+            Some(result) => {
+                finished.push(result);
+                false
+            }
+            None => true,
+        });
+        for (serial, result) in finished {
+            match result {
+                Ok(device_state::Outcome::Changed(tweak, original)) => {
+                    self.device_state.record(&serial, tweak, original);
+                    self.notices.device(&serial, format!("✅ {} changed.", tweak.label()));
+                }
+                Ok(device_state::Outcome::Restored) => {
+                    self.device_state.clear(&serial);
+                    self.notices.device(&serial, "✅ Device defaults restored.");
+                }
+                Err(e) => self.notices.device(&serial, format!("⚠️ Failed to change device settings: {}", e)),
+            }
+        }
+    }
+
+    fn show_window_sizing(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        ui.horizontal(|ui| {
+            ui.label("Window size:");
+            let before = self.settings.window_sizing;
+            egui::ComboBox::from_id_salt("window_sizing").selected_text(before.label()).show_ui(ui, |ui| {
+                for sizing in geometry::WindowSizing::ALL {
+                    ui.selectable_value(&mut self.settings.window_sizing, sizing, sizing.label());
+                }
+            });
+            if self.settings.window_sizing != before {
+                let _ = self.settings.save();
+            }
+            let mut args = self.build_scrcpy_args(&serial);
+            args.retain(|a| !a.starts_with("--window-width") && !a.starts_with("--window-height"));
+            match (self.suggested_window_size(&serial, &args), self.monitor) {
+                (Some((width, height)), Some((scale, monitor))) => {
+                    ui.label(format!("{}×{} at {:.0}% scaling", width, height, scale * 100.0));
+                    if width as f32 > monitor.x || height as f32 > monitor.y {
+                        ui.colored_label(egui::Color32::ORANGE, "⚠ larger than the monitor");
+                    }
+                }
+                _ if self.settings.window_sizing != geometry::WindowSizing::Auto => {
+                    ui.label("(screen size unknown)");
+                }
+                _ => {}
+            }
+        });
+    }
+
+    /// Window flags and a fixed position and size for the selected device.
+    fn show_window_placement(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        let current = self.settings.window_placements.get(&serial).cloned().unwrap_or_default();
+        let mut placement = current.clone();
+        ui.collapsing("🪟 Window", |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut placement.fullscreen, "Fullscreen");
+                ui.checkbox(&mut placement.always_on_top, "Always on top");
+                ui.checkbox(&mut placement.borderless, "Borderless");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Title:");
+                ui.add(egui::TextEdit::singleline(&mut placement.title).hint_text("from the title template").desired_width(200.0));
+            });
+            ui.horizontal(|ui| {
+                for (label, value) in [("X", &mut placement.x), ("Y", &mut placement.y)] {
+                    let mut enabled = value.is_some();
+                    if ui.checkbox(&mut enabled, label).changed() {
+                        *value = enabled.then_some(0);
+                    }
+                    if let Some(v) = value {
+                        ui.add(egui::DragValue::new(v).range(-10_000..=10_000).suffix(" px"));
+                    }
+                }
+                for (label, value, default) in [("Width", &mut placement.width, 540), ("Height", &mut placement.height, 960)] {
+                    let mut enabled = value.is_some();
+                    if ui.checkbox(&mut enabled, label).changed() {
+                        *value = enabled.then_some(default);
+                    }
+                    if let Some(v) = value {
+                        ui.add(egui::DragValue::new(v).range(100..=10_000).suffix(" px"));
+                    }
+                }
+            });
+            ui.label("Width and height here replace the window size suggestion.");
+        });
+        if placement != current {
+            if placement == Default::default() {
+                self.settings.window_placements.remove(&serial);
+            } else {
+                self.settings.window_placements.insert(serial, placement);
+            }
+            let _ = self.settings.save();
+        }
+    }
+
+    /// Max size, bit rate and FPS for the selected device; unset values keep
+    /// what the device config says.
+    fn show_quality(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        let config = self.crop_args.clone().unwrap_or_default();
+        let default = |name: &str| cmdline::option_value(&config, name).unwrap_or_else(|| "scrcpy default".to_string());
+        let mut quality = self.settings.quality.get(&serial).cloned().unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.label("Quality:");
+            let mut enabled = quality.max_size.is_some();
+            if ui.checkbox(&mut enabled, "Max size").on_hover_text(format!("Config: {}", default("--max-size"))).changed() {
+                quality.max_size = enabled.then_some(1920);
+            }
+            if let Some(size) = &mut quality.max_size {
+                ui.add(egui::DragValue::new(size).range(240..=4096).speed(8).suffix(" px"));
+            }
+            let mut enabled = quality.video_bit_rate_mbps.is_some();
+            if ui.checkbox(&mut enabled, "Bit rate").on_hover_text(format!("Config: {}", default("--video-bit-rate"))).changed() {
+                quality.video_bit_rate_mbps = enabled.then_some(8.0);
+            }
+            if let Some(rate) = &mut quality.video_bit_rate_mbps {
+                ui.add(egui::Slider::new(rate, 0.5..=50.0).suffix(" Mbps").logarithmic(true));
+            }
+            let mut enabled = quality.max_fps.is_some();
+            if ui.checkbox(&mut enabled, "Max FPS").on_hover_text(format!("Config: {}", default("--max-fps"))).changed() {
+                quality.max_fps = enabled.then_some(60);
+            }
+            if let Some(fps) = &mut quality.max_fps {
+                ui.add(egui::DragValue::new(fps).range(1..=240).suffix(" fps"));
+            }
+            if ui.add_enabled(quality != Default::default(), egui::Button::new("Reset to config default")).clicked() {
+                quality = Default::default();
+            }
+            let calibrating = self.calibration.as_ref().is_some_and(|c| !c.is_done());
+            if ui
+                .add_enabled(!calibrating, egui::Button::new("🎯 Calibrate"))
+                .on_hover_text("Runs short test sessions at a few sizes and bit rates and suggests the smoothest")
+                .clicked()
+            {
+                self.start_calibration(&serial);
+            }
+        });
+        if self.settings.quality.get(&serial).cloned().unwrap_or_default() != quality {
+            if quality == Default::default() {
+                self.settings.quality.remove(&serial);
+            } else {
+                self.settings.quality.insert(serial, quality);
+            }
+            let _ = self.settings.save();
+        }
+    }
+
+    /// Video codec and encoder for the selected device, from `--list-encoders`.
+    fn show_video_encoder(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        let encoders = self.capabilities.get(&serial).map(|c| c.video_encoders.clone()).unwrap_or_default();
+        let mut choice = self.settings.video_encoders.get(&serial).cloned().unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.label("🎞 Video codec:");
+            let codec_label = if choice.codec.is_empty() { "default" } else { choice.codec.as_str() };
+            egui::ComboBox::from_id_salt("video_codec").selected_text(codec_label.to_string()).show_ui(ui, |ui| {
+                ui.selectable_value(&mut choice.codec, String::new(), "default");
+                for codec in ["h264", "h265", "av1"] {
+                    let available = encoders.is_empty() || encoders.iter().any(|(c, _)| c == codec);
+                    ui.add_enabled_ui(available, |ui| {
+                        ui.selectable_value(&mut choice.codec, codec.to_string(), codec)
+                            .on_disabled_hover_text("No encoder for this codec on the device");
+                    });
+                }
+            });
+            let codec = if choice.codec.is_empty() { "h264" } else { choice.codec.as_str() };
+            let for_codec: Vec<&String> = encoders.iter().filter(|(c, _)| c == codec).map(|(_, e)| e).collect();
+            if !for_codec.iter().any(|e| **e == choice.encoder) {
+                choice.encoder.clear();
+            }
+            ui.label("Encoder:");
+            let encoder_label = if choice.encoder.is_empty() { "(device default)" } else { choice.encoder.as_str() };
+            egui::ComboBox::from_id_salt("video_encoder").selected_text(encoder_label.to_string()).show_ui(ui, |ui| {
+                ui.selectable_value(&mut choice.encoder, String::new(), "(device default)");
+                for encoder in for_codec {
+                    ui.selectable_value(&mut choice.encoder, encoder.clone(), encoder);
+                }
+            });
+            if self.capabilities_job.is_some() {
+                ui.spinner();
+            } else if ui.small_button("↻").on_hover_text("List the device's encoders again").clicked() {
+                self.capabilities.remove(&serial);
+                self.request_capabilities(&serial);
+            }
+        });
+        if self.capabilities.contains_key(&serial) && self.settings.video_encoders.get(&serial).cloned().unwrap_or_default() != choice {
+            if choice == settings::VideoEncoderChoice::default() {
+                self.settings.video_encoders.remove(&serial);
+            } else {
+                self.settings.video_encoders.insert(serial, choice);
+            }
+            let _ = self.settings.save();
+        }
+    }
+
+    /// Display, camera or a new virtual display as the video source, with the
+    /// camera, size and FPS picked from the device's listing.
+    fn show_video_source(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        let cameras = self.capabilities.get(&serial).map(|c| c.cameras.clone()).unwrap_or_default();
+        let current = self.settings.cameras.get(&serial).cloned();
+        let mut choice = current.clone();
+        let current_display = self.settings.virtual_displays.get(&serial).cloned();
+        let mut display = current_display.clone();
+        ui.horizontal(|ui| {
+            ui.label("📷 Source:");
+            let before = if choice.is_some() { "camera" } else if display.is_some() { "virtual" } else { "display" };
+            let mut source = before;
+            ui.radio_value(&mut source, "display", "Display");
+            ui.radio_value(&mut source, "camera", "Camera").on_hover_text("Mirror a device camera (Android 12+), e.g. as a webcam");
+            ui.radio_value(&mut source, "virtual", "New display")
+                .on_hover_text("Create a secondary virtual display instead of mirroring the main one (scrcpy 3.0+); the start app opens there");
+            if source != before {
+                choice = (source == "camera").then(Default::default);
+                display = (source == "virtual").then(Default::default);
+            }
+        });
+        if let Some(display) = &mut display {
+            ui.horizontal(|ui| {
+                ui.label("Size:");
+                ui.add(egui::DragValue::new(&mut display.width).range(0..=7680)).on_hover_text("0 uses the main display's size");
+                ui.label("x");
+                ui.add(egui::DragValue::new(&mut display.height).range(0..=7680));
+                ui.label("DPI:");
+                ui.add(egui::DragValue::new(&mut display.dpi).range(0..=1000)).on_hover_text("0 uses the main display's density");
+            });
+        }
+        if display != current_display {
+            match display {
+                Some(display) => self.settings.virtual_displays.insert(serial.clone(), display),
+                None => self.settings.virtual_displays.remove(&serial),
+            };
+            let _ = self.settings.save();
+        }
+        if let Some(camera) = &mut choice {
+            ui.horizontal(|ui| {
+                ui.label("Camera:");
+                let label = |id: &str| match cameras.iter().find(|c| c.id == id) {
+                    Some(c) => format!("{} ({})", c.id, c.description),
+                    None if id.is_empty() => "default".to_string(),
+                    None => id.to_string(),
+                };
+                egui::ComboBox::from_id_salt("camera_id").selected_text(label(&camera.camera_id)).show_ui(ui, |ui| {
+                    ui.selectable_value(&mut camera.camera_id, String::new(), "default");
+                    for c in &cameras {
+                        ui.selectable_value(&mut camera.camera_id, c.id.clone(), label(&c.id));
+                    }
+                });
+                let listed = cameras.iter().find(|c| c.id == camera.camera_id);
+                let sizes = listed.map(|c| c.sizes.clone()).unwrap_or_default();
+                let rates = listed.map(|c| c.fps.clone()).unwrap_or_default();
+                ui.label("Size:");
+                let size_label = if camera.size.is_empty() { "default" } else { camera.size.as_str() };
+                egui::ComboBox::from_id_salt("camera_size").selected_text(size_label.to_string()).show_ui(ui, |ui| {
+                    ui.selectable_value(&mut camera.size, String::new(), "default");
+                    for size in sizes {
+                        ui.selectable_value(&mut camera.size, size.clone(), size);
+                    }
+                });
+                ui.label("FPS:");
+                let fps_label = camera.fps.map_or_else(|| "default".to_string(), |f| f.to_string());
+                egui::ComboBox::from_id_salt("camera_fps").selected_text(fps_label).show_ui(ui, |ui| {
+                    ui.selectable_value(&mut camera.fps, None, "default");
+                    for fps in rates {
+                        ui.selectable_value(&mut camera.fps, Some(fps), fps.to_string());
+                    }
+                });
+                if cameras.is_empty() && self.capabilities_job.is_none() && self.capabilities.contains_key(&serial) {
+                    ui.weak("No cameras reported");
+                }
+            });
+        }
+        if choice != current {
+            match choice {
+                Some(camera) => self.settings.cameras.insert(serial, camera),
+                None => self.settings.cameras.remove(&serial),
+            };
+            let _ = self.settings.save();
+        }
+    }
+
+    /// Display picker, shown when the device reports more than one display
+    /// or one was picked before.
+    fn show_display_picker(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        let displays = self.capabilities.get(&serial).map(|c| c.displays.clone()).unwrap_or_default();
+        let current = self.settings.display_ids.get(&serial).copied();
+        if displays.len() < 2 && current.is_none() {
+            return;
+        }
+        if self.settings.cameras.contains_key(&serial) || self.settings.virtual_displays.contains_key(&serial) {
+            return;
+        }
+        let mut choice = current;
+        let label = |id: u32| match displays.iter().find(|(d, _)| *d == id) {
+            Some((_, description)) if !description.is_empty() => format!("{} ({})", id, description),
+            _ => id.to_string(),
+        };
+        ui.horizontal(|ui| {
+            ui.label("🖥 Display:");
+            let selected = choice.map_or_else(|| "main".to_string(), label);
+            egui::ComboBox::from_id_salt("display_id").selected_text(selected).show_ui(ui, |ui| {
+                ui.selectable_value(&mut choice, None, "main");
+                for (id, _) in &displays {
+                    ui.selectable_value(&mut choice, Some(*id), label(*id));
+                }
+            });
+        });
+        if choice != current {
+            match choice {
+                Some(id) => self.settings.display_ids.insert(serial, id),
+                None => self.settings.display_ids.remove(&serial),
+            };
+            let _ = self.settings.save();
+        }
+    }
+
+    /// Nickname of a device, saved when the field loses focus.
+    fn show_device_alias(&mut self, ui: &mut egui::Ui, serial: &str) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Alias:").strong());
+            let mut alias = self.settings.device_aliases.get(serial).cloned().unwrap_or_default();
+            let edit = egui::TextEdit::singleline(&mut alias).hint_text("e.g. Pixel 7 – desk").desired_width(200.0);
+            let response = ui.add_enabled(!self.locked(), edit).on_hover_text("Shown instead of the serial in the device list and sessions");
+            if response.changed() {
+                if alias.trim().is_empty() {
+                    self.settings.device_aliases.remove(serial);
+                } else {
+                    self.settings.device_aliases.insert(serial.to_string(), alias);
+                }
+            }
+            if response.lost_focus() {
+                let _ = self.settings.save();
+            }
+        });
+    }
+
+    /// Notes for a device, saved when the field loses focus.
+    fn show_device_notes(&mut self, ui: &mut egui::Ui, serial: &str) {
+        ui.horizontal_top(|ui| {
+            ui.label(egui::RichText::new("Notes:").strong());
+            let mut notes = self.settings.device_notes.get(serial).cloned().unwrap_or_default();
+            let edit = egui::TextEdit::multiline(&mut notes).hint_text("Owner, known quirks…").desired_rows(2).desired_width(f32::INFINITY);
+            let response = ui.add_enabled(!self.locked(), edit);
+            if response.changed() {
+                if notes.trim().is_empty() {
+                    self.settings.device_notes.remove(serial);
+                } else {
+                    self.settings.device_notes.insert(serial.to_string(), notes);
+                }
+            }
+            if response.lost_focus() {
+                let _ = self.settings.save();
+            }
+        });
+    }
+
+    /// The scrcpy command line the next start runs, rebuilt every frame so it
+    /// follows the options.
+    fn show_command_preview(&mut self, ui: &mut egui::Ui, serial: &str) {
+        let mut args = self.build_scrcpy_args(serial);
+        if self.settings.record_session {
+            let path = self.record_to.clone().unwrap_or_else(|| self.record_path_for(serial));
+            args.push(format!("--record={}", path.display()));
+        }
+        let line = journal::command_line(&self.scrcpy_binary_for(serial), &args);
+        ui.collapsing("Command preview", |ui| {
+            ui.horizontal_top(|ui| {
+                if ui.small_button("📋 Copy").on_hover_text("Copy the command line to the clipboard").clicked() {
+                    ui.ctx().copy_text(line.clone());
+                    self.notices.global("✅ Command copied.");
+                }
+                ui.add(egui::Label::new(egui::RichText::new(&line).monospace()).wrap());
+            });
+        });
+    }
+
+    /// Demo flags kept on across runs.
+    fn show_screen_toggles(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = ui.checkbox(&mut self.settings.turn_screen_off, "Turn screen off").on_hover_text("--turn-screen-off: the device screen stays dark while mirroring").changed();
+            changed |= ui.checkbox(&mut self.settings.stay_awake, "Stay awake").on_hover_text("--stay-awake: keep the device awake while plugged in").changed();
+            changed |= ui.checkbox(&mut self.settings.show_touches, "Show touches").on_hover_text("--show-touches: show taps on the device screen").changed();
+            changed |= ui
+                .checkbox(&mut self.settings.echo_input, "Echo input")
+                .on_hover_text("Show taps and keys sent from this window (touch pad, embedded view, text input) on screen for the audience")
+                .changed();
+            changed |= ui.checkbox(&mut self.settings.view_only, "👁 View only").on_hover_text("--no-control: nobody can interact with the device through the mirror").changed();
+            if changed {
+                let _ = self.settings.save();
+            }
+        });
+    }
+
+    /// Audio forwarding flags added to the launch command.
+    fn show_audio_options(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = ui.checkbox(&mut self.settings.forward_audio, "🔊 Forward audio")
+                .on_hover_text("Unchecked launches with --no-audio")
+                .changed();
+            ui.add_enabled_ui(self.settings.forward_audio, |ui| {
+                egui::ComboBox::from_id_salt("audio_codec")
+                    .selected_text(self.settings.audio_codec.name())
+                    .show_ui(ui, |ui| {
+                        for codec in audio::AudioCodec::ALL {
+                            changed |= ui.selectable_value(&mut self.settings.audio_codec, codec, codec.name()).changed();
+                        }
+                    });
+                egui::ComboBox::from_id_salt("audio_source")
+                    .selected_text(self.settings.audio_source.label())
+                    .show_ui(ui, |ui| {
+                        for source in audio::AudioSource::ALL {
+                            changed |= ui.selectable_value(&mut self.settings.audio_source, source, source.label()).changed();
+                        }
+                    });
+            });
+            if changed {
+                let _ = self.settings.save();
+            }
+        });
+    }
+
+    /// "Record session" checkbox, container and output path for `--record`.
+    fn show_record_options(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = ui.checkbox(&mut self.settings.record_session, "⏺ Record session").changed();
+            egui::ComboBox::from_id_salt("video_format")
+                .selected_text(self.settings.video_format.extension())
+                .show_ui(ui, |ui| {
+                    for format in VideoFormat::ALL {
+                        changed |= ui.selectable_value(&mut self.settings.video_format, format, format.extension()).changed();
+                    }
+                });
+            if changed {
+                let _ = self.settings.save();
+            }
+            if !self.settings.record_session {
+                return;
+            }
+            let serial = self.selected_serial().unwrap_or_default();
+            if ui.button("📁 Save as…").clicked() {
+                let format = self.settings.video_format;
+                let suggested = self.record_path_for(&serial);
+                let picked = rfd::FileDialog::new()
+                    .add_filter(format.extension(), &[format.extension()])
+                    .set_directory(suggested.parent().unwrap_or(Path::new(".")))
+                    .set_file_name(suggested.file_name().and_then(|n| n.to_str()).unwrap_or_default())
+                    .save_file();
+                if let Some(mut path) = picked {
+                    match path.extension().and_then(|e| e.to_str()).and_then(VideoFormat::from_extension) {
+                        Some(format) if format != self.settings.video_format => {
+                            self.settings.video_format = format;
+                            let _ = self.settings.save();
+                        }
+                        Some(_) => {}
+                        None => {
+                            path.set_extension(format.extension());
+                        }
+                    }
+                    self.record_to = Some(path);
+                }
+            }
+            match &self.record_to {
+                Some(path) => {
+                    ui.label(path.display().to_string());
+                    if ui.small_button("Clear").clicked() {
+                        self.record_to = None;
+                    }
+                }
+                None => {
+                    let dir = self.record_path_for(&serial).parent().map(|d| d.display().to_string()).unwrap_or_default();
+                    ui.label(format!("to {}", dir)).on_hover_text("Timestamped file name per device; the device config can set its own path");
+                }
+            }
+        });
+    }
+
+    /// Periodic screenshot of the selected device from the shared screencap service.
+    fn show_preview(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        ui.collapsing("🖼 Preview", |ui| {
+            self.screencaps.request(&serial, Duration::from_secs(5));
+            if let Some(capture) = self.screencaps.latest(&serial) {
+                let image = || egui::ColorImage::from_rgba_unmultiplied([capture.width, capture.height], &capture.rgba);
+                match &mut self.preview_texture {
+                    Some((seq, _)) if *seq == capture.seq => {}
+                    Some((seq, texture)) => {
+                        texture.set(image(), egui::TextureOptions::LINEAR);
+                        *seq = capture.seq;
+                    }
+                    None => self.preview_texture = Some((capture.seq, ui.ctx().load_texture("device_preview", image(), egui::TextureOptions::LINEAR))),
+                }
+                if let Some((_, texture)) = &self.preview_texture {
+                    let size = texture.size_vec2();
+                    ui.add(egui::Image::new(texture).fit_to_exact_size(size * (240.0 / size.y.max(size.x))));
+                }
+                ui.label(format!("Taken {}s ago", capture.at.elapsed().as_secs()));
+            } else if let Some(e) = self.screencaps.error(&serial) {
+                ui.colored_label(egui::Color32::RED, format!("Screenshot failed: {}", e));
+            } else {
+                ui.spinner();
+            }
+        });
+    }
+
+    /// Device settings the app changes and restores when mirroring ends.
+    fn show_device_settings(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        let busy = !self.device_state_jobs.is_empty();
+        ui.collapsing("🛠 Device settings", |ui| {
+            ui.label("Changes are undone when the session ends, or with \"Restore device defaults\".");
+            ui.add_enabled_ui(!busy, |ui| {
+                ui.horizontal(|ui| {
+                    for (tweak, on) in [(Tweak::StayAwake, "7"), (Tweak::ShowTouches, "1")] {
+                        if ui.button(format!("{} on", tweak.label())).clicked() {
+                            self.change_device_setting(&serial, tweak, on);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.density_input).hint_text("dpi, e.g. 320").desired_width(90.0));
+                    if ui.button("Set density").clicked() && self.density_input.trim().parse::<u32>().is_ok() {
+                        let value = self.density_input.trim().to_string();
+                        self.change_device_setting(&serial, Tweak::Density, &value);
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut self.resolution_input).hint_text("e.g. 720x1280").desired_width(90.0));
+                    let valid = self.resolution_input.trim().split_once('x').is_some_and(|(w, h)| w.parse::<u32>().is_ok() && h.parse::<u32>().is_ok());
+                    if ui.button("Set resolution").clicked() && valid {
+                        let value = self.resolution_input.trim().to_string();
+                        self.change_device_setting(&serial, Tweak::Resolution, &value);
+                    }
+                });
+            });
+            let originals = self.device_state.originals(&serial);
+            if originals.is_empty() {
+                ui.label("Nothing changed on this device.");
+                return;
+            }
+            for (tweak, value) in &originals {
+                ui.label(format!("• {} (was {})", tweak.label(), value));
+            }
+            if ui.add_enabled(!busy, egui::Button::new("↺ Restore device defaults")).clicked() {
+                self.restore_device_state(&serial);
+            }
+        });
+    }
+
+    /// Android 11+ "Wireless debugging" pairing, by code or by QR code.
+    fn show_pairing(&mut self, ui: &mut egui::Ui) {
+        let busy = self.wireless_job.is_some();
+        ui.label("Pair a new device (Android 11+, Developer options → Wireless debugging):");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.pairing_address).hint_text("IP:port for pairing").desired_width(160.0));
+            ui.add(egui::TextEdit::singleline(&mut self.pairing_code).hint_text("6-digit code").desired_width(80.0));
+            let ready = !self.pairing_address.trim().is_empty() && self.pairing_code.trim().len() == 6;
+            if ui.add_enabled(!busy && ready, egui::Button::new("Pair")).on_hover_text("Pair device with pairing code").clicked() {
+                let address = self.pairing_address.trim().to_string();
+                let code = std::mem::take(&mut self.pairing_code);
+                self.wireless_job = Some(Job::spawn(move || wireless::pair_and_connect(&address, &code)));
+            }
+        });
+        if let Some((pairing, texture)) = &self.qr_pairing {
+            ui.label("On the phone, tap \"Pair device with QR code\" and scan:");
+            ui.add(egui::Image::new(texture).fit_to_exact_size(egui::vec2(200.0, 200.0)));
+            if ui.button("Cancel").clicked() {
+                pairing.cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        } else if ui.add_enabled(!busy, egui::Button::new("Show pairing QR code")).clicked() {
+            let pairing = wireless::QrPairing::new();
+            if let Some((width, dark)) = pairing.modules() {
+                // Two-module quiet zone around the code
+                let size = width + 4;
+                let mut pixels = vec![egui::Color32::WHITE; size * size];
+                for (i, _) in dark.iter().enumerate().filter(|(_, d)| **d) {
+                    pixels[(i / width + 2) * size + i % width + 2] = egui::Color32::BLACK;
+                }
+                let image = egui::ColorImage { size: [size, size], pixels };
+                let texture = ui.ctx().load_texture("pairing_qr", image, egui::TextureOptions::NEAREST);
+                let (name, password, cancel) = (pairing.name.clone(), pairing.password.clone(), pairing.cancel.clone());
+                self.wireless_job = Some(Job::spawn(move || wireless::QrPairing::wait_and_pair(&name, &password, &cancel)));
+                self.qr_pairing = Some((pairing, texture));
             }
-        } else {
-            "scrcpy not found".to_string()
         }
     }
 
-    fn get_device_type(serial: &str) -> String {
-        let output = Command::new("adb")
-            .arg("-s").arg(serial)
-            .arg("shell")
-            .arg("getprop ro.product.model")
-            .stdout(Stdio::piped())
-            .output();
-        if let Ok(output) = output {
-            let text = String::from_utf8_lossy(&output.stdout);
-            text.trim().to_string()
-        } else {
-            "Unknown".to_string()
-        }
+    /// Walks through wireless adb over a hotspot, for places without shared Wi-Fi.
+    fn show_hotspot_helper(&mut self, ui: &mut egui::Ui) {
+        let busy = self.wireless_job.is_some();
+        ui.collapsing("🔥 Hotspot helper", |ui| {
+            for mode in wireless::Hotspot::ALL {
+                ui.radio_value(&mut self.hotspot, mode, mode.label());
+            }
+            for (i, step) in self.hotspot.steps().iter().enumerate() {
+                ui.label(format!("{}. {}", i + 1, step));
+            }
+            let usb = self.selected_serial().filter(|s| !session::is_wireless_serial(s));
+            let hint = match &usb {
+                Some(serial) => format!("Enable adb over TCP on {} and connect to its hotspot address", self.device_display_name(serial)),
+                None => format!("Look for a phone with wireless debugging or port {} open on this computer's networks", wireless::TCPIP_PORT),
+            };
+            if ui.add_enabled(!busy, egui::Button::new("🔎 Find phone")).on_hover_text(hint).clicked() {
+                self.wireless_job = Some(Job::spawn(move || wireless::connect_over_hotspot(usb.as_deref())));
+            }
+        });
     }
 
-    fn detect_and_apply_device_type(&mut self) {
-        if self.devices.is_empty() { return; }
-        let serial = &self.devices[self.selected_device];
-        let dev_type = Self::get_device_type(serial);
-        // Avoid double borrow by splitting logic
-        let config = self.device_config.get(&dev_type).or_else(|| self.device_config.get("default")).cloned();
-        if let Some(cfg) = config {
-            self.apply_crop(&cfg.scrcpy_args);
-            self.device_type = cfg.label;
-        } else {
-            self.apply_crop("");
-        }
+    /// Shown once scrcpy reported clipboard sync errors: sends text to the device
+    /// through adb and the ADBKeyBoard IME instead.
+    fn show_clipboard_fallback(&mut self, ui: &mut egui::Ui, serial: &str, failures: usize) {
+        ui.colored_label(egui::Color32::ORANGE, format!("⚠ scrcpy clipboard sync failed ({}×) – using the adb fallback for this session", failures));
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.clipboard_text).hint_text("Paste text here"));
+            if ui.add_enabled(self.ime_job.is_none() && !self.clipboard_text.is_empty(), egui::Button::new("📋 Send to device"))
+                .on_hover_text("Types the text on the device via ADBKeyBoard")
+                .clicked()
+            {
+                let serial = serial.to_string();
+                let text = std::mem::take(&mut self.clipboard_text);
+                self.ime_job = Some(Job::spawn(move || ime::type_text(&serial, &text).map(|_| "Clipboard text sent to device.".to_string())));
+            }
+        });
     }
 
-    fn apply_crop(&mut self, crop: &str) {
-        self.crop_args = None;
-        self.applied_config = crop.to_string();
-        let mut crop_args = Vec::new();
-        let mut iter = crop.split_whitespace().peekable();
-        while let Some(part) = iter.next() {
-            match part {
-                s if s.starts_with("--crop") => {
-                    crop_args.push(s.to_string());
-                },
-                "-m" => {
-                    if let Some(_val) = iter.next() {
-                        // max_size removed from struct/UI
+    /// One row per session: which devices are mirrored, with their own controls.
+    fn show_sessions(&mut self, ui: &mut egui::Ui) {
+        if self.sessions.is_empty() {
+            return;
+        }
+        if self.sessions.iter().filter(|s| s.is_running()).count() > 1 {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Sessions").strong());
+                if ui.small_button("⏹ Stop all").clicked() {
+                    self.confirm_then(Confirm::StopAllSessions, "Stop mirroring on all devices?", ConfirmedAction::Run(PaletteAction::StopAllSessions));
+                }
+            });
+        }
+        for serial in self.sessions.serials() {
+            let Some(session) = self.sessions.get(&serial) else { continue };
+            let name = match serial.strip_prefix("otg:") {
+                _ if !session.is_otg() => self.device_display_name(&serial),
+                Some(usb) => format!("OTG {}", self.device_display_name(usb)),
+                None => "OTG (USB device)".to_string(),
+            };
+            let device_listed = session.is_otg() || self.devices.contains(&serial);
+            let mut restart = false;
+            let mut dismiss = false;
+            let mut stop = false;
+            let mut export = false;
+            let mut cancel = false;
+            let mut keep_alive = self.keep_alive.contains_key(&serial);
+            ui.horizontal(|ui| {
+                match &session.state {
+                    SessionState::Running if let Some(stage) = session.launch_stage => {
+                        ui.spinner();
+                        ui.label(format!("Starting {}", name)).on_hover_text(&serial);
+                        ui.add(egui::ProgressBar::new(stage.progress()).text(stage.label()).desired_width(200.0));
+                        cancel = ui.small_button("✖ Cancel").clicked();
+                    }
+                    SessionState::Running => {
+                        let secs = session.started_at.elapsed().as_secs();
+                        let verb = if session.is_otg() { "Controlling" } else { "Mirroring" };
+                        ui.colored_label(egui::Color32::GREEN, format!("● {} {} ({}:{:02})", verb, name, secs / 60, secs % 60))
+                            .on_hover_text(&serial);
+                        if let Some((cpu, memory)) = session.pid().and_then(|pid| self.process_usage.get(pid)) {
+                            let color = if cpu >= 50.0 { egui::Color32::ORANGE } else { ui.visuals().weak_text_color() };
+                            ui.colored_label(color, format!("CPU {:.0}% · {}", cpu, self.formats().bytes(memory)))
+                                .on_hover_text("scrcpy's CPU use (100% = one core) and memory; lower the bit rate, size or FPS to save battery");
+                        }
+                        if session.is_view_only() {
+                            ui.colored_label(egui::Color32::LIGHT_BLUE, "👁 View only").on_hover_text("Control disabled (--no-control)");
+                        }
+                        if let Some(path) = session.record_path() {
+                            ui.colored_label(egui::Color32::LIGHT_RED, "⏺ REC").on_hover_text(path.display().to_string());
+                            ui.label(path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+                        }
+                        for (name, running) in session.companion_names() {
+                            if running {
+                                ui.label(format!("+ {}", name));
+                            } else {
+                                ui.colored_label(egui::Color32::ORANGE, format!("{} exited", name));
+                            }
+                        }
+                        stop = ui.small_button("⏹ Stop").clicked();
                     }
-                },
-                "-b" | "--video-bit-rate" => {
-                    if let Some(_val) = iter.next() {
-                        // video_bitrate removed from struct/UI
+                    SessionState::Disconnected => {
+                        ui.colored_label(egui::Color32::ORANGE, format!("⚠ {} disconnected", name));
+                        restart = ui.add_enabled(device_listed, egui::Button::new("Reconnect"))
+                            .on_disabled_hover_text("Waiting for the device to show up in adb again")
+                            .clicked();
+                        dismiss = ui.button("Dismiss").clicked();
                     }
-                },
-                _ => {
-                    crop_args.push(part.to_string());
-                    if let Some(next) = iter.peek() {
-                        if !next.starts_with('-') {
-                            crop_args.push(iter.next().unwrap().to_string());
+                    SessionState::Exited(code) => {
+                        let code = code.map(|c| c.to_string()).unwrap_or_else(|| "unknown".to_string());
+                        ui.colored_label(egui::Color32::GRAY, format!("scrcpy for {} exited (code {})", name, code));
+                        if let Some(error) = session.last_error() {
+                            ui.colored_label(egui::Color32::RED, error);
                         }
+                        restart = ui.add_enabled(device_listed, egui::Button::new("Restart")).clicked();
+                        dismiss = ui.button("Dismiss").clicked();
                     }
                 }
+                if let Some(status) = self.keep_alive_status(&serial) {
+                    ui.colored_label(egui::Color32::ORANGE, status);
+                }
+                // Surveillance restarts its sessions by itself
+                if !self.surveillance.contains_key(&serial) && !self.locked() {
+                    ui.checkbox(&mut keep_alive, "♻").on_hover_text(format!(
+                        "Keep alive: relaunch with the same arguments {}s after scrcpy exits unexpectedly, up to {} times",
+                        self.settings.keep_alive_delay_secs, self.settings.keep_alive_max_retries
+                    ));
+                }
+                if !self.locked() {
+                    export = ui.small_button("💾").on_hover_text("Export this session's log").clicked();
+                }
+            });
+            let lines = session.log_lines();
+            if !lines.is_empty() {
+                egui::CollapsingHeader::new(format!("📜 Output ({} lines)", lines.len()))
+                    .id_salt(("session_output", &serial))
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical().max_height(160.0).stick_to_bottom(true).show(ui, |ui| {
+                            for line in &lines {
+                                let text = egui::RichText::new(line).monospace();
+                                if line.starts_with("ERROR") {
+                                    ui.colored_label(egui::Color32::RED, text);
+                                } else if line.starts_with("WARN") {
+                                    ui.colored_label(egui::Color32::ORANGE, text);
+                                } else {
+                                    ui.label(text);
+                                }
+                            }
+                        });
+                    });
+            }
+            let clipboard_failures = session.clipboard_failures();
+            if clipboard_failures > 0 && session.is_running() {
+                self.show_clipboard_fallback(ui, &serial, clipboard_failures);
+            }
+            self.show_device_notice(ui, &serial);
+            if keep_alive != self.keep_alive.contains_key(&serial) {
+                if keep_alive {
+                    self.keep_alive.insert(serial.clone(), Default::default());
+                } else {
+                    self.keep_alive.remove(&serial);
+                }
+            }
+            if restart {
+                self.restart_session(&serial);
+            } else if dismiss || cancel {
+                self.end_session(&serial);
+            } else if stop {
+                self.confirm_then(Confirm::StopSession, format!("Stop mirroring {}?", name), ConfirmedAction::EndSession(serial.clone()));
+            } else if export {
+                self.export_session_log(&serial);
             }
         }
-        if !crop_args.is_empty() {
-            self.crop_args = Some(crop_args.join(" "));
+    }
+
+    /// (Re)binds the local HTTP API to the port from settings, unless it
+    /// already listens there; 0 turns it off.
+    fn restart_http_api(&mut self) {
+        let port = self.settings.http_api_port;
+        if self.http_api.as_ref().is_some_and(|api| api.port == port) {
+            return;
+        }
+        // Joins the old accept loop, so the port can be bound again
+        self.http_api = None;
+        if port == 0 {
+            return;
+        }
+        match http_api::HttpApi::start(port) {
+            Ok(api) => self.http_api = Some(api),
+            Err(e) => self.notices.global(format!("⚠️ Failed to start the HTTP API on port {}: {}", port, e)),
         }
     }
 
+    fn selected_serial(&self) -> Option<String> {
+        self.devices.get(self.selected_device).cloned()
+    }
+
+    /// Lists devices in the background unless a listing is already running.
     fn refresh_devices(&mut self) {
-        let devices = Self::get_adb_devices();
+        if self.devices_job.is_none() {
+            self.devices_job = Some(Job::spawn(devices::list));
+        }
+    }
+
+    fn poll_device_list(&mut self) {
+        let Some(devices) = self.devices_job.as_ref().and_then(Job::poll) else { return };
+        self.devices_job = None;
         if devices != self.devices {
+            self.rule_pending.retain(|s| devices.contains(s));
+            self.rule_pending.extend(devices.iter().filter(|d| !self.devices.contains(d)).cloned());
+            self.device_props.request_all(&devices);
             self.devices = devices;
             if self.selected_device >= self.devices.len() {
                 self.selected_device = 0;
@@ -213,137 +2682,799 @@ impl ScrcpyGuiApp {
         }
     }
 
-    fn download_and_update_device_config(url: &str, path: &str) -> std::io::Result<()> {
-        let resp = reqwest::blocking::get(url).expect("Failed to download device config");
-        let text = resp.text().expect("Failed to read response text");
-        std::fs::write(path, text)
+    fn download_config(&mut self) {
+        if self.settings.config_per_model {
+            self.download_config_entries(true);
+            return;
+        }
+        if self.config_download_job.is_some() {
+            return;
+        }
+        let url = self.settings.config_url.clone();
+        let sources = self.settings.config_source.clone();
+        let use_token = self.config_token_stored;
+        self.config_download_job = Some(Job::spawn(move || {
+            let token = if use_token { secrets::get(secrets::Secret::ConfigToken)? } else { None };
+            Self::download_and_update_device_config(config_source::open(&sources, &url, token).as_ref(), &persist::data(DEVICE_CONFIG_PATH))
+        }));
+    }
+
+    /// Per-model config mode: fetches the entries of connected models, all of
+    /// them with `again`, else only those not requested yet in this run.
+    fn download_config_entries(&mut self, again: bool) {
+        if self.config_download_job.is_some() {
+            return;
+        }
+        let mut models: Vec<String> = self
+            .devices
+            .iter()
+            .filter_map(|serial| self.device_props.get(serial))
+            .map(|info| info.model.clone())
+            .filter(|model| !model.is_empty() && (again || !self.config_models_requested.contains(model)))
+            .collect();
+        models.sort();
+        models.dedup();
+        if models.is_empty() {
+            if again {
+                self.notices.global("⚠️ No connected device to download a config entry for.");
+            }
+            return;
+        }
+        self.config_models_requested.extend(models.iter().cloned());
+        let url = self.settings.config_url.clone();
+        let sources = self.settings.config_source.clone();
+        let use_token = self.config_token_stored;
+        self.config_download_job = Some(Job::spawn(move || {
+            let token = if use_token { secrets::get(secrets::Secret::ConfigToken)? } else { None };
+            let source = config_source::open(&sources, &url, token);
+            models.iter().try_for_each(|model| Self::download_config_entry(source.as_ref(), model))
+        }));
+    }
+
+    /// Re-reads a local directory config source whenever its file changes.
+    fn watch_config_source(&mut self) {
+        if self.safe_mode.is_some() {
+            return;
+        }
+        let mtime = config_source::watched_mtime(&self.settings.config_source, &self.settings.config_url);
+        if self.config_watch.is_some() && mtime.is_some() && mtime != self.config_watch {
+            self.download_config();
+        }
+        self.config_watch = mtime;
+    }
+
+    fn poll_config_download(&mut self) {
+        let Some(result) = self.config_download_job.as_ref().and_then(Job::poll) else { return };
+        self.config_download_job = None;
+        match result {
+            Ok(_) => {
+                self.notices.global("✅ Config downloaded successfully.");
+                self.reload_device_config();
+            }
+            Err(e) => self.notices.global(format!("⚠️ Failed to download config: {}", e)),
+        }
+    }
+
+    /// Downloads the device config, refusing anything that doesn't parse so a
+    /// bad response never replaces a working local file.
+    fn download_and_update_device_config(source: &dyn config_source::ConfigSource, path: &Path) -> Result<(), String> {
+        let text = source.fetch()?;
+        serde_json::from_str::<HashMap<String, DeviceConfig>>(&text)
+            .map_err(|e| format!("invalid config: {}", e))?;
+        persist::write_atomic(path, text).map_err(|e| e.to_string())
+    }
+
+    /// Fetches the entry of one model and merges it into the local config. A
+    /// source without an entry for the model leaves the config as it is.
+    fn download_config_entry(source: &dyn config_source::ConfigSource, model: &str) -> Result<(), String> {
+        let Some(text) = source.fetch_entry(model)? else { return Ok(()) };
+        let entry: DeviceConfig = serde_json::from_str(&text).map_err(|e| format!("invalid config entry for {}: {}", model, e))?;
+        let (mut config, error) = Self::load_device_config();
+        if let Some(error) = error {
+            return Err(format!("{} is corrupted, restore or reset it first: {}", DEVICE_CONFIG_PATH, error));
+        }
+        config.insert(model.to_string(), entry);
+        let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+        persist::write_atomic(persist::data(DEVICE_CONFIG_PATH), text).map_err(|e| e.to_string())
+    }
+
+    /// Loads the local device config, falling back to the bundled default. The
+    /// second value is the parse error when the local file exists but is corrupted.
+    fn load_device_config() -> (HashMap<String, DeviceConfig>, Option<String>) {
+        let (config, error) = match persist::read_json(persist::data(DEVICE_CONFIG_PATH)) {
+            Ok(config) => (config, None),
+            Err(e) => {
+                eprintln!("Failed to load {}: {}", DEVICE_CONFIG_PATH, e);
+                (None, Some(e))
+            }
+        };
+        let config = config.unwrap_or_else(|| {
+            persist::read_json(persist::data(DEFAULT_DEVICE_CONFIG_PATH))
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to load {}: {}", DEFAULT_DEVICE_CONFIG_PATH, e);
+                    None
+                })
+                .unwrap_or_default()
+        });
+        (config, error)
+    }
+
+    fn reload_device_config(&mut self) {
+        let (config, error) = Self::load_device_config();
+        self.device_config = config;
+        self.config_draft = None;
+        if let Some(error) = error {
+            self.notices.global(format!("⚠️ Failed to load {}: {}", DEVICE_CONFIG_PATH, error));
+        }
+        self.detect_and_apply_device_type();
+    }
+
+    /// Reads a restored or reset file back in; it stays held when that fails.
+    fn reload_recovered(&mut self, path: &'static str) -> Result<(), String> {
+        persist::release(persist::data(path));
+        let result = match path {
+            DEVICE_CONFIG_PATH => {
+                let (config, error) = Self::load_device_config();
+                error.map_or(Ok(()), Err).map(|()| {
+                    self.device_config = config;
+                    self.config_draft = None;
+                    self.detect_and_apply_device_type();
+                })
+            }
+            stats::STATS_PATH => UsageStats::load().map(|stats| self.stats = stats),
+            recordings::LIBRARY_PATH => RecordingLibrary::load().map(|library| self.recordings = library),
+            rules::RULES_PATH => RuleSet::load().map(|rules| self.rules.rules = rules.rules),
+            presets::PRESETS_PATH => presets::load().map(|presets| self.presets = presets),
+            history::HISTORY_PATH => history::ConnectionHistory::load().map(|history| self.history = history),
+            launches::LAUNCHES_PATH => launches::LaunchHistory::load().map(|launches| self.launches = launches),
+            _ => Ok(()),
+        };
+        if result.is_err() {
+            persist::hold(persist::data(path));
+        }
+        result
+    }
+
+    fn show_recovery_window(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = self.recovery.first() else { return };
+        let (path, error) = (prompt.path, prompt.error.clone());
+        let mut restore = false;
+        let mut reset = false;
+        egui::Window::new("Corrupted file")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{} could not be loaded:", path));
+                ui.colored_label(egui::Color32::RED, &error);
+                ui.label("Defaults are in use and the file is left untouched until you choose:");
+                ui.horizontal(|ui| {
+                    restore = ui.add_enabled(persist::has_backup(persist::data(path)), egui::Button::new("Restore backup"))
+                        .on_disabled_hover_text("No backup available")
+                        .clicked();
+                    reset = ui.button("Reset to defaults").clicked();
+                });
+            });
+        if !restore && !reset {
+            return;
+        }
+        let result = if restore {
+            persist::restore_backup(persist::data(path)).map_err(|e| e.to_string())
+        } else if path == SETTINGS_PATH {
+            Ok(())
+        } else {
+            fs::remove_file(persist::data(path)).map_err(|e| e.to_string())
+        };
+        if let Err(e) = result {
+            self.notices.global(format!("⚠️ Recovery of {} failed: {}", path, e));
+            return;
+        }
+        if path == SETTINGS_PATH {
+            let loaded = if reset { Ok(Settings::default()) } else { Settings::load() };
+            match loaded {
+                Ok(settings) => {
+                    self.settings = settings;
+                    let _ = self.settings.save();
+                }
+                Err(e) => {
+                    self.recovery[0].error = e;
+                    return;
+                }
+            }
+        } else if let Err(e) = self.reload_recovered(path) {
+            self.recovery[0].error = e;
+            return;
+        }
+        self.recovery.remove(0);
+        self.notices.global(format!("✅ {} recovered.", path));
     }
 }
 
 impl eframe::App for ScrcpyGuiApp {
+    /// Hands running sessions over to the next run, ends calibration and
+    /// profile comparison, waits for stopped sessions to quit, writes pending
+    /// rule edits and, since a clean exit within the first seconds is not a
+    /// failed startup, clears the startup marker.
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Sessions keep running after the GUI closes; the next run adopts them
+        self.sessions.save_handoff();
+        self.cancel_calibration();
+        self.profile_compare.stop();
+        // Their recordings are only complete once scrcpy has quit
+        for session in &mut self.stopping_sessions {
+            session.wait_stopped();
+        }
+        for rec in &mut self.stopping_recordings {
+            rec.session.wait_stopped();
+        }
+        self.poll_stopping();
+        self.save_rule_edits(true);
+        if !self.startup_finished {
+            safe_mode::finish_startup();
+        }
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        ctx.request_repaint();
-        if self.last_refresh.elapsed() > Duration::from_secs(1) {
+        match self.advanced.repaint_interval_ms {
+            0 => ctx.request_repaint(),
+            ms => ctx.request_repaint_after(Duration::from_millis(ms)),
+        }
+        let zoom = self.advanced.ui_zoom.clamp(0.5, 3.0);
+        if ctx.zoom_factor() != zoom {
+            ctx.set_zoom_factor(zoom);
+        }
+        if self.last_refresh.elapsed() > self.advanced.device_poll() {
             self.last_refresh = Instant::now();
             self.refresh_devices();
+            let stopped = self.sessions.check_health(&self.devices);
+            self.sessions_stopped(stopped);
+            self.run_surveillance();
+            self.run_keep_alive();
+            let pids: Vec<u32> = self.sessions.iter().filter_map(Session::pid).collect();
+            self.process_usage.refresh(&pids);
+            self.watch_config_source();
+            if self.battery_job.is_none() && !self.devices.is_empty() {
+                let devices = self.devices.clone();
+                self.battery_job = Some(Job::spawn(move || battery::query(&devices)));
+            }
+            if let Some(api) = &self.http_api {
+                self.publish_health(api);
+            }
+            self.sessions.save_handoff();
+            if let Some(group) = &mut self.group_recording {
+                for member in &mut group.members {
+                    member.session.check_health(&self.devices);
+                }
+            }
+            if let Some(rec) = &mut self.audio_recording {
+                rec.session.check_health(&self.devices);
+                if !rec.session.is_running() {
+                    let rec = self.audio_recording.take().unwrap();
+                    self.finish_recording(rec);
+                }
+            }
+        }
+        self.poll_device_list();
+        self.run_calibration();
+        let exited = self.sessions.reap(&self.devices);
+        self.sessions_stopped(exited);
+        self.poll_scrcpy_versions();
+        self.known_flags.poll();
+        self.poll_config_download();
+        self.poll_device_props();
+        self.run_rules();
+        self.console.poll();
+        self.poll_installs();
+        self.poll_power_jobs();
+        self.poll_stopping();
+        self.poll_preflight();
+        self.poll_profile_compare();
+        if let Some(result) = self.diagnostics_job.as_ref().and_then(Job::poll) {
+            self.diagnostics_job = None;
+            self.notices.global(match result {
+                Ok(path) => format!("✅ Diagnostic bundle saved to {}", path.display()),
+                Err(e) => format!("⚠️ Failed to export diagnostic bundle: {}", e),
+            });
+        }
+        if let Some((serial, result)) = self.screenshot_job.as_ref().and_then(Job::poll) {
+            self.screenshot_job = None;
+            self.notices.device(&serial, match result {
+                Ok(path) => format!("✅ Screenshot saved to {}", path.display()),
+                Err(e) => format!("⚠️ Screenshot failed: {}", e),
+            });
+        }
+        if let Some(result) = self.chapters_job.as_ref().and_then(Job::poll) {
+            self.chapters_job = None;
+            self.notices.global(match result {
+                Ok(path) => format!("✅ Chapters written to {}", path.display()),
+                Err(e) => format!("⚠️ Failed to embed chapters: {}", e),
+            });
+        }
+        let mut watermarked = Vec::new();
+        self.watermark_jobs.retain(|job| match job.poll() {
+            Some(result) => {
+                watermarked.push(result);
+                false
+            }
+            None => true,
+        });
+        for result in watermarked {
+            self.notices.global(match result {
+                Ok(path) => format!("✅ Watermarked copy written to {}", path.display()),
+                Err(e) => format!("⚠️ Failed to watermark recording: {}", e),
+            });
+        }
+        self.updater.poll();
+        self.package_catalog.poll();
+        if let Some(result) = self.ime_job.as_ref().and_then(Job::poll) {
+            self.ime_job = None;
+            self.notices.global(match result {
+                Ok(msg) => format!("✅ {}", msg),
+                Err(e) => format!("⚠️ Text input failed: {}", e),
+            });
+        }
+        self.poll_device_state();
+        self.touch_pad.poll();
+        if !self.startup_finished && self.launched_at.elapsed() > STARTUP_GRACE {
+            self.startup_finished = true;
+            safe_mode::finish_startup();
+        }
+        self.monitor = ctx.input(|i| Some((i.viewport().native_pixels_per_point?, i.viewport().monitor_size?)));
+        self.sessions.update_launch_stages();
+        if let Some((serial, caps)) = self.capabilities_job.as_ref().and_then(Job::poll) {
+            self.capabilities_job = None;
+            self.capabilities.insert(serial, caps);
+        }
+        if let Some(levels) = self.battery_job.as_ref().and_then(Job::poll) {
+            self.battery_job = None;
+            self.battery = levels.into_iter().collect();
+        }
+        if let Some(result) = self.wireless_job.as_ref().and_then(Job::poll) {
+            self.wireless_job = None;
+            self.qr_pairing = None;
+            self.notices.global(match result {
+                Ok(msg) => format!("✅ {}", msg),
+                Err(e) => format!("⚠️ Wireless ADB failed: {}", e),
+            });
+            self.refresh_devices();
         }
         if !self.devices.is_empty() && self.selected_device != self.last_selected_device {
             self.last_selected_device = self.selected_device;
+            self.selection_changed_at = Some(Instant::now());
+        }
+        if let Some(changed_at) = self.selection_changed_at
+            && changed_at.elapsed() >= SELECTION_DEBOUNCE
+        {
+            self.selection_changed_at = None;
             self.detect_and_apply_device_type();
         }
-        let scrcpy_version = Self::get_scrcpy_version();
+        let binary = match self.selected_serial() {
+            Some(serial) => self.scrcpy_binary_for(&serial),
+            None => Some(self.settings.default_scrcpy_binary.trim().to_string()).filter(|b| !b.is_empty()).unwrap_or_else(|| "scrcpy".to_string()),
+        };
+        self.request_scrcpy_version(&binary);
+        let scrcpy_version = self.scrcpy_versions[&binary].clone();
         egui::TopBottomPanel::top("top_bar").show(ctx, |ui| {
             ui.horizontal_centered(|ui| {
                 ui.heading("📱 scrcpy GUI");
-                ui.label(egui::RichText::new(format!("v{}", scrcpy_version)).color(egui::Color32::LIGHT_BLUE).size(16.0));
-            });
-        });
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add_space(8.0);
-            egui::CollapsingHeader::new("Configuration").default_open(true).show(ui, |ui| {
-                ui.horizontal(|ui| {
-                    ui.label("Config URL:");
-                    ui.text_edit_singleline(&mut self.config_url).on_hover_text("Remote JSON config for device types");
-                    if ui.button("⬇ Download").on_hover_text("Download latest config from URL").clicked() {
-                        match Self::download_and_update_device_config(&self.config_url, "scrcpy_device_config.json") {
-                            Ok(_) => self.status_message = "✅ Config downloaded successfully.".to_string(),
-                            Err(e) => self.status_message = format!("⚠️ Failed to download config: {}", e),
-                        }
+                let version = egui::Label::new(egui::RichText::new(format!("v{}", scrcpy_version)).color(egui::Color32::LIGHT_BLUE).size(16.0))
+                    .sense(egui::Sense::click());
+                if ui.add(version).on_hover_text(format!("{} – click to re-detect", binary)).clicked() {
+                    self.scrcpy_versions.clear();
+                }
+                if ui.button("🔍").on_hover_text("Command palette (Ctrl+K)").clicked() {
+                    self.palette.toggle();
+                }
+                if self.locked() {
+                    if ui.button("🔒").on_hover_text("Operator mode – unlock").clicked() {
+                        self.show_unlock = true;
                     }
-                });
-                let changed = ui.checkbox(&mut self.auto_download_on_start, "Auto download config on start")
-                    .on_hover_text("Download config at app startup").changed();
-                if changed {
-                    // Save to settings.json
-                    let settings = serde_json::json!({
-                        "auto_download_on_start": self.auto_download_on_start
-                    });
-                    let _ = std::fs::write("settings.json", serde_json::to_string_pretty(&settings).unwrap());
+                    return;
+                }
+                if ui.button("⚙").on_hover_text("Settings").clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+                if ui.button("📊").on_hover_text("Usage stats").clicked() {
+                    self.show_stats = !self.show_stats;
+                }
+                if ui.button("🎞").on_hover_text("Recordings").clicked() {
+                    self.show_recordings = !self.show_recordings;
+                }
+                if ui.button("🕘").on_hover_text("Launch history").clicked() {
+                    self.show_launch_history = !self.show_launch_history;
+                }
+                if ui.button("⚡").on_hover_text("Automation rules").clicked() {
+                    self.show_rules = !self.show_rules;
+                }
+                if ui.button("👥").on_hover_text("Device groups").clicked() {
+                    self.show_groups = !self.show_groups;
+                }
+                if ui.button("📋").on_hover_text("Device loans").clicked() {
+                    self.show_loans = !self.show_loans;
+                }
+                if !self.settings.operator_pin_hash.is_empty() && ui.button("🔓").on_hover_text("Switch to operator mode").clicked() {
+                    self.lock_operator_mode();
                 }
             });
+        });
+        egui::CentralPanel::default().show(ctx, |ui| {
             ui.add_space(8.0);
-            if !self.status_message.is_empty() {
-                let is_error = self.status_message.to_lowercase().contains("fail") || self.status_message.to_lowercase().contains("error");
-                let color = if is_error { egui::Color32::RED } else { egui::Color32::GREEN };
-                let icon = if is_error { "⚠️" } else { "✅" };
-                ui.horizontal(|ui| {
-                    ui.colored_label(color, icon);
-                    ui.colored_label(color, &self.status_message);
-                });
+            if let Some(reason) = self.safe_mode {
+                ui.colored_label(
+                    egui::Color32::ORANGE,
+                    format!("🛟 Safe mode ({}): default settings, bundled device config, no config download or automation rules. Settings are not saved; restart normally to leave.", reason.label()),
+                );
                 ui.add_space(4.0);
             }
+            self.show_automation_failures(ui);
             ui.group(|ui| {
                 ui.horizontal(|ui| {
                     ui.label("Device:");
-                    if self.devices.is_empty() {
+                    let recent = self.history.recent(RECENT_DEVICES);
+                    if self.devices.is_empty() && recent.is_empty() {
                         ui.label("No devices found");
                     } else {
+                        let loans = &self.settings.loans;
+                        let device_label = |serial: &str| {
+                            let mirrored = if self.sessions.is_running(serial) { "● " } else { "" };
+                            let overdue = if loans.get(serial).is_some_and(loans::Loan::is_overdue) { " ⏰" } else { "" };
+                            let battery = self.battery.get(serial).map(|b| format!(" {}", b.label())).unwrap_or_default();
+                            if let Some(alias) = self.settings.device_aliases.get(serial) {
+                                return format!("{}{}{}{}", mirrored, alias, battery, overdue);
+                            }
+                            match self.device_props.get(serial) {
+                                Some(info) if !info.model.is_empty() => format!("{}{} ({}){}{}", mirrored, serial, info.model, battery, overdue),
+                                _ => format!("{}{}{}{}", mirrored, serial, battery, overdue),
+                            }
+                        };
+                        let selected_text = match self.devices.get(self.selected_device) {
+                            Some(serial) => device_label(serial),
+                            None => "No devices connected".to_string(),
+                        };
+                        let mut connect = None;
                         egui::ComboBox::new("device_select", "Device")
-                            .selected_text(self.devices[self.selected_device].clone())
+                            .selected_text(selected_text)
                             .show_ui(ui, |ui| {
+                                if !recent.is_empty() {
+                                    ui.weak("Recently used");
+                                    for used in recent {
+                                        if let Some(i) = self.devices.iter().position(|d| *d == used.serial) {
+                                            ui.selectable_value(&mut self.selected_device, i, device_label(&used.serial));
+                                            continue;
+                                        }
+                                        ui.horizontal(|ui| {
+                                            let name = match self.settings.device_aliases.get(&used.serial) {
+                                                Some(alias) => alias.clone(),
+                                                None if used.model.is_empty() => used.serial.clone(),
+                                                None => format!("{} ({})", used.serial, used.model),
+                                            };
+                                            ui.add_enabled(false, egui::Label::new(format!("{} – offline", name)));
+                                            // mDNS serials can't be passed to adb connect
+                                            if used.serial.contains(':')
+                                                && !used.serial.contains("._adb-tls-connect.")
+                                                && ui.small_button("🔗 Connect").on_hover_text("adb connect").clicked()
+                                            {
+                                                connect = Some(used.serial.clone());
+                                            }
+                                        });
+                                    }
+                                    ui.separator();
+                                }
+                                ui.weak("Connected");
                                 for (i, dev) in self.devices.iter().enumerate() {
-                                    ui.selectable_value(&mut self.selected_device, i, dev);
+                                    if !recent.iter().any(|used| used.serial == *dev) {
+                                        ui.selectable_value(&mut self.selected_device, i, device_label(dev));
+                                    }
                                 }
                             });
+                        if let Some(address) = connect
+                            && self.wireless_job.is_none()
+                        {
+                            self.wireless_job = Some(Job::spawn(move || wireless::connect(&address)));
+                        }
                         if ui.button("↻").on_hover_text("Refresh device list").clicked() {
                             self.refresh_devices();
                         }
                     }
                 });
+                // Devices with a session show their messages in its row
+                if let Some(serial) = self.selected_serial()
+                    && self.sessions.get(&serial).is_none()
+                {
+                    self.show_device_notice(ui, &serial);
+                }
+                if let Some(serial) = self.selected_serial() {
+                    self.show_installs(ui, &serial);
+                }
+                if !self.devices.is_empty() && !self.locked() {
+                    ui.horizontal(|ui| {
+                        let serial = self.devices[self.selected_device].clone();
+                        ui.label("Start app:");
+                        let current = self.settings.start_apps.get(&serial).map(|pkg| {
+                            self.package_catalog
+                                .get(&serial)
+                                .and_then(|list| list.iter().find(|e| &e.package == pkg))
+                                .map(|e| e.display_name().to_string())
+                                .unwrap_or_else(|| pkg.clone())
+                        });
+                        ui.label(current.as_deref().unwrap_or("(none)"));
+                        if ui.button("📦 Pick…").on_hover_text("Choose an app to open when mirroring starts (--start-app)").clicked() {
+                            self.app_search.clear();
+                            self.show_app_picker = true;
+                        }
+                        if self.settings.start_apps.contains_key(&serial) && ui.button("Clear").clicked() {
+                            self.settings.start_apps.remove(&serial);
+                            let _ = self.settings.save();
+                        }
+                    });
+                    let serial = self.devices[self.selected_device].clone();
+                    let mut tether = self.settings.reverse_tethering.contains(&serial);
+                    if ui.checkbox(&mut tether, "🌐 Share this PC's internet while mirroring")
+                        .on_hover_text("Reverse tethering over USB with gnirehtet, started and stopped with the session")
+                        .changed()
+                    {
+                        if tether {
+                            self.settings.reverse_tethering.insert(serial);
+                        } else {
+                            self.settings.reverse_tethering.remove(&serial);
+                        }
+                        let _ = self.settings.save();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("scrcpy binary:");
+                        let serial = self.devices[self.selected_device].clone();
+                        let hint = self.config_scrcpy_binary(&serial).unwrap_or_else(|| "scrcpy".to_string());
+                        ui.add(egui::TextEdit::singleline(&mut self.scrcpy_binary_input).hint_text(hint))
+                            .on_hover_text("Executable used to launch scrcpy for this device only");
+                        if ui.button("Save").clicked() {
+                            let path = self.scrcpy_binary_input.trim().to_string();
+                            if path.is_empty() {
+                                self.settings.scrcpy_binaries.remove(&serial);
+                            } else {
+                                self.settings.scrcpy_binaries.insert(serial.clone(), path);
+                            }
+                            match self.settings.save() {
+                                Ok(_) => self.notices.global("✅ scrcpy binary override saved.".to_string()),
+                                Err(e) => self.notices.global(format!("⚠️ Failed to save settings: {}", e)),
+                            }
+                        }
+                        if ui.button("Clear").clicked() {
+                            self.scrcpy_binary_input.clear();
+                            self.settings.scrcpy_binaries.remove(&serial);
+                            let _ = self.settings.save();
+                        }
+                    });
+                }
             });
+            if !self.locked() {
+                self.show_wireless(ui);
+                self.show_device_settings(ui);
+                self.show_preview(ui);
+                self.show_touch_pad(ui);
+                self.show_text_input(ui);
+                self.show_adb_console(ui);
+                if let Some(serial) = self.selected_serial() {
+                    ui.horizontal(|ui| {
+                        if ui.button("📜 Logcat").on_hover_text("Stream adb logcat of this device").clicked() {
+                            self.open_logcat(&serial);
+                        }
+                        if ui.button("📁 Files").on_hover_text("Browse the device storage, pull and push files").clicked() {
+                            self.open_file_browser(&serial);
+                        }
+                        if ui.button("⚖ Compare profiles").on_hover_text("Mirror this device twice with two profiles side by side").clicked() {
+                            self.show_profile_compare = true;
+                        }
+                        self.show_power_menu(ui, &serial);
+                    });
+                }
+            }
             ui.add_space(8.0);
             egui::Frame::group(ui.style()).show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label(egui::RichText::new(format!("Device type: ")).strong());
+                    ui.label(egui::RichText::new("Device type: ").strong());
                     ui.label(egui::RichText::new(&self.device_type).color(egui::Color32::YELLOW));
                 });
+                if let Some(info) = self.devices.get(self.selected_device).and_then(|s| self.device_props.get(s)) {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Model:").strong());
+                        let sdk = info.sdk.map(|v| format!(" (SDK {})", v)).unwrap_or_default();
+                        ui.label(format!("{} {} – Android {}{}", info.manufacturer, info.model, info.android_version, sdk));
+                    });
+                }
                 ui.horizontal(|ui| {
                     ui.label(egui::RichText::new("Applied config:").strong());
                     ui.label(egui::RichText::new(&self.applied_config).color(egui::Color32::LIGHT_GREEN));
                 });
-            });
-            ui.add_space(8.0);
-            egui::CollapsingHeader::new("Advanced").default_open(false).show(ui, |ui| {
-                if self.device_config.is_empty() {
-                    ui.colored_label(egui::Color32::RED, "Device config missing or invalid!");
-                } else {
-                    ui.label("Loaded device configs:");
-                    egui::ScrollArea::vertical().max_height(100.0).show(ui, |ui| {
-                        for (k, v) in &self.device_config {
-                            ui.horizontal(|ui| {
-                                ui.label(egui::RichText::new(k).strong());
-                                ui.label(format!("{} | {}", v.label, v.scrcpy_args));
-                            });
-                        }
+                if let Some(serial) = self.selected_serial() {
+                    for problem in self.profile_problems(&serial) {
+                        ui.colored_label(egui::Color32::ORANGE, format!("⚠ {}", problem));
+                    }
+                    if !self.locked() {
+                        self.show_loan_editor(ui, &serial);
+                    }
+                    self.show_device_alias(ui, &serial);
+                    self.show_device_notes(ui, &serial);
+                }
+                if let Some(info) = self.devices.get(self.selected_device).and_then(|s| self.device_props.get(s)) {
+                    ui.collapsing(format!("Device properties ({})", info.props.len()), |ui| {
+                        egui::ScrollArea::vertical().id_salt("device_props").max_height(150.0).show(ui, |ui| {
+                            let mut props: Vec<_> = info.props.iter().collect();
+                            props.sort();
+                            for (k, v) in props {
+                                ui.label(format!("{} = {}", k, v));
+                            }
+                        });
                     });
                 }
             });
-            ui.add_space(12.0);
+            ui.add_space(20.0);
+            if !self.presets.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label("Preset:");
+                    let current = if self.selected_preset.is_empty() { "(none)" } else { self.selected_preset.as_str() };
+                    egui::ComboBox::from_id_salt("preset").selected_text(current.to_string()).show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.selected_preset, String::new(), "(none)");
+                        for preset in &self.presets {
+                            ui.selectable_value(&mut self.selected_preset, preset.name.clone(), &preset.name).on_hover_text(&preset.args);
+                        }
+                    });
+                    ui.label("added to the device config").on_hover_text(format!("Edit presets in {}", presets::PRESETS_PATH));
+                });
+            }
+            if !self.locked() {
+                self.show_window_sizing(ui);
+                self.show_window_placement(ui);
+            }
             ui.horizontal(|ui| {
-                if ui.add_enabled(!self.devices.is_empty() && self.scrcpy_process.is_none(), egui::Button::new("▶ Start scrcpy")).on_hover_text("Launch scrcpy for selected device").clicked() {
-                    let mut cmd = Command::new("scrcpy");
-                    if !self.devices.is_empty() {
-                        cmd.arg("--serial").arg(&self.devices[self.selected_device]);
+                let selected = self.selected_serial().unwrap_or_default();
+                if self.settings.otg_mode {
+                    let key = session::otg_key(self.selected_serial().filter(|s| !session::is_wireless_serial(s)).as_deref());
+                    let running = self.sessions.is_running(&key);
+                    if ui.add_enabled(!running, egui::Button::new("▶ Start OTG")).on_hover_text("Keyboard and mouse over USB without mirroring; works without adb authorization").clicked() {
+                        self.start_otg_session();
                     }
-                    if let Some(ref crop) = self.crop_args {
-                        for arg in crop.split_whitespace() {
-                            cmd.arg(arg);
-                        }
+                    if ui.add_enabled(running, egui::Button::new("⏹ Stop OTG")).clicked() {
+                        self.end_session(&key);
                     }
-                    match cmd.spawn() {
-                        Ok(child) => self.scrcpy_process = Some(child),
-                        Err(e) => self.status_message = format!("⚠️ Failed to start scrcpy: {}", e),
+                } else {
+                    let running = self.sessions.is_running(&selected);
+                    if ui.add_enabled(!self.devices.is_empty() && !running && self.selection_changed_at.is_none(), egui::Button::new("▶ Start scrcpy")).on_hover_text("Launch scrcpy for selected device").clicked() {
+                        self.start_session();
                     }
-                }
-                if ui.add_enabled(self.scrcpy_process.is_some(), egui::Button::new("⏹ Stop scrcpy")).on_hover_text("Stop running scrcpy process").clicked() {
-                    if let Some(child) = &mut self.scrcpy_process {
-                        let _ = child.kill();
+                    if ui.add_enabled(running, egui::Button::new("⏹ Stop scrcpy")).on_hover_text("Stop scrcpy for the selected device").clicked() {
+                        self.confirm_then(Confirm::StopSession, format!("Stop mirroring {}?", selected), ConfirmedAction::EndSession(selected.clone()));
                     }
-                    self.scrcpy_process = None;
+                }
+                if !self.locked() && ui.checkbox(&mut self.settings.otg_mode, "🔌 OTG").on_hover_text("Launch scrcpy --otg: HID keyboard and mouse only, no mirroring").changed() {
+                    let _ = self.settings.save();
+                }
+                if !self.locked() && ui.add_enabled(!self.devices.is_empty() && self.embedded.is_none(), egui::Button::new("📺 Mirror in window"))
+                    .on_hover_text("Show the screen inside this window (needs ffmpeg)")
+                    .clicked()
+                {
+                    let serial = self.devices[self.selected_device].clone();
+                    self.start_embedded(&serial);
+                }
+                if !self.locked() && self.devices.len() > 1 && ui.button("🆚 Compare").on_hover_text("Two embedded streams side by side").clicked() {
+                    self.show_compare = true;
+                }
+                if !self.locked() && ui.add_enabled(self.sessions.get(&selected).is_some(), egui::Button::new("💾 Export log")).on_hover_text("Save this session's scrcpy output to the logs folder").clicked() {
+                    self.export_session_log(&selected);
                 }
             });
+            if let Some(serial) = self.selected_serial().filter(|_| !self.settings.otg_mode) {
+                if !self.locked() {
+                    self.show_extra_args(ui, &serial);
+                }
+                self.show_command_preview(ui, &serial);
+            }
+            if !self.locked() {
+                self.show_quality(ui);
+                self.show_video_encoder(ui);
+                self.show_video_source(ui);
+                self.show_display_picker(ui);
+                self.show_screen_toggles(ui);
+                self.show_audio_options(ui);
+                self.show_record_options(ui);
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut self.settings.record_audio_with_session, "🎙 Record audio to a separate file")
+                        .on_hover_text("Starts an audio-only scrcpy recording alongside mirroring")
+                        .changed()
+                    {
+                        let _ = self.settings.save();
+                    }
+                    let before = self.settings.audio_format;
+                    egui::ComboBox::from_id_salt("audio_format")
+                        .selected_text(self.settings.audio_format.extension())
+                        .show_ui(ui, |ui| {
+                            for format in AudioFormat::ALL {
+                                ui.selectable_value(&mut self.settings.audio_format, format, format.extension());
+                            }
+                        });
+                    if self.settings.audio_format != before {
+                        let _ = self.settings.save();
+                    }
+                    if self.audio_recording.is_some() {
+                        if ui.button("⏹ Stop audio").clicked() {
+                            self.stop_audio_recording();
+                        }
+                    } else if ui.add_enabled(!self.devices.is_empty(), egui::Button::new("🎙 Record audio only")).clicked() {
+                        let serial = self.devices[self.selected_device].clone();
+                        self.start_audio_recording(&serial, false);
+                    }
+                });
+                if let Some(rec) = &self.audio_recording {
+                    ui.colored_label(egui::Color32::LIGHT_RED, format!("🎙 Recording audio of {} → {}", rec.session.serial, rec.path.display()));
+                }
+                if !self.recording_starts().is_empty() {
+                    ui.horizontal(|ui| {
+                        let response = ui.add(egui::TextEdit::singleline(&mut self.mark_note).hint_text("Bookmark note (optional)"));
+                        let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if ui.button("🔖 Mark").on_hover_text("Bookmark this moment in the running recordings").clicked() || entered {
+                            self.add_mark();
+                        }
+                    });
+                }
+            }
+            self.show_bandwidth_meter(ui);
+            if !self.locked() {
+                egui::CollapsingHeader::new("Synchronized group recording").default_open(false).show(ui, |ui| {
+                    if let Some(group) = &self.group_recording {
+                        let secs = group.started.elapsed().as_secs();
+                        ui.colored_label(egui::Color32::LIGHT_RED, format!("⏺ Recording {} devices ({}:{:02})", group.members.len(), secs / 60, secs % 60));
+                        for (timing, member) in group.timings().iter().zip(&group.members) {
+                            let start = timing.start_offset_ms.map(|ms| format!("+{} ms", ms)).unwrap_or_else(|| "waiting…".to_string());
+                            let state = if member.session.is_running() { "" } else { " (stopped)" };
+                            ui.label(format!("{}: spawned +{} ms, recording {}{}", timing.serial, timing.spawn_offset_ms, start, state));
+                        }
+                        if ui.button("⏹ Stop group recording").clicked() {
+                            self.confirm_then(Confirm::StopAllSessions, "Stop recording on all devices?", ConfirmedAction::Run(PaletteAction::StopGroupRecording));
+                        }
+                    } else {
+                        ui.label("Records every ticked device at once, with a shared timestamp and per-device start offsets.");
+                        for serial in &self.devices {
+                            let mut included = !self.group_excluded.contains(serial);
+                            if ui.checkbox(&mut included, self.device_display_name(serial)).on_hover_text(serial).changed() {
+                                if included {
+                                    self.group_excluded.remove(serial);
+                                } else {
+                                    self.group_excluded.insert(serial.clone());
+                                }
+                            }
+                        }
+                        if ui.add_enabled(!self.devices.is_empty(), egui::Button::new("⏺ Start synchronized recording")).clicked() {
+                            self.start_group_recording();
+                        }
+                    }
+                });
+            }
+            self.show_sessions(ui);
+            self.show_surveillance(ui);
             ui.add_space(8.0);
         });
+        self.show_changelog_windows(ctx);
+        self.show_recovery_window(ctx);
+        self.show_stats_window(ctx);
+        self.show_settings_window(ctx);
+        self.show_command_palette(ctx);
+        self.show_app_picker_window(ctx);
+        self.show_recordings_window(ctx);
+        self.show_launch_history_window(ctx);
+        self.show_rules_window(ctx);
+        self.show_groups_window(ctx);
+        self.show_loans_window(ctx);
+        self.show_calibration_window(ctx);
+        self.show_logcat_window(ctx);
+        self.show_file_browser(ctx);
+        self.show_profile_compare_window(ctx);
+        self.show_migration_window(ctx);
+        self.show_preflight_window(ctx);
+        self.show_input_echo(ctx);
+        self.handle_dropped_files(ctx);
+        self.show_toasts(ctx);
+        self.show_confirm_window(ctx);
+        self.show_unlock_window(ctx);
+        self.show_embedded_window(ctx);
+        self.show_compare_window(ctx);
         egui::TopBottomPanel::bottom("footer").show(ctx, |ui| {
             ui.horizontal_centered(|ui| {
                 ui.hyperlink_to("scrcpy project", "https://github.com/Genymobile/scrcpy");
@@ -353,11 +3484,51 @@ impl eframe::App for ScrcpyGuiApp {
     }
 }
 
+/// Launch arguments taken from a device config's `scrcpy_args`.
+pub(crate) fn parse_config_args(crop: &str) -> Option<String> {
+    let mut crop_args = Vec::new();
+    let mut iter = crop.split_whitespace().peekable();
+    while let Some(part) = iter.next() {
+        match part {
+            s if s.starts_with("--crop") => {
+                crop_args.push(s.to_string());
+            },
+            _ => {
+                crop_args.push(part.to_string());
+                if let Some(next) = iter.peek()
+                    && !next.starts_with('-')
+                {
+                    crop_args.push(iter.next().unwrap().to_string());
+                }
+            }
+        }
+    }
+    if crop_args.is_empty() { None } else { Some(crop_args.join(" ")) }
+}
+
+/// One labelled horizontal bar of a stats chart; `fraction` is in 0..=1.
+fn bar_row(ui: &mut egui::Ui, label: &str, fraction: f32, value: &str) {
+    ui.horizontal(|ui| {
+        ui.add_sized([120.0, 16.0], egui::Label::new(label).truncate());
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(140.0, 12.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        let mut bar = rect;
+        bar.set_width(rect.width() * fraction.clamp(0.0, 1.0));
+        ui.painter().rect_filled(bar, 2.0, egui::Color32::LIGHT_BLUE);
+        ui.label(value);
+    });
+}
+
 fn main() {
+    if updater::apply_staged_update() {
+        return;
+    }
+    let migrated = paths::use_config_dir(std::env::args().any(|a| a == "--portable"));
+    let safe_mode = safe_mode::begin_startup(std::env::args().any(|a| a == "--safe-mode"));
     let options = eframe::NativeOptions::default();
     let _ = eframe::run_native(
         "scrcpy GUI",
         options,
-        Box::new(|_cc| Ok(Box::new(ScrcpyGuiApp::default()))),
+        Box::new(move |_cc| Ok(Box::new(ScrcpyGuiApp::new(safe_mode, migrated)))),
     );
 }
@@ -0,0 +1,287 @@
+use crate::presets::{self, Preset};
+use crate::{ScrcpyGuiApp, cmdline};
+use eframe::egui;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Front-ends whose saved setups can be turned into presets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    /// QtScrcpy `config.ini`: a `[common]` section plus one per serial.
+    QtScrcpy,
+    /// guiscrcpy `guiscrcpy.json`.
+    Guiscrcpy,
+    /// Escrcpy and other JSON configs that store options under their
+    /// scrcpy flag names, e.g. `"--video-bit-rate": "8M"`.
+    FlagJson,
+    /// Shell or batch scripts, one `scrcpy …` command per profile.
+    Script,
+}
+
+impl Source {
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::QtScrcpy => "QtScrcpy",
+            Source::Guiscrcpy => "guiscrcpy",
+            Source::FlagJson => "Escrcpy / scrcpy options JSON",
+            Source::Script => "scrcpy command lines",
+        }
+    }
+}
+
+/// A profile found in the imported file.
+pub struct Candidate {
+    pub name: String,
+    pub args: String,
+    /// Settings that have no scrcpy option or can't be carried over.
+    pub skipped: Vec<String>,
+    pub import: bool,
+}
+
+/// Options that tie a command to one device or one run, never kept.
+const DROPPED_OPTIONS: [&str; 5] = ["-s", "--serial", "--tcpip", "--select-usb", "--select-tcpip"];
+
+/// Appends `value` to `name` as an option, or records why it was left out.
+/// Values with whitespace are skipped since presets are split on it.
+fn push_option(args: &mut Vec<String>, skipped: &mut Vec<String>, name: &str, value: &Value) {
+    if DROPPED_OPTIONS.contains(&name) {
+        return;
+    }
+    match value {
+        Value::Bool(true) => args.push(name.to_string()),
+        Value::Bool(false) | Value::Null => {}
+        Value::String(s) if s.is_empty() => {}
+        Value::String(s) if s.chars().any(char::is_whitespace) => skipped.push(format!("{} (value with spaces)", name)),
+        Value::String(s) => args.push(format!("{}={}", name, s)),
+        Value::Number(n) => args.push(format!("{}={}", name, n)),
+        Value::Array(_) | Value::Object(_) => skipped.push(name.to_string()),
+    }
+}
+
+/// Arguments of a command line with the device selection removed; `None`
+/// when it doesn't run scrcpy.
+fn script_args(line: &str) -> Option<Vec<String>> {
+    let words = cmdline::split(line).ok()?;
+    let start = words.iter().position(|w| {
+        let file = w.rsplit(['/', '\\']).next().unwrap_or(w).to_ascii_lowercase();
+        file == "scrcpy" || file == "scrcpy.exe"
+    })?;
+    let mut args = Vec::new();
+    let mut words = words.into_iter().skip(start + 1).peekable();
+    while let Some(word) = words.next() {
+        let name = word.split('=').next().unwrap_or(&word);
+        if DROPPED_OPTIONS.contains(&name) {
+            if !word.contains('=') && matches!(name, "-s" | "--serial" | "--tcpip") {
+                words.next_if(|next| !next.starts_with('-'));
+            }
+            continue;
+        }
+        // A shell redirection or chained command ends the scrcpy call
+        if matches!(word.as_str(), "&&" | "||" | "|" | ";" | ">" | "2>&1") {
+            break;
+        }
+        args.push(word);
+    }
+    Some(args)
+}
+
+fn parse_script(text: &str, stem: &str) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    let mut comment = None;
+    for line in text.lines().map(str::trim) {
+        let lower = line.to_ascii_lowercase();
+        if let Some(c) = line.strip_prefix('#').or_else(|| line.strip_prefix("::")).or_else(|| lower.starts_with("rem ").then(|| &line[4..])) {
+            let c = c.trim();
+            if !c.is_empty() && !c.starts_with('!') {
+                comment = Some(c.to_string());
+            }
+            continue;
+        }
+        let Some(args) = script_args(line) else { continue };
+        let name = comment.take().unwrap_or_else(|| format!("{} {}", stem, candidates.len() + 1));
+        let (args, skipped): (Vec<String>, Vec<String>) = args.into_iter().partition(|a| !a.contains(char::is_whitespace));
+        candidates.push(Candidate { name, args: args.join(" "), skipped, import: true });
+    }
+    candidates
+}
+
+/// QtScrcpy keys that map to a scrcpy option when set to `true`.
+const QTSCRCPY_FLAGS: [(&str, &str); 4] = [
+    ("WindowOnTop", "--always-on-top"),
+    ("AutoOffScreen", "--turn-screen-off"),
+    ("FramelessWindow", "--window-borderless"),
+    ("StayAwake", "--stay-awake"),
+];
+
+fn parse_qtscrcpy(text: &str) -> Vec<Candidate> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.to_string(), Vec::new()));
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some((_, entries)) = sections.last_mut()
+        {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+        .into_iter()
+        .filter(|(_, entries)| entries.iter().any(|(k, _)| k == "BitRate"))
+        .map(|(section, entries)| {
+            let mut args = Vec::new();
+            let mut skipped = Vec::new();
+            for (key, value) in entries {
+                if key == "BitRate" {
+                    args.push(format!("--video-bit-rate={}", value));
+                } else if let Some((_, option)) = QTSCRCPY_FLAGS.iter().find(|(k, _)| *k == key) {
+                    if value == "true" {
+                        args.push(option.to_string());
+                    }
+                } else if value != "false" && !value.is_empty() {
+                    skipped.push(key);
+                }
+            }
+            let name = if section == "common" { "QtScrcpy".to_string() } else { format!("QtScrcpy {}", section) };
+            Candidate { name, args: args.join(" "), skipped, import: true }
+        })
+        .collect()
+}
+
+fn parse_guiscrcpy(config: &serde_json::Map<String, Value>) -> Candidate {
+    let mut args = Vec::new();
+    let mut skipped = Vec::new();
+    for (key, value) in config {
+        match (key.as_str(), value) {
+            // guiscrcpy passes its bit rate to scrcpy in kilobits
+            ("bitrate", Value::Number(n)) => args.push(format!("--video-bit-rate={}K", n)),
+            ("dimension", Value::Number(n)) => args.push(format!("--max-size={}", n)),
+            ("fullscreen", Value::Bool(true)) => args.push("--fullscreen".to_string()),
+            ("swtouches", Value::Bool(true)) => args.push("--show-touches".to_string()),
+            ("dispRO", Value::Bool(true)) => args.push("--no-control".to_string()),
+            ("extra", Value::String(extra)) => args.extend(extra.split_whitespace().map(str::to_string)),
+            (_, Value::Bool(false) | Value::Null) => {}
+            ("bitrate" | "dimension" | "fullscreen" | "swtouches" | "dispRO" | "extra", _) => {}
+            _ => skipped.push(key.clone()),
+        }
+    }
+    Candidate { name: "guiscrcpy".to_string(), args: args.join(" "), skipped, import: true }
+}
+
+/// Every object holding `--option` keys, named after where it sits, e.g.
+/// Escrcpy's `global` scope and per-device scopes.
+fn collect_flag_objects(value: &Value, path: &str, out: &mut Vec<Candidate>) {
+    let Value::Object(map) = value else { return };
+    if map.keys().any(|k| k.starts_with("--")) {
+        let mut args = Vec::new();
+        let mut skipped = Vec::new();
+        for (key, value) in map.iter().filter(|(k, _)| k.starts_with("--")) {
+            push_option(&mut args, &mut skipped, key, value);
+        }
+        let name = if path.is_empty() { "Imported".to_string() } else { path.to_string() };
+        out.push(Candidate { name, args: args.join(" "), skipped, import: true });
+        return;
+    }
+    for (key, child) in map {
+        let path = if path.is_empty() { key.clone() } else { format!("{} {}", path, key) };
+        collect_flag_objects(child, &path, out);
+    }
+}
+
+/// Detects the format of `path` from its content and lists its profiles.
+pub fn read(path: &Path) -> Result<(Source, Vec<Candidate>), String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let (source, candidates) = if let Ok(json) = serde_json::from_str::<Value>(&text) {
+        match &json {
+            Value::Object(map) if map.contains_key("swtouches") || map.contains_key("dimension") => (Source::Guiscrcpy, vec![parse_guiscrcpy(map)]),
+            _ => {
+                let mut candidates = Vec::new();
+                collect_flag_objects(&json, "", &mut candidates);
+                (Source::FlagJson, candidates)
+            }
+        }
+    } else if text.lines().any(|l| l.trim().starts_with('[')) && text.contains("BitRate") {
+        (Source::QtScrcpy, parse_qtscrcpy(&text))
+    } else {
+        (Source::Script, parse_script(&text, &stem))
+    };
+    if candidates.is_empty() {
+        return Err(format!("no scrcpy profiles found in {}", path.display()));
+    }
+    Ok((source, candidates))
+}
+
+/// A file being imported, shown for review before anything is saved.
+pub struct Migration {
+    pub path: PathBuf,
+    pub source: Source,
+    pub candidates: Vec<Candidate>,
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn open_migration(&mut self) {
+        let Some(path) = rfd::FileDialog::new().set_title("Import profiles from another scrcpy GUI").pick_file() else { return };
+        match read(&path) {
+            Ok((source, candidates)) => self.migration = Some(Migration { path, source, candidates }),
+            Err(e) => self.notices.global(format!("⚠️ Import failed: {}", e)),
+        }
+    }
+
+    /// Adds the checked candidates to the presets, renaming any that clash
+    /// with an existing preset.
+    fn import_candidates(&mut self) {
+        let Some(migration) = self.migration.take() else { return };
+        let mut count = 0;
+        for candidate in migration.candidates.into_iter().filter(|c| c.import) {
+            let mut name = candidate.name.clone();
+            let mut n = 2;
+            while self.presets.iter().any(|p| p.name == name) {
+                name = format!("{} ({})", candidate.name, n);
+                n += 1;
+            }
+            self.presets.push(Preset { name, args: candidate.args, segment_minutes: None });
+            count += 1;
+        }
+        match presets::save(&self.presets) {
+            Ok(()) => self.notices.global(format!("✅ Imported {} profile(s) from {} as presets.", count, migration.source.label())),
+            Err(e) => self.notices.global(format!("⚠️ Failed to save {}: {}", presets::PRESETS_PATH, e)),
+        }
+    }
+
+    pub(crate) fn show_migration_window(&mut self, ctx: &egui::Context) {
+        let Some(migration) = &mut self.migration else { return };
+        let mut open = true;
+        let mut import = false;
+        egui::Window::new("📥 Import profiles").open(&mut open).default_width(560.0).show(ctx, |ui| {
+            ui.label(format!("{} · {}", migration.source.label(), migration.path.display()));
+            ui.label("Checked profiles are added to the presets. Review and edit the options before importing.");
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                egui::Grid::new("migration").num_columns(2).striped(true).show(ui, |ui| {
+                    for candidate in &mut migration.candidates {
+                        ui.checkbox(&mut candidate.import, "");
+                        ui.vertical(|ui| {
+                            ui.text_edit_singleline(&mut candidate.name);
+                            ui.add(egui::TextEdit::singleline(&mut candidate.args).desired_width(440.0).font(egui::TextStyle::Monospace));
+                            if !candidate.skipped.is_empty() {
+                                ui.weak(format!("Not carried over: {}", candidate.skipped.join(", ")));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+            ui.separator();
+            let checked = migration.candidates.iter().filter(|c| c.import).count();
+            if ui.add_enabled(checked > 0, egui::Button::new(format!("Import {} profile(s)", checked))).clicked() {
+                import = true;
+            }
+        });
+        if import {
+            self.import_candidates();
+        } else if !open {
+            self.migration = None;
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::ScrcpyGuiApp;
+use eframe::egui;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a confirmation stays on screen; errors stay until dismissed.
+const SUCCESS_TIME: Duration = Duration::from_secs(6);
+/// Toasts shown at once; older ones are dropped.
+const MAX_TOASTS: usize = 5;
+
+pub struct Notice {
+    pub text: String,
+    pub at: Instant,
+}
+
+impl Notice {
+    fn new(text: String) -> Self {
+        Self { text, at: Instant::now() }
+    }
+
+    pub fn is_error(&self) -> bool {
+        let lower = self.text.to_lowercase();
+        self.text.starts_with("⚠️") || lower.contains("fail") || lower.contains("error")
+    }
+
+    fn expired(&self) -> bool {
+        !self.is_error() && self.at.elapsed() > SUCCESS_TIME
+    }
+
+    fn color(&self) -> egui::Color32 {
+        if self.is_error() { egui::Color32::RED } else { egui::Color32::GREEN }
+    }
+}
+
+/// Feedback for the user: app-wide messages shown as toasts, and messages
+/// about one device shown inline next to it.
+#[derive(Default)]
+pub struct Notices {
+    toasts: Vec<Notice>,
+    devices: HashMap<String, Notice>,
+}
+
+impl Notices {
+    pub fn global(&mut self, text: impl Into<String>) {
+        self.toasts.push(Notice::new(text.into()));
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    /// Replaces the previous message of the device.
+    pub fn device(&mut self, serial: &str, text: impl Into<String>) {
+        self.devices.insert(serial.to_string(), Notice::new(text.into()));
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// The device's last message, with a button to dismiss it.
+    pub(crate) fn show_device_notice(&mut self, ui: &mut egui::Ui, serial: &str) {
+        let Some(notice) = self.notices.devices.get(serial) else { return };
+        if notice.expired() {
+            self.notices.devices.remove(serial);
+            return;
+        }
+        let mut dismiss = false;
+        ui.horizontal(|ui| {
+            ui.colored_label(notice.color(), &notice.text);
+            dismiss = ui.small_button("✖").on_hover_text("Dismiss").clicked();
+        });
+        if dismiss {
+            self.notices.devices.remove(serial);
+        }
+    }
+
+    /// App-wide messages stacked in the bottom right corner.
+    pub(crate) fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.notices.toasts.retain(|t| !t.expired());
+        if self.notices.toasts.is_empty() {
+            return;
+        }
+        let mut dismiss = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.notices.toasts.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(360.0);
+                        ui.horizontal(|ui| {
+                            ui.colored_label(toast.color(), &toast.text);
+                            if ui.small_button("✖").clicked() {
+                                dismiss = Some(i);
+                            }
+                        });
+                    });
+                }
+            });
+        if let Some(i) = dismiss {
+            self.notices.toasts.remove(i);
+        }
+    }
+}
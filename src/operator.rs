@@ -0,0 +1,106 @@
+use crate::ScrcpyGuiApp;
+use eframe::egui;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::hash::{BuildHasher, Hasher};
+
+/// PBKDF2 rounds for the PIN; a short PIN is only as safe as guessing it is slow.
+const PIN_ITERATIONS: u32 = 100_000;
+const PIN_SCHEME: &str = "pbkdf2-sha256";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// PBKDF2-HMAC-SHA256 with a single 32-byte output block.
+fn pbkdf2(pin: &str, salt: &str, iterations: u32) -> String {
+    let mac = Hmac::<Sha256>::new_from_slice(pin.trim().as_bytes()).expect("HMAC accepts keys of any length");
+    let mut block = mac.clone().chain_update(salt.as_bytes()).chain_update(1u32.to_be_bytes()).finalize().into_bytes();
+    let mut key = block;
+    for _ in 1..iterations {
+        block = mac.clone().chain_update(block).finalize().into_bytes();
+        key.iter_mut().zip(block).for_each(|(k, b)| *k ^= b);
+    }
+    hex(&key)
+}
+
+/// Random enough to tell installs apart; the salt need not be secret.
+fn new_salt() -> String {
+    let mut salt = Vec::new();
+    for _ in 0..2 {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+        salt.extend(hasher.finish().to_be_bytes());
+    }
+    hex(&salt)
+}
+
+/// Salted PBKDF2 of an operator PIN as `scheme$iterations$salt$key`; only
+/// this is stored in settings.
+pub fn pin_hash(pin: &str) -> String {
+    let salt = new_salt();
+    format!("{}${}${}${}", PIN_SCHEME, PIN_ITERATIONS, salt, pbkdf2(pin, &salt, PIN_ITERATIONS))
+}
+
+/// Whether `pin` is the one `stored` was made from.
+pub fn pin_matches(pin: &str, stored: &str) -> bool {
+    match stored.split('$').collect::<Vec<_>>()[..] {
+        [PIN_SCHEME, iterations, salt, key] => iterations.parse().is_ok_and(|n| pbkdf2(pin, salt, n) == key),
+        _ => false,
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// Operator mode: only device selection and start/stop are available.
+    pub(crate) fn locked(&self) -> bool {
+        self.settings.operator_locked
+    }
+
+    pub(crate) fn lock_operator_mode(&mut self) {
+        self.settings.operator_locked = true;
+        self.show_settings = false;
+        self.show_stats = false;
+        self.show_rules = false;
+        self.show_recordings = false;
+        self.show_launch_history = false;
+        self.show_app_picker = false;
+        match self.settings.save() {
+            Ok(_) => self.notices.global("✅ Operator mode on.".to_string()),
+            Err(e) => self.notices.global(format!("⚠️ Failed to save settings: {}", e)),
+        }
+    }
+
+    pub(crate) fn show_unlock_window(&mut self, ctx: &egui::Context) {
+        if !self.show_unlock {
+            return;
+        }
+        let mut open = true;
+        let mut unlock = false;
+        egui::Window::new("🔒 Operator mode")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if self.settings.operator_pin_hash.is_empty() {
+                    ui.label("This machine was locked by its configuration. Set operator_locked to false in settings.json to unlock.");
+                    return;
+                }
+                ui.label("Enter the PIN to unlock settings and advanced actions.");
+                let response = ui.add(egui::TextEdit::singleline(&mut self.pin_input).password(true));
+                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                unlock = ui.button("Unlock").clicked() || entered;
+            });
+        if unlock {
+            if pin_matches(&self.pin_input, &self.settings.operator_pin_hash) {
+                self.settings.operator_locked = false;
+                let _ = self.settings.save();
+                self.notices.global("✅ Operator mode off.".to_string());
+                open = false;
+            } else {
+                self.notices.global("⚠️ Wrong PIN, unlock failed.".to_string());
+            }
+            self.pin_input.clear();
+        }
+        self.show_unlock = open;
+    }
+}
@@ -0,0 +1,313 @@
+use crate::adb;
+use crate::persist;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+pub const CACHE_DIR: &str = "cache";
+
+/// APKs bigger than this are not pulled just to read their label and icon.
+const MAX_APK_PULL_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageEntry {
+    pub package: String,
+    pub label: Option<String>,
+    /// PNG extracted from the APK into the icon cache.
+    pub icon: Option<PathBuf>,
+    /// Set once label/icon resolution was attempted, successful or not.
+    #[serde(default)]
+    pub resolved: bool,
+    /// Installed by the user (`pm list packages -3`); `None` in caches
+    /// written before this was recorded.
+    #[serde(default)]
+    pub third_party: Option<bool>,
+}
+
+impl PackageEntry {
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.package)
+    }
+}
+
+fn adb_shell(serial: &str, command: &str) -> Option<String> {
+    let output = adb::run(&["-s", serial, "shell", command], adb::SHORT, None).ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Packages that have a launcher activity, i.e. what `--start-app` can open.
+pub fn list_launchable_packages(serial: &str) -> Vec<String> {
+    // `query-activities --brief` prints `package/activity` lines (Android 7+)
+    let mut packages: Vec<String> = adb_shell(
+        serial,
+        "cmd package query-activities --brief -a android.intent.action.MAIN -c android.intent.category.LAUNCHER",
+    )
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|l| l.trim().split_once('/').map(|(pkg, _)| pkg.to_string()))
+    .collect();
+    if packages.is_empty() {
+        packages = adb_shell(serial, "pm list packages")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("package:").map(str::to_string))
+            .collect();
+    }
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+/// Packages installed by the user rather than with the system image.
+fn list_third_party_packages(serial: &str) -> HashSet<String> {
+    adb_shell(serial, "pm list packages -3")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("package:").map(str::to_string))
+        .collect()
+}
+
+fn cache_file(serial: &str) -> PathBuf {
+    let safe: String = serial.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    persist::data(CACHE_DIR).join("packages").join(format!("{}.json", safe))
+}
+
+fn load_cache(serial: &str) -> Option<Vec<PackageEntry>> {
+    persist::read_json(cache_file(serial)).ok().flatten()
+}
+
+fn save_cache(serial: &str, entries: &[PackageEntry]) {
+    let path = cache_file(serial);
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(data) = serde_json::to_string_pretty(entries) {
+        let _ = persist::write_atomic(path, data);
+    }
+}
+
+/// Reads label and icon with the host `aapt` tool from a pulled copy of the APK.
+/// Returns `(None, None)` when aapt is not installed or the APK is too large.
+fn resolve_label_icon(serial: &str, package: &str) -> (Option<String>, Option<PathBuf>) {
+    let Some(apk) = adb_shell(serial, &format!("pm path {}", package))
+        .and_then(|out| out.lines().find_map(|l| l.trim().strip_prefix("package:").map(str::to_string)))
+    else {
+        return (None, None);
+    };
+    let size = adb_shell(serial, &format!("stat -c %s {}", apk))
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .unwrap_or(u64::MAX);
+    if size > MAX_APK_PULL_BYTES {
+        return (None, None);
+    }
+    let tmp_dir = persist::data(CACHE_DIR).join("tmp");
+    let _ = fs::create_dir_all(&tmp_dir);
+    let local_apk = tmp_dir.join(format!("{}.apk", package));
+    let pulled = adb::run(&["-s", serial, "pull", &apk, &local_apk.to_string_lossy()], adb::LONG, None).is_ok_and(|o| o.status.success());
+    if !pulled {
+        return (None, None);
+    }
+    let badging = Command::new("aapt")
+        .arg("dump").arg("badging").arg(&local_apk)
+        .stderr(Stdio::null())
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default();
+    let value_of = |line: &str| line.split('\'').nth(1).map(str::to_string);
+    let label = badging
+        .lines()
+        .find(|l| l.starts_with("application-label:"))
+        .and_then(value_of);
+    // Highest density PNG icon; adaptive (XML) icons can't be shown
+    let icon_entry = badging
+        .lines()
+        .filter(|l| l.starts_with("application-icon-"))
+        .filter_map(value_of)
+        .rfind(|p| p.ends_with(".png"));
+    let icon = icon_entry.and_then(|entry| {
+        let mut archive = zip::ZipArchive::new(fs::File::open(&local_apk).ok()?).ok()?;
+        let mut bytes = Vec::new();
+        archive.by_name(&entry).ok()?.read_to_end(&mut bytes).ok()?;
+        let icon_dir = persist::data(CACHE_DIR).join("icons");
+        fs::create_dir_all(&icon_dir).ok()?;
+        let path = icon_dir.join(format!("{}.png", package));
+        fs::write(&path, bytes).ok()?;
+        Some(path)
+    });
+    let _ = fs::remove_file(&local_apk);
+    (label, icon)
+}
+
+enum CatalogUpdate {
+    List(String, Vec<PackageEntry>),
+    Done(String),
+    /// Label and icon of one package of a device.
+    Resolved(String, String, Option<String>, Option<PathBuf>),
+    /// No host `aapt`, so nothing on the device can be resolved.
+    NoAapt(String),
+}
+
+/// Per-device list of launchable packages, persisted in the cache directory.
+/// Labels and icons are only read for the packages the picker shows, since
+/// each one costs an APK pull.
+pub struct PackageCatalog {
+    lists: HashMap<String, Vec<PackageEntry>>,
+    loading: HashSet<String>,
+    /// Packages handed to the resolver of each device since the last refresh.
+    requested: HashMap<String, HashSet<String>>,
+    resolvers: HashMap<String, Sender<String>>,
+    no_aapt: bool,
+    tx: Sender<CatalogUpdate>,
+    rx: Receiver<CatalogUpdate>,
+}
+
+impl Default for PackageCatalog {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            lists: HashMap::new(),
+            loading: HashSet::new(),
+            requested: HashMap::new(),
+            resolvers: HashMap::new(),
+            no_aapt: false,
+            tx,
+            rx,
+        }
+    }
+}
+
+impl PackageCatalog {
+    pub fn get(&self, serial: &str) -> Option<&[PackageEntry]> {
+        self.lists.get(serial).map(Vec::as_slice)
+    }
+
+    pub fn is_loading(&self, serial: &str) -> bool {
+        self.loading.contains(serial)
+    }
+
+    /// Packages of `serial` whose label is being read.
+    pub fn resolving(&self, serial: &str) -> usize {
+        if self.no_aapt {
+            return 0;
+        }
+        let (Some(requested), Some(list)) = (self.requested.get(serial), self.lists.get(serial)) else { return 0 };
+        list.iter().filter(|e| !e.resolved && requested.contains(&e.package)).count()
+    }
+
+    /// Loads the cached list, or queries the device when there is none or
+    /// `refresh` is set. Labels already resolved are kept across refreshes.
+    pub fn request(&mut self, serial: &str, refresh: bool) {
+        if self.loading.contains(serial) {
+            return;
+        }
+        if !refresh {
+            if self.lists.contains_key(serial) {
+                return;
+            }
+            if let Some(cached) = load_cache(serial) {
+                self.lists.insert(serial.to_string(), cached);
+                return;
+            }
+        }
+        self.loading.insert(serial.to_string());
+        // Entries left unresolved may be requested again
+        self.requested.remove(serial);
+        let tx = self.tx.clone();
+        let serial = serial.to_string();
+        let previous = self.lists.get(&serial).cloned().or_else(|| load_cache(&serial)).unwrap_or_default();
+        thread::spawn(move || {
+            let known: HashMap<_, _> = previous.into_iter().map(|e| (e.package.clone(), e)).collect();
+            let third_party = list_third_party_packages(&serial);
+            let entries: Vec<PackageEntry> = list_launchable_packages(&serial)
+                .into_iter()
+                .map(|package| {
+                    let mut entry = known.get(&package).cloned().unwrap_or(PackageEntry {
+                        package,
+                        label: None,
+                        icon: None,
+                        resolved: false,
+                        third_party: None,
+                    });
+                    // An empty list most likely means the query failed
+                    entry.third_party = (!third_party.is_empty()).then(|| third_party.contains(&entry.package));
+                    entry
+                })
+                .collect();
+            save_cache(&serial, &entries);
+            let _ = tx.send(CatalogUpdate::List(serial.clone(), entries));
+            let _ = tx.send(CatalogUpdate::Done(serial));
+        });
+    }
+
+    /// Reads the label and icon of `packages` of `serial` in the background,
+    /// one at a time, skipping those already resolved or requested.
+    pub fn resolve(&mut self, serial: &str, packages: &[String]) {
+        if self.no_aapt {
+            return;
+        }
+        let Some(list) = self.lists.get(serial) else { return };
+        let requested = self.requested.entry(serial.to_string()).or_default();
+        let wanted: Vec<&String> = packages
+            .iter()
+            .filter(|p| list.iter().any(|e| &e.package == *p && !e.resolved) && !requested.contains(*p))
+            .collect();
+        if wanted.is_empty() {
+            return;
+        }
+        let resolver = self.resolvers.entry(serial.to_string()).or_insert_with(|| {
+            let (queue, jobs) = mpsc::channel::<String>();
+            let (tx, serial) = (self.tx.clone(), serial.to_string());
+            thread::spawn(move || {
+                if Command::new("aapt").arg("version").stdout(Stdio::null()).stderr(Stdio::null()).status().is_err() {
+                    let _ = tx.send(CatalogUpdate::NoAapt(serial));
+                    return;
+                }
+                for package in jobs {
+                    let (label, icon) = resolve_label_icon(&serial, &package);
+                    let _ = tx.send(CatalogUpdate::Resolved(serial.clone(), package, label, icon));
+                }
+            });
+            queue
+        });
+        for package in wanted {
+            requested.insert(package.clone());
+            let _ = resolver.send(package.clone());
+        }
+    }
+
+    pub fn poll(&mut self) {
+        let mut resolved = HashSet::new();
+        while let Ok(update) = self.rx.try_recv() {
+            match update {
+                CatalogUpdate::List(serial, entries) => {
+                    self.lists.insert(serial, entries);
+                }
+                CatalogUpdate::Done(serial) => {
+                    self.loading.remove(&serial);
+                }
+                CatalogUpdate::Resolved(serial, package, label, icon) => {
+                    let Some(entry) = self.lists.get_mut(&serial).and_then(|l| l.iter_mut().find(|e| e.package == package)) else { continue };
+                    entry.label = label;
+                    entry.icon = icon;
+                    entry.resolved = true;
+                    resolved.insert(serial);
+                }
+                CatalogUpdate::NoAapt(serial) => {
+                    // Without aapt leave entries unresolved so a later run can retry
+                    self.no_aapt = true;
+                    self.resolvers.remove(&serial);
+                }
+            }
+        }
+        for serial in resolved {
+            if let Some(list) = self.lists.get(&serial) {
+                save_cache(&serial, list);
+            }
+        }
+    }
+}
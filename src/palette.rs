@@ -0,0 +1,42 @@
+/// Scores `text` against `query` as a case-insensitive subsequence match;
+/// consecutive and word-start matches rank higher. `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut qi = 0;
+    let mut prev_match: Option<usize> = None;
+    for (ti, &c) in text.iter().enumerate() {
+        if qi < query.len() && c == query[qi] {
+            score += 1;
+            if prev_match == Some(ti.wrapping_sub(1)) {
+                score += 3;
+            }
+            if ti == 0 || !text[ti - 1].is_alphanumeric() {
+                score += 5;
+            }
+            prev_match = Some(ti);
+            qi += 1;
+        }
+    }
+    (qi == query.len()).then(|| score - text.len() as i32 / 10)
+}
+
+/// State of the Ctrl+K command palette.
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    pub query: String,
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+}
@@ -0,0 +1,109 @@
+use crate::settings::SETTINGS_PATH;
+use crate::{DEFAULT_DEVICE_CONFIG_PATH, DEVICE_CONFIG_PATH, advanced, device_state, groups, handoff, history, journal, launches, logs, packages, persist, presets, recordings, rules, stats, tools};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Folder created under the platform config directory.
+const APP_DIR: &str = "scrcpy-rust-gui";
+
+/// Data files copied over from the working directory on first use, with
+/// their backups. The originals stay, so an older build still finds them.
+const MIGRATED_FILES: [&str; 13] = [
+    SETTINGS_PATH,
+    DEVICE_CONFIG_PATH,
+    presets::PRESETS_PATH,
+    advanced::ADVANCED_PATH,
+    stats::STATS_PATH,
+    recordings::LIBRARY_PATH,
+    rules::RULES_PATH,
+    groups::GROUPS_PATH,
+    device_state::DEVICE_STATE_PATH,
+    handoff::HANDOFF_PATH,
+    history::HISTORY_PATH,
+    journal::JOURNAL_PATH,
+    launches::LAUNCHES_PATH,
+];
+
+/// Folders moved along, when they can be renamed in place.
+const MIGRATED_DIRS: [&str; 4] = [logs::LOGS_DIR, packages::CACHE_DIR, tools::TOOLS_DIR, "recordings"];
+
+/// `%APPDATA%` on Windows, `~/Library/Application Support` on macOS and
+/// `$XDG_CONFIG_HOME` or `~/.config` elsewhere.
+pub fn config_dir() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").filter(|h| !h.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").filter(|a| !a.is_empty()).map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library").join("Application Support"))
+    } else {
+        // The spec ignores relative values
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).filter(|p| p.is_absolute()).or_else(|| home().map(|h| h.join(".config")))
+    };
+    base.map(|b| b.join(APP_DIR))
+}
+
+/// Brings the data files of an older run from `old` into `dir`, unless `dir`
+/// already has settings. Returns what was brought over.
+fn migrate(old: &Path, dir: &Path) -> Vec<String> {
+    if dir.join(SETTINGS_PATH).exists() || !old.join(SETTINGS_PATH).exists() {
+        return Vec::new();
+    }
+    let mut moved = Vec::new();
+    for name in MIGRATED_FILES {
+        let from = old.join(name);
+        if from.is_file() {
+            match fs::copy(&from, dir.join(name)) {
+                Ok(_) => moved.push(name.to_string()),
+                Err(e) => eprintln!("Failed to copy {}: {}", from.display(), e),
+            }
+        }
+        let backup = persist::backup_path(&from);
+        if backup.is_file() {
+            let _ = fs::copy(&backup, persist::backup_path(dir.join(name)));
+        }
+    }
+    for name in MIGRATED_DIRS {
+        let (from, to) = (old.join(name), dir.join(name));
+        if from.is_dir() && !to.exists() {
+            match fs::rename(&from, &to) {
+                Ok(()) => moved.push(format!("{}/", name)),
+                Err(e) => eprintln!("Failed to move {}, it stays where it is: {}", from.display(), e),
+            }
+        }
+    }
+    moved
+}
+
+/// Keeps the data files in the platform config directory however the app
+/// was launched, by resolving them against it with `persist::data`, and
+/// brings over the files of an older run from the working directory. With
+/// `portable` everything stays in the working directory. Returns a notice
+/// to show when files were brought over.
+pub fn use_config_dir(portable: bool) -> Option<String> {
+    if portable {
+        return None;
+    }
+    let Some(dir) = config_dir() else {
+        eprintln!("No config directory found; using the working directory.");
+        return None;
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("Failed to create {}: {}; using the working directory.", dir.display(), e);
+        return None;
+    }
+    let old = env::current_dir().unwrap_or_default();
+    let exe_dir = env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+    // The bundled default config ships next to the executable
+    let bundled = exe_dir.iter().chain([&old]).map(|d| d.join(DEFAULT_DEVICE_CONFIG_PATH)).find(|p| p.is_file());
+    let same_dir = old.canonicalize().ok() == dir.canonicalize().ok();
+    let moved = if same_dir { Vec::new() } else { migrate(&old, &dir) };
+    if let Some(bundled) = bundled
+        && !same_dir
+        && let Err(e) = fs::copy(&bundled, dir.join(DEFAULT_DEVICE_CONFIG_PATH))
+    {
+        eprintln!("Failed to copy {}: {}", bundled.display(), e);
+    }
+    persist::set_data_dir(dir.clone());
+    (!moved.is_empty()).then(|| format!("✅ Brought {} over from {} to {}.", moved.join(", "), old.display(), dir.display()))
+}
@@ -0,0 +1,89 @@
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Folder the data files are kept in; the working directory until set.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Files that failed to load, left as they are until recovered.
+static HELD: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Makes `data` resolve against `dir`; only the first call counts.
+pub fn set_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+/// Where the data file or folder `name`, e.g. `settings.json`, is kept.
+pub fn data(name: impl AsRef<Path>) -> PathBuf {
+    match DATA_DIR.get() {
+        Some(dir) => dir.join(name),
+        None => name.as_ref().to_path_buf(),
+    }
+}
+
+/// Refuses writes to `path` until `release`, so the defaults used after a
+/// failed load never replace the file or rotate it over its backup.
+pub fn hold(path: impl AsRef<Path>) {
+    HELD.lock().unwrap().push(path.as_ref().to_path_buf());
+}
+
+pub fn release(path: impl AsRef<Path>) {
+    HELD.lock().unwrap().retain(|p| p != path.as_ref());
+}
+
+/// `<path>.bak`, the single backup generation kept for a persisted file.
+pub fn backup_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// Writes via a temp file and atomic rename so a crash never leaves a
+/// half-written file; the previous version is kept as the backup.
+pub fn write_atomic(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    if HELD.lock().unwrap().iter().any(|p| p == path) {
+        return Err(io::Error::other(format!("{} is read-only until it is recovered", path.display())));
+    }
+    let tmp = temp_path(path);
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(data.as_ref())?;
+        file.sync_all()?;
+    }
+    if path.exists() {
+        fs::copy(path, backup_path(path))?;
+    }
+    fs::rename(&tmp, path)
+}
+
+/// Reads a JSON file: `Ok(None)` if it does not exist, `Err` with the reason if
+/// it exists but cannot be read or parsed.
+pub fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Option<T>, String> {
+    match fs::read_to_string(path.as_ref()) {
+        Ok(data) => serde_json::from_str(&data).map(Some).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn has_backup(path: impl AsRef<Path>) -> bool {
+    backup_path(path).exists()
+}
+
+/// Puts the backup generation back in place of a corrupted file.
+pub fn restore_backup(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let data = fs::read(backup_path(path))?;
+    let tmp = temp_path(path);
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)
+}
@@ -0,0 +1,45 @@
+use crate::cmdline;
+use crate::session::CompanionSpec;
+use serde::{Deserialize, Serialize};
+
+/// Relay/loopback setup a device config can declare, e.g. scrcpy writing to a
+/// v4l2 loopback device that ffmpeg streams from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineConfig {
+    /// Loopback device passed to scrcpy as `--v4l2-sink`, e.g. `/dev/video2`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub v4l2_sink: Option<String>,
+    /// Processes started after scrcpy and stopped with it.
+    #[serde(default)]
+    pub aux: Vec<AuxProcess>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuxProcess {
+    pub name: String,
+    /// Command line template; `{serial}` and `{v4l2_sink}` are substituted.
+    pub command: String,
+}
+
+impl PipelineConfig {
+    /// Extra scrcpy arguments needed by the pipeline.
+    pub fn scrcpy_args(&self) -> Vec<String> {
+        self.v4l2_sink.iter().filter(|s| !s.trim().is_empty()).map(|s| format!("--v4l2-sink={}", s.trim())).collect()
+    }
+
+    /// Expands the auxiliary command templates for a device.
+    pub fn companions(&self, serial: &str) -> Result<Vec<CompanionSpec>, String> {
+        let sink = self.v4l2_sink.as_deref().unwrap_or_default().trim();
+        self.aux
+            .iter()
+            .map(|aux| {
+                let line = aux.command.replace("{serial}", serial).replace("{v4l2_sink}", sink);
+                let args = cmdline::split(&line).map_err(|e| format!("{}: {}", aux.name, e))?;
+                if args.is_empty() {
+                    return Err(format!("{}: empty command", aux.name));
+                }
+                Ok(CompanionSpec { name: aux.name.clone(), args, on_stop: None })
+            })
+            .collect()
+    }
+}
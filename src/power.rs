@@ -0,0 +1,98 @@
+use crate::confirm::{Confirm, ConfirmedAction};
+use crate::job::Job;
+use crate::{ScrcpyGuiApp, adb};
+use eframe::egui;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerAction {
+    Reboot,
+    Recovery,
+    Bootloader,
+    PowerOff,
+}
+
+impl PowerAction {
+    pub const ALL: [PowerAction; 4] = [PowerAction::Reboot, PowerAction::Recovery, PowerAction::Bootloader, PowerAction::PowerOff];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PowerAction::Reboot => "Reboot",
+            PowerAction::Recovery => "Reboot to recovery",
+            PowerAction::Bootloader => "Reboot to bootloader",
+            PowerAction::PowerOff => "Power off",
+        }
+    }
+
+    /// Arguments after `adb -s <serial>`. adb has no power-off target, so
+    /// that one goes through the device's own `reboot -p`.
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            PowerAction::Reboot => &["reboot"],
+            PowerAction::Recovery => &["reboot", "recovery"],
+            PowerAction::Bootloader => &["reboot", "bootloader"],
+            PowerAction::PowerOff => &["shell", "reboot", "-p"],
+        }
+    }
+}
+
+pub fn run(serial: &str, action: PowerAction) -> Result<(), String> {
+    let mut args = vec!["-s", serial];
+    args.extend(action.args());
+    let output = adb::run(&args, adb::SHORT, None)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// A reboot or power off sent to a device.
+pub struct PowerJob {
+    serial: String,
+    action: PowerAction,
+    job: Job<Result<(), String>>,
+}
+
+impl ScrcpyGuiApp {
+    /// Reboot menu of a device; every entry asks first unless confirmations
+    /// for rebooting are turned off.
+    pub(crate) fn show_power_menu(&mut self, ui: &mut egui::Ui, serial: &str) {
+        let busy = self.power_jobs.iter().any(|p| p.serial == serial);
+        ui.add_enabled_ui(!busy, |ui| {
+            ui.menu_button("⏻ Reboot", |ui| {
+                for action in PowerAction::ALL {
+                    if ui.button(action.label()).clicked() {
+                        ui.close_menu();
+                        let message = format!("{} {}? A running session is stopped first.", action.label(), self.device_display_name(serial));
+                        self.confirm_then(Confirm::RebootDevice, message, ConfirmedAction::Power(serial.to_string(), action));
+                    }
+                }
+            });
+        });
+    }
+
+    pub(crate) fn run_power_action(&mut self, serial: &str, action: PowerAction) {
+        if self.sessions.is_running(serial) {
+            self.end_session(serial);
+        }
+        let job_serial = serial.to_string();
+        self.power_jobs.push(PowerJob { serial: serial.to_string(), action, job: Job::spawn(move || run(&job_serial, action)) });
+    }
+
+    pub(crate) fn poll_power_jobs(&mut self) {
+        let mut finished = Vec::new();
+        self.power_jobs.retain(|power| match power.job.poll() {
+            Some(result) => {
+                finished.push((power.serial.clone(), power.action, result));
+                false
+            }
+            None => true,
+        });
+        for (serial, action, result) in finished {
+            match result {
+                Ok(()) => self.notices.global(format!("✅ {}: {} sent.", serial, action.label())),
+                Err(e) => self.notices.global(format!("⚠️ {} failed on {}: {}", action.label(), serial, e)),
+            }
+        }
+    }
+}
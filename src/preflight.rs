@@ -0,0 +1,210 @@
+use crate::job::Job;
+use crate::{ScrcpyGuiApp, adb, lint};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use sysinfo::Disks;
+
+/// A shell round trip slower than this hints at a flaky connection.
+const SLOW_SHELL: Duration = Duration::from_secs(2);
+/// Free space under which a recording may be cut short.
+const LOW_SPACE: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Ok,
+    Warning,
+    /// scrcpy can't start; only "Check again" is offered.
+    Failed,
+}
+
+pub struct Check {
+    pub label: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+pub struct Report {
+    pub checks: Vec<Check>,
+    /// Fetched when the device had none cached.
+    pub capabilities: Option<lint::Capabilities>,
+}
+
+impl Report {
+    fn worst(&self) -> Status {
+        if self.checks.iter().any(|c| c.status == Status::Failed) {
+            Status::Failed
+        } else if self.checks.iter().any(|c| c.status == Status::Warning) {
+            Status::Warning
+        } else {
+            Status::Ok
+        }
+    }
+}
+
+fn check(label: &'static str, status: Status, detail: impl Into<String>) -> Check {
+    Check { label, status, detail: detail.into() }
+}
+
+fn shell_check(serial: &str) -> Check {
+    let started = Instant::now();
+    let answered = adb::run(&["-s", serial, "shell", "echo", "ok"], adb::SHORT, None)
+        .map(|out| out.status.success() && String::from_utf8_lossy(&out.stdout).trim() == "ok");
+    let took = started.elapsed();
+    match answered {
+        Ok(true) if took > SLOW_SHELL => check("Shell", Status::Warning, format!("answered slowly ({} ms)", took.as_millis())),
+        Ok(true) => check("Shell", Status::Ok, format!("answered in {} ms", took.as_millis())),
+        Ok(false) => check("Shell", Status::Failed, "the device doesn't answer adb shell; check authorization"),
+        Err(e) => check("Shell", Status::Failed, e),
+    }
+}
+
+/// Wakefulness from `dumpsys power` and the lock screen from `dumpsys
+/// window`, whose field names vary between Android versions.
+fn screen_check(serial: &str) -> Check {
+    let power = adb::shell(serial, "dumpsys power").unwrap_or_default();
+    let wakefulness = power.lines().find_map(|l| l.trim().strip_prefix("mWakefulness=").map(str::to_string));
+    let window = adb::shell(serial, "dumpsys window").unwrap_or_default();
+    let locked = ["mDreamingLockscreen=true", "mShowingLockscreen=true", "isKeyguardShowing=true"].iter().any(|f| window.contains(f));
+    match wakefulness.as_deref() {
+        None => check("Screen", Status::Warning, "state unknown"),
+        Some("Awake") if locked => check("Screen", Status::Warning, "on but locked; unlock it to interact"),
+        Some("Awake") => check("Screen", Status::Ok, "on and unlocked"),
+        Some(state) => check("Screen", Status::Warning, format!("{}; the mirror stays black until the device wakes", state.to_lowercase())),
+    }
+}
+
+/// Free space on the disk holding `dir`, the one with the longest matching
+/// mount point.
+fn storage_check(dir: &Path) -> Check {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+    let Some(disk) = disks.iter().filter(|d| dir.starts_with(d.mount_point())).max_by_key(|d| d.mount_point().as_os_str().len()) else {
+        return check("Storage", Status::Warning, format!("no disk found for {}", dir.display()));
+    };
+    let free = disk.available_space();
+    let gib = free as f64 / (1024.0 * 1024.0 * 1024.0);
+    if free < LOW_SPACE {
+        check("Storage", Status::Warning, format!("only {:.1} GiB free for the recording", gib))
+    } else {
+        check("Storage", Status::Ok, format!("{:.1} GiB free for the recording", gib))
+    }
+}
+
+fn encoder_check(caps: &lint::Capabilities, fetched: bool) -> Check {
+    let source = if fetched { "listed" } else { "cached" };
+    match caps.video_encoders.len() {
+        0 => check("Encoders", Status::Warning, "scrcpy couldn't list the encoders; encoder options can't be checked"),
+        n => check("Encoders", Status::Ok, format!("{} video encoder(s) {}", n, source)),
+    }
+}
+
+/// Runs the checks in order; the rest are skipped once the shell fails.
+pub fn probe(binary: &str, serial: &str, record_dir: Option<&Path>, cached: Option<&lint::Capabilities>) -> Report {
+    let mut checks = vec![shell_check(serial)];
+    if checks[0].status == Status::Failed {
+        return Report { checks, capabilities: None };
+    }
+    checks.push(screen_check(serial));
+    if let Some(dir) = record_dir {
+        checks.push(storage_check(dir));
+    }
+    let capabilities = cached.is_none().then(|| lint::fetch_capabilities(binary, serial));
+    checks.push(encoder_check(cached.or(capabilities.as_ref()).unwrap_or(&lint::Capabilities::default()), capabilities.is_some()));
+    Report { checks, capabilities }
+}
+
+/// A launch waiting on its pre-flight check.
+pub struct Preflight {
+    serial: String,
+    record: Option<PathBuf>,
+    segment_minutes: Option<u32>,
+    job: Option<Job<Report>>,
+    report: Option<Report>,
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn start_preflight(&mut self, serial: String, record: Option<PathBuf>, segment_minutes: Option<u32>) {
+        let mut preflight = Preflight { serial, record, segment_minutes, job: None, report: None };
+        self.run_preflight_probe(&mut preflight);
+        self.preflight = Some(preflight);
+    }
+
+    fn run_preflight_probe(&self, preflight: &mut Preflight) {
+        let binary = self.scrcpy_binary_for(&preflight.serial);
+        let serial = preflight.serial.clone();
+        let record_dir = preflight.record.as_ref().map(|p| p.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new(".")).to_path_buf());
+        let cached = self.capabilities.get(&serial).cloned();
+        preflight.report = None;
+        preflight.job = Some(Job::spawn(move || probe(&binary, &serial, record_dir.as_deref(), cached.as_ref())));
+    }
+
+    /// Launches straight away when every check passed; otherwise the
+    /// summary stays up until the user starts anyway or cancels.
+    pub(crate) fn poll_preflight(&mut self) {
+        let Some(preflight) = &mut self.preflight else { return };
+        let Some(mut report) = preflight.job.as_ref().and_then(Job::poll) else { return };
+        preflight.job = None;
+        if let Some(caps) = report.capabilities.take() {
+            self.capabilities.insert(preflight.serial.clone(), caps);
+        }
+        if report.worst() == Status::Ok {
+            let preflight = self.preflight.take().expect("checked above");
+            self.start_session_on(&preflight.serial, preflight.record, preflight.segment_minutes);
+        } else {
+            preflight.report = Some(report);
+        }
+    }
+
+    pub(crate) fn show_preflight_window(&mut self, ctx: &egui::Context) {
+        let Some(preflight) = &self.preflight else { return };
+        let mut open = true;
+        let (mut start, mut again) = (false, false);
+        let name = self.device_display_name(&preflight.serial);
+        egui::Window::new("🛫 Pre-flight check").open(&mut open).collapsible(false).resizable(false).show(ctx, |ui| {
+            let Some(report) = &preflight.report else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Checking {}…", name));
+                });
+                return;
+            };
+            ui.label(format!("{} may not mirror as expected:", name));
+            egui::Grid::new("preflight").num_columns(3).show(ui, |ui| {
+                for check in &report.checks {
+                    let (icon, color) = match check.status {
+                        Status::Ok => ("✅", egui::Color32::GREEN),
+                        Status::Warning => ("⚠️", egui::Color32::ORANGE),
+                        Status::Failed => ("❌", egui::Color32::RED),
+                    };
+                    ui.colored_label(color, icon);
+                    ui.strong(check.label);
+                    ui.label(&check.detail);
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.add_enabled(report.worst() != Status::Failed, egui::Button::new("▶ Start anyway")).clicked() {
+                    start = true;
+                }
+                if ui.button("↻ Check again").clicked() {
+                    again = true;
+                }
+            });
+            if ui.checkbox(&mut self.settings.preflight_check, "Check devices before starting").changed() {
+                let _ = self.settings.save();
+            }
+        });
+        if start {
+            let preflight = self.preflight.take().expect("shown above");
+            self.start_session_on(&preflight.serial, preflight.record, preflight.segment_minutes);
+        } else if again {
+            let mut preflight = self.preflight.take().expect("shown above");
+            self.run_preflight_probe(&mut preflight);
+            self.preflight = Some(preflight);
+        } else if !open {
+            self.preflight = None;
+        }
+    }
+}
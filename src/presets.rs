@@ -0,0 +1,46 @@
+use crate::persist;
+use serde::{Deserialize, Serialize};
+
+/// Kept next to `scrcpy_device_config.json`.
+pub const PRESETS_PATH: &str = "scrcpy_presets.json";
+
+/// A named set of scrcpy arguments applied on top of the device config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub args: String,
+    /// Surveillance: record in segments of this many minutes and restart
+    /// scrcpy whenever it exits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_minutes: Option<u32>,
+}
+
+pub fn builtin() -> Vec<Preset> {
+    [
+        ("Low latency", "--max-size=1024 --video-bit-rate=4M --max-fps=60 --audio-buffer=40", None),
+        ("Recording", "--video-bit-rate=16M --max-fps=60 --no-playback-audio", None),
+        ("Presentation", "--fullscreen --stay-awake --show-touches --no-control", None),
+        ("Surveillance", "--video-source=camera --camera-fps=15 --no-window --no-audio --no-control", Some(15)),
+    ]
+    .into_iter()
+    .map(|(name, args, segment_minutes)| Preset { name: name.to_string(), args: args.to_string(), segment_minutes })
+    .collect()
+}
+
+/// Presets from `scrcpy_presets.json`; the built-in ones are written there on
+/// first use so they can be edited. `Err` when the file can't be parsed.
+pub fn load() -> Result<Vec<Preset>, String> {
+    if let Some(presets) = persist::read_json(persist::data(PRESETS_PATH))? {
+        return Ok(presets);
+    }
+    let presets = builtin();
+    if let Ok(data) = serde_json::to_string_pretty(&presets) {
+        let _ = persist::write_atomic(persist::data(PRESETS_PATH), data);
+    }
+    Ok(presets)
+}
+
+pub fn save(presets: &[Preset]) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(presets).map_err(|e| e.to_string())?;
+    persist::write_atomic(persist::data(PRESETS_PATH), data).map_err(|e| e.to_string())
+}
@@ -0,0 +1,171 @@
+use crate::groups_window::profile_combo;
+use crate::session::Session;
+use crate::{ScrcpyGuiApp, cmdline, parse_config_args};
+use eframe::egui;
+
+/// Where the second session of a comparison mirrors from.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SecondDisplay {
+    /// The main display again, with a second encoder (not every device has one).
+    SameDisplay,
+    /// `--new-display` (scrcpy 3.0+).
+    #[default]
+    NewDisplay,
+    /// `--display-id`, for devices with a secondary display.
+    DisplayId(u32),
+}
+
+/// One side of a comparison: a device config entry plus extra options.
+#[derive(Debug, Clone, Default)]
+pub struct CompareSide {
+    /// Config entry name; empty uses the config applied in the main panel.
+    pub profile: String,
+    pub extra_args: String,
+}
+
+/// Two sessions of the same device with different profiles, started and
+/// stopped together.
+#[derive(Default)]
+pub struct ProfileCompare {
+    pub sides: [CompareSide; 2],
+    pub second_display: SecondDisplay,
+    /// Serial and the two sessions while the pair runs.
+    running: Option<(String, [Session; 2])>,
+}
+
+impl ProfileCompare {
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((_, sessions)) = &mut self.running {
+            sessions.iter_mut().for_each(Session::stop);
+        }
+        self.running = None;
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// Arguments of one side: its profile merged with its extra options, the
+    /// display choice on the second side, and a title telling them apart.
+    fn compare_args(&self, serial: &str, index: usize) -> Result<Vec<String>, String> {
+        let side = &self.profile_compare.sides[index];
+        let base = match side.profile.trim() {
+            "" => self.crop_args.clone().unwrap_or_default(),
+            name => self.device_config.get(name).map(|c| c.scrcpy_args.clone()).ok_or_else(|| format!("no profile named {}", name))?,
+        };
+        let merged = cmdline::merge_args(&base, &side.extra_args);
+        let mut args = self.build_scrcpy_args_with(serial, parse_config_args(&merged).as_deref());
+        args.retain(|a| !a.starts_with("--window-title") && !a.starts_with("--record"));
+        if index == 1 {
+            let display = match self.profile_compare.second_display {
+                SecondDisplay::SameDisplay => None,
+                SecondDisplay::NewDisplay => Some("--new-display".to_string()),
+                SecondDisplay::DisplayId(id) => Some(format!("--display-id={}", id)),
+            };
+            if let Some(display) = display {
+                args.retain(|a| !a.starts_with("--new-display") && !a.starts_with("--display-id") && !a.starts_with("--video-source"));
+                args.push(display);
+            }
+        }
+        let label = if side.profile.is_empty() { "current config" } else { side.profile.as_str() };
+        args.push(format!("--window-title={} {}: {}", ["A", "B"][index], self.device_display_name(serial), label));
+        Ok(args)
+    }
+
+    fn start_profile_compare(&mut self, serial: &str) {
+        let binary = self.scrcpy_binary_for(serial);
+        let spawn = |index| self.compare_args(serial, index).and_then(|args| Session::spawn(&binary, serial, args).map_err(|e| e.to_string()));
+        let spawned = spawn(0).and_then(|mut first| match spawn(1) {
+            Ok(second) => Ok([first, second]),
+            Err(e) => {
+                first.stop();
+                Err(e)
+            }
+        });
+        match spawned {
+            Ok(sessions) => self.profile_compare.running = Some((serial.to_string(), sessions)),
+            Err(e) => self.notices.device(serial, format!("⚠️ Failed to start the comparison: {}", e)),
+        }
+    }
+
+    /// Stops both sessions as soon as one of them ends.
+    pub(crate) fn poll_profile_compare(&mut self) {
+        let Some((serial, sessions)) = &mut self.profile_compare.running else { return };
+        let devices = self.devices.clone();
+        let Some(ended) = sessions.iter_mut().position(|s| s.reap(&devices) || s.check_health(&devices)) else { return };
+        let error = sessions[ended].last_error().map(|e| format!(": {}", e)).unwrap_or_default();
+        let message = format!("⚠️ Comparison stopped, side {} ended{}", ["A", "B"][ended], error);
+        let serial = serial.clone();
+        self.profile_compare.stop();
+        self.notices.device(&serial, message);
+    }
+
+    pub(crate) fn show_profile_compare_window(&mut self, ctx: &egui::Context) {
+        if !self.show_profile_compare {
+            return;
+        }
+        let mut open = true;
+        let mut start = None;
+        let mut profiles: Vec<String> = self.device_config.keys().cloned().collect();
+        profiles.sort();
+        let serial = self.selected_serial();
+        egui::Window::new("⚖ Compare profiles").open(&mut open).resizable(false).show(ctx, |ui| {
+            ui.label("Mirrors the selected device twice, each side with its own profile, to compare encoders or bit rates.");
+            let compare = &mut self.profile_compare;
+            ui.add_enabled_ui(!compare.is_running(), |ui| {
+                egui::Grid::new("profile_compare").num_columns(3).show(ui, |ui| {
+                    for (i, side) in compare.sides.iter_mut().enumerate() {
+                        ui.strong(["A", "B"][i]);
+                        profile_combo(ui, &format!("compare_profile_{}", i), &mut side.profile, &profiles, "(current config)");
+                        ui.add(egui::TextEdit::singleline(&mut side.extra_args).hint_text("extra options, e.g. --video-bit-rate=2M").desired_width(240.0));
+                        ui.end_row();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("B shows:");
+                    let display = &mut compare.second_display;
+                    ui.radio_value(display, SecondDisplay::NewDisplay, "New virtual display")
+                        .on_hover_text("--new-display, scrcpy 3.0+");
+                    ui.radio_value(display, SecondDisplay::SameDisplay, "Same display")
+                        .on_hover_text("Needs a device that can run two encoders at once");
+                    let mut id = match display {
+                        SecondDisplay::DisplayId(id) => Some(*id),
+                        _ => None,
+                    };
+                    if ui.radio(id.is_some(), "Display id").clicked() && id.is_none() {
+                        id = Some(1);
+                    }
+                    if let Some(id) = &mut id {
+                        ui.add(egui::DragValue::new(id).range(0..=64));
+                        *display = SecondDisplay::DisplayId(*id);
+                    }
+                });
+            });
+            ui.horizontal(|ui| match (&compare.running, &serial) {
+                (Some((running, _)), _) => {
+                    ui.colored_label(egui::Color32::GREEN, format!("● Comparing on {}", running));
+                    if ui.button("⏹ Stop both").clicked() {
+                        compare.stop();
+                    }
+                }
+                (None, Some(serial)) => {
+                    if ui.button("▶ Start pair").clicked() {
+                        start = Some(serial.clone());
+                    }
+                }
+                (None, None) => {
+                    ui.weak("Select a device first.");
+                }
+            });
+        });
+        if let Some(serial) = start {
+            self.start_profile_compare(&serial);
+        }
+        if !open {
+            self.profile_compare.stop();
+        }
+        self.show_profile_compare = open;
+    }
+}
@@ -0,0 +1,392 @@
+use crate::persist;
+use crate::session::Session;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const LIBRARY_PATH: &str = "recordings.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordingKind {
+    Video,
+    Audio,
+}
+
+/// A note taken while recording, e.g. "crash after login".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub at: DateTime<Local>,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingEntry {
+    pub path: PathBuf,
+    pub serial: String,
+    pub kind: RecordingKind,
+    pub started_at: DateTime<Local>,
+    pub duration_secs: u64,
+    pub bytes: u64,
+    /// Bookmarks as offsets in seconds from the start of the recording.
+    #[serde(default)]
+    pub marks: Vec<(u64, String)>,
+}
+
+fn format_offset(secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+impl RecordingEntry {
+    /// `<recording>.marks.txt`, one `HH:MM:SS note` line per bookmark.
+    pub fn marks_path(&self) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(".marks.txt");
+        PathBuf::from(name)
+    }
+
+    fn write_marks(&self) -> std::io::Result<()> {
+        let text: String = self.marks.iter().map(|(secs, note)| format!("{}  {}\n", format_offset(*secs), note)).collect();
+        std::fs::write(self.marks_path(), text)
+    }
+
+    /// ffmpeg FFMETADATA with one chapter per bookmark, each lasting until the
+    /// next one (or the end of the recording).
+    fn ffmetadata(&self) -> String {
+        let mut text = ";FFMETADATA1\n".to_string();
+        for (i, (start, note)) in self.marks.iter().enumerate() {
+            let end = self.marks.get(i + 1).map(|m| m.0).unwrap_or(self.duration_secs.max(start + 1));
+            text.push_str(&format!("[CHAPTER]\nTIMEBASE=1/1\nSTART={}\nEND={}\ntitle={}\n", start, end, note.replace(['\n', '='], " ")));
+        }
+        text
+    }
+
+    /// `<stem>_chapters.<ext>` next to the recording.
+    pub fn chapters_path(&self) -> PathBuf {
+        let stem = self.path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let ext = self.path.extension().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        self.path.with_file_name(format!("{}_chapters.{}", stem, ext))
+    }
+
+    /// Writes a copy of the recording with the bookmarks as chapters, using
+    /// ffmpeg from PATH. Returns the path of the new file.
+    pub fn embed_chapters(&self) -> Result<PathBuf, String> {
+        let meta = self.path.with_extension("ffmetadata");
+        std::fs::write(&meta, self.ffmetadata()).map_err(|e| e.to_string())?;
+        let out = self.chapters_path();
+        let result = std::process::Command::new("ffmpeg")
+            .arg("-y").arg("-loglevel").arg("error")
+            .arg("-i").arg(&self.path)
+            .arg("-i").arg(&meta)
+            .args(["-map_metadata", "1", "-map_chapters", "1", "-map", "0", "-codec", "copy"])
+            .arg(&out)
+            .output();
+        let _ = std::fs::remove_file(&meta);
+        match result {
+            Ok(output) if output.status.success() => Ok(out),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) => Err(format!("ffmpeg failed: {}", e)),
+        }
+    }
+}
+
+/// Index of the recordings made through the GUI, persisted in `recordings.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingLibrary {
+    pub entries: Vec<RecordingEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioFormat {
+    Opus,
+    Flac,
+    Wav,
+}
+
+impl AudioFormat {
+    pub const ALL: [AudioFormat; 3] = [AudioFormat::Opus, AudioFormat::Flac, AudioFormat::Wav];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "wav",
+        }
+    }
+
+    /// scrcpy `--audio-codec` able to produce this container.
+    pub fn codec(self) -> &'static str {
+        match self {
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "raw",
+        }
+    }
+}
+
+/// Container of a mirrored session recorded with `--record`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VideoFormat {
+    Mp4,
+    Mkv,
+}
+
+impl VideoFormat {
+    pub const ALL: [VideoFormat; 2] = [VideoFormat::Mp4, VideoFormat::Mkv];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            VideoFormat::Mp4 => "mp4",
+            VideoFormat::Mkv => "mkv",
+        }
+    }
+
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.extension().eq_ignore_ascii_case(ext))
+    }
+}
+
+/// Overlay burned into a profile's recordings after they finish, e.g. for
+/// footage attached to bug reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Watermark {
+    pub device_name: bool,
+    /// Wall-clock time of each frame.
+    pub timestamp: bool,
+    /// Extra text, e.g. a build number.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub text: String,
+}
+
+impl Default for Watermark {
+    fn default() -> Self {
+        Self { device_name: true, timestamp: true, text: String::new() }
+    }
+}
+
+impl Watermark {
+    /// drawtext text with expansion; `started_at` anchors the frame timestamps.
+    fn drawtext(&self, device_name: &str, started_at: DateTime<Local>) -> String {
+        // Literal text must not start an expansion
+        let literal = |s: &str| s.replace('\\', "\\\\").replace('%', "\\%");
+        let mut parts = Vec::new();
+        if self.device_name {
+            parts.push(literal(device_name));
+        }
+        if !self.text.trim().is_empty() {
+            parts.push(literal(self.text.trim()));
+        }
+        if self.timestamp {
+            parts.push(format!("%{{pts:localtime:{}:%Y-%m-%d %H\\:%M\\:%S}}", started_at.timestamp()));
+        }
+        parts.join("  ")
+    }
+
+    /// `<stem>_watermark.<ext>` next to the recording.
+    pub fn output_path(recording: &Path) -> PathBuf {
+        let stem = recording.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let ext = recording.extension().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        recording.with_file_name(format!("{}_watermark.{}", stem, ext))
+    }
+
+    /// Writes a copy of the recording with the overlay in the bottom-left
+    /// corner, re-encoding the video with ffmpeg from PATH. Returns the path
+    /// of the new file.
+    pub fn burn_in(&self, recording: &Path, device_name: &str, started_at: DateTime<Local>) -> Result<PathBuf, String> {
+        // ffmpeg runs in the recording's folder so the filter only names files,
+        // which avoids escaping drive letters and separators in the filter string
+        let dir = recording.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let name = |path: &Path| path.file_name().map(PathBuf::from).unwrap_or_default();
+        let text_file = recording.with_extension("overlay.txt");
+        std::fs::write(&text_file, self.drawtext(device_name, started_at)).map_err(|e| e.to_string())?;
+        let out = Self::output_path(recording);
+        let filter = format!(
+            "drawtext=textfile={}:fontcolor=white:fontsize=h/30:box=1:boxcolor=black@0.5:boxborderw=8:x=16:y=h-th-16",
+            name(&text_file).display()
+        );
+        let result = std::process::Command::new("ffmpeg")
+            .current_dir(dir)
+            .arg("-y").arg("-loglevel").arg("error")
+            .arg("-i").arg(name(recording))
+            .arg("-vf").arg(filter)
+            .args(["-c:a", "copy"])
+            .arg(name(&out))
+            .output();
+        let _ = std::fs::remove_file(&text_file);
+        match result {
+            Ok(output) if output.status.success() => Ok(out),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) => Err(format!("ffmpeg failed: {}", e)),
+        }
+    }
+}
+
+/// A scrcpy process writing a recording file.
+pub struct ActiveRecording {
+    pub session: Session,
+    pub path: PathBuf,
+    pub kind: RecordingKind,
+    pub started_at: DateTime<Local>,
+    /// Started together with the mirroring session and stopped with it.
+    pub linked: bool,
+}
+
+/// Per-device timing of a synchronized group recording, relative to the
+/// moment the group was started.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupMemberTiming {
+    pub serial: String,
+    pub file: PathBuf,
+    pub spawn_offset_ms: u64,
+    /// When scrcpy reported that recording started; `None` if it never did.
+    pub start_offset_ms: Option<u64>,
+}
+
+/// Recordings of several devices started together under one shared timestamp.
+pub struct GroupRecording {
+    pub stamp: String,
+    pub started_at: DateTime<Local>,
+    pub started: std::time::Instant,
+    pub members: Vec<ActiveRecording>,
+}
+
+impl GroupRecording {
+    pub fn timings(&self) -> Vec<GroupMemberTiming> {
+        self.members
+            .iter()
+            .map(|m| GroupMemberTiming {
+                serial: m.session.serial.clone(),
+                file: m.path.clone(),
+                spawn_offset_ms: (m.session.started_at - self.started).as_millis() as u64,
+                start_offset_ms: m
+                    .session
+                    .first_output_containing("Recording started")
+                    .map(|at| (at - self.started).as_millis() as u64),
+            })
+            .collect()
+    }
+
+    /// Writes `group_<stamp>.json` next to the recordings, for aligning footage.
+    pub fn write_metadata(&self, dir: &str) -> std::io::Result<PathBuf> {
+        let meta = serde_json::json!({
+            "stamp": self.stamp,
+            "started_at": self.started_at.to_rfc3339(),
+            "members": self.timings(),
+        });
+        let path = Path::new(dir).join(format!("group_{}.json", self.stamp));
+        persist::write_atomic(&path, serde_json::to_string_pretty(&meta).map_err(std::io::Error::other)?)?;
+        Ok(path)
+    }
+}
+
+fn file_safe(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+/// `<dir>/<serial>_<timestamp>.<ext>`, with the serial made file-name safe.
+pub fn recording_path(dir: &str, serial: &str, ext: &str) -> PathBuf {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    Path::new(dir).join(format!("{}_{}.{}", file_safe(serial), stamp, ext))
+}
+
+/// Where a device type records, from the `recording` entry of its device
+/// config, e.g. `{"path": "{dir}/watches/{model}_{timestamp}", "format": "Mkv"}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingTemplate {
+    /// Placeholders `{dir}` (the recordings folder), `{serial}`, `{model}`,
+    /// `{label}` and `{timestamp}`. Empty keeps the default file name.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    /// Container when the path has no video extension of its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<VideoFormat>,
+}
+
+impl RecordingTemplate {
+    pub fn expand(&self, dir: &str, serial: &str, model: &str, label: &str, default_format: VideoFormat) -> PathBuf {
+        let format = self.format.unwrap_or(default_format);
+        if self.path.trim().is_empty() {
+            return recording_path(dir, serial, format.extension());
+        }
+        let path = PathBuf::from(
+            self.path
+                .trim()
+                .replace("{dir}", dir)
+                .replace("{serial}", &file_safe(serial))
+                .replace("{model}", &file_safe(model))
+                .replace("{label}", &file_safe(label))
+                .replace("{timestamp}", &Local::now().format("%Y%m%d-%H%M%S").to_string()),
+        );
+        let has_video_ext = path.extension().and_then(|e| VideoFormat::from_extension(&e.to_string_lossy())).is_some();
+        if has_video_ext {
+            return path;
+        }
+        let mut name = path.into_os_string();
+        name.push(format!(".{}", format.extension()));
+        PathBuf::from(name)
+    }
+}
+
+impl RecordingLibrary {
+    /// An empty library when the file is missing; `Err` when it can't be parsed.
+    pub fn load() -> Result<Self, String> {
+        let mut library: Self = persist::read_json(persist::data(LIBRARY_PATH))?.unwrap_or_default();
+        // Recorded into the relative default folder by older runs
+        for entry in library.entries.iter_mut().filter(|e| e.path.is_relative()) {
+            entry.path = persist::data(&entry.path);
+        }
+        Ok(library)
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(data) => {
+                if let Err(e) = persist::write_atomic(persist::data(LIBRARY_PATH), data) {
+                    eprintln!("Failed to save {}: {}", LIBRARY_PATH, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize recordings: {}", e),
+        }
+    }
+
+    /// Adds a finished recording, reading its size from disk, with the
+    /// bookmarks taken while it ran. Returns the size.
+    pub fn add(&mut self, path: PathBuf, serial: &str, kind: RecordingKind, started_at: DateTime<Local>, duration_secs: u64, marks: &[Bookmark]) -> u64 {
+        let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let marks: Vec<(u64, String)> = marks
+            .iter()
+            .filter_map(|m| {
+                let offset = (m.at - started_at).num_seconds();
+                (0..=duration_secs as i64).contains(&offset).then(|| (offset as u64, m.note.clone()))
+            })
+            .collect();
+        let entry = RecordingEntry {
+            path,
+            serial: serial.to_string(),
+            kind,
+            started_at,
+            duration_secs,
+            bytes,
+            marks,
+        };
+        if !entry.marks.is_empty()
+            && let Err(e) = entry.write_marks()
+        {
+            eprintln!("Failed to write {}: {}", entry.marks_path().display(), e);
+        }
+        self.entries.push(entry);
+        self.save();
+        bytes
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.entries.remove(index);
+            self.save();
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::persist;
+use crate::session::is_wireless_serial;
+use chrono::{DateTime, Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const RULES_PATH: &str = "rules.json";
+
+/// Execution log entries kept in memory.
+const MAX_RULE_EVENTS: usize = 200;
+/// Pause in editing after which the rules are saved.
+pub const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Connection {
+    Any,
+    Usb,
+    Wifi,
+}
+
+impl Connection {
+    pub const ALL: [Connection; 3] = [Connection::Any, Connection::Usb, Connection::Wifi];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Connection::Any => "USB or Wi-Fi",
+            Connection::Usb => "USB",
+            Connection::Wifi => "Wi-Fi",
+        }
+    }
+}
+
+/// "When a device matching `device` connects over `connection` between
+/// `from_hour` and `to_hour`, start mirroring with `profile`."
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    pub name: String,
+    pub enabled: bool,
+    /// Case-insensitive part of the serial or model; empty matches any device.
+    pub device: String,
+    pub connection: Connection,
+    /// Active hours as `[from, to)`, wrapping past midnight when `from > to`.
+    /// Equal values mean all day.
+    pub from_hour: u32,
+    pub to_hour: u32,
+    /// Device config entry to start with; empty uses the detected one.
+    pub profile: String,
+    /// Also record the mirrored session to the recordings folder.
+    pub record: bool,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self {
+            name: "New rule".to_string(),
+            enabled: true,
+            device: String::new(),
+            connection: Connection::Any,
+            from_hour: 0,
+            to_hour: 0,
+            profile: String::new(),
+            record: false,
+        }
+    }
+}
+
+impl Rule {
+    pub fn matches(&self, serial: &str, model: &str, hour: u32) -> bool {
+        let pattern = self.device.trim().to_lowercase();
+        let device_ok = pattern.is_empty()
+            || serial.to_lowercase().contains(&pattern)
+            || model.to_lowercase().contains(&pattern);
+        let connection_ok = match self.connection {
+            Connection::Any => true,
+            Connection::Usb => !is_wireless_serial(serial),
+            Connection::Wifi => is_wireless_serial(serial),
+        };
+        let hour_ok = match self.from_hour.cmp(&self.to_hour) {
+            std::cmp::Ordering::Equal => true,
+            std::cmp::Ordering::Less => (self.from_hour..self.to_hour).contains(&hour),
+            std::cmp::Ordering::Greater => hour >= self.from_hour || hour < self.to_hour,
+        };
+        self.enabled && device_ok && connection_ok && hour_ok
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleEvent {
+    pub at: DateTime<Local>,
+    pub rule: String,
+    pub serial: String,
+    pub outcome: String,
+}
+
+/// Automation rules persisted in `rules.json`, plus the log of what they did.
+#[derive(Debug, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+    pub events: VecDeque<RuleEvent>,
+    /// Last edit not saved yet.
+    edited_at: Option<Instant>,
+}
+
+impl RuleSet {
+    /// No rules when the file is missing; `Err` when it can't be parsed.
+    pub fn load() -> Result<Self, String> {
+        let rules = persist::read_json(persist::data(RULES_PATH))?.unwrap_or_default();
+        Ok(Self { rules, events: VecDeque::new(), edited_at: None })
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let data = serde_json::to_string_pretty(&self.rules).map_err(std::io::Error::other)?;
+        persist::write_atomic(persist::data(RULES_PATH), data)
+    }
+
+    pub fn edited(&mut self) {
+        self.edited_at = Some(Instant::now());
+    }
+
+    pub fn has_unsaved_edits(&self) -> bool {
+        self.edited_at.is_some()
+    }
+
+    /// Saves edits once typing paused for `SAVE_DELAY`, or right away with
+    /// `now`. `None` when there was nothing to save yet.
+    pub fn save_edits(&mut self, now: bool) -> Option<std::io::Result<()>> {
+        let edited_at = self.edited_at?;
+        if !now && edited_at.elapsed() < SAVE_DELAY {
+            return None;
+        }
+        self.edited_at = None;
+        Some(self.save())
+    }
+
+    /// First enabled rule matching a device that just connected.
+    pub fn first_match(&self, serial: &str, model: &str) -> Option<&Rule> {
+        let hour = Local::now().hour();
+        self.rules.iter().find(|r| r.matches(serial, model, hour))
+    }
+
+    pub fn log(&mut self, rule: &str, serial: &str, outcome: impl Into<String>) {
+        if self.events.len() >= MAX_RULE_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(RuleEvent {
+            at: Local::now(),
+            rule: rule.to_string(),
+            serial: serial.to_string(),
+            outcome: outcome.into(),
+        });
+    }
+}
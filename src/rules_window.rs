@@ -0,0 +1,109 @@
+use crate::ScrcpyGuiApp;
+use crate::rules::{self, Connection, Rule};
+use eframe::egui;
+
+impl ScrcpyGuiApp {
+    pub(crate) fn show_rules_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_rules;
+        let mut profiles: Vec<String> = self.device_config.keys().cloned().collect();
+        profiles.sort();
+        egui::Window::new("⚡ Automation rules")
+            .open(&mut open)
+            .default_size([560.0, 420.0])
+            .show(ctx, |ui| {
+                ui.label("Rules are checked when a device connects; the first matching rule starts a session.");
+                // Field edits are saved once typing pauses, added or removed rules right away
+                let (mut changed, mut structural) = (false, false);
+                let mut remove = None;
+                for (i, rule) in self.rules.rules.iter_mut().enumerate() {
+                    ui.push_id(i, |ui| {
+                        egui::Frame::group(ui.style()).show(ui, |ui| {
+                            changed |= rule_editor(ui, rule, &profiles);
+                            if ui.small_button("🗑 Remove").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    });
+                }
+                if let Some(i) = remove {
+                    self.rules.rules.remove(i);
+                    structural = true;
+                }
+                if ui.button("➕ Add rule").clicked() {
+                    self.rules.rules.push(Rule::default());
+                    structural = true;
+                }
+                if changed || structural {
+                    self.rules.edited();
+                }
+                if structural {
+                    self.save_rule_edits(true);
+                }
+                ui.separator();
+                ui.label(egui::RichText::new("Execution log").strong());
+                egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                    if self.rules.events.is_empty() {
+                        ui.label("Nothing has run yet.");
+                    }
+                    for event in self.rules.events.iter().rev() {
+                        ui.label(format!("{}  {} → {}: {}", event.at.format("%H:%M:%S"), event.rule, event.serial, event.outcome));
+                    }
+                });
+            });
+        self.show_rules = open;
+        // Closing the window saves what is left
+        self.save_rule_edits(!open);
+        if self.rules.has_unsaved_edits() {
+            ctx.request_repaint_after(rules::SAVE_DELAY);
+        }
+    }
+
+    pub(crate) fn save_rule_edits(&mut self, now: bool) {
+        if let Some(Err(e)) = self.rules.save_edits(now) {
+            self.notices.global(format!("⚠️ Failed to save rules: {}", e));
+        }
+    }
+}
+
+/// Edits one rule in place; returns whether anything changed.
+fn rule_editor(ui: &mut egui::Ui, rule: &mut Rule, profiles: &[String]) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui.checkbox(&mut rule.enabled, "").changed();
+        changed |= ui.text_edit_singleline(&mut rule.name).changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("When device");
+        changed |= ui.add(egui::TextEdit::singleline(&mut rule.device).hint_text("any").desired_width(120.0))
+            .on_hover_text("Part of the serial or model")
+            .changed();
+        ui.label("connects via");
+        egui::ComboBox::from_id_salt("connection")
+            .selected_text(rule.connection.label())
+            .show_ui(ui, |ui| {
+                for connection in Connection::ALL {
+                    changed |= ui.selectable_value(&mut rule.connection, connection, connection.label()).changed();
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("between");
+        changed |= ui.add(egui::DragValue::new(&mut rule.from_hour).range(0..=23).suffix("h")).changed();
+        ui.label("and");
+        changed |= ui.add(egui::DragValue::new(&mut rule.to_hour).range(0..=23).suffix("h"))
+            .on_hover_text("Same hour as the start means all day")
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("start profile");
+        let selected = if rule.profile.is_empty() { "(detected)".to_string() } else { rule.profile.clone() };
+        egui::ComboBox::from_id_salt("profile").selected_text(selected).show_ui(ui, |ui| {
+            changed |= ui.selectable_value(&mut rule.profile, String::new(), "(detected)").changed();
+            for profile in profiles {
+                changed |= ui.selectable_value(&mut rule.profile, profile.clone(), profile).changed();
+            }
+        });
+        changed |= ui.checkbox(&mut rule.record, "and record").changed();
+    });
+    changed
+}
@@ -0,0 +1,39 @@
+use crate::persist;
+use std::fs;
+
+/// Present while the GUI is starting; left behind when a startup crashed.
+const STARTUP_MARKER: &str = "startup.pending";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Started with `--safe-mode`.
+    Requested,
+    /// The previous run did not finish starting up.
+    PreviousCrash,
+}
+
+impl Reason {
+    pub fn label(self) -> &'static str {
+        match self {
+            Reason::Requested => "started with --safe-mode",
+            Reason::PreviousCrash => "the previous start did not complete",
+        }
+    }
+}
+
+/// Marks the start of startup and decides whether this run is in safe mode.
+pub fn begin_startup(requested: bool) -> Option<Reason> {
+    let marker = persist::data(STARTUP_MARKER);
+    let crashed = fs::metadata(&marker).is_ok();
+    let _ = fs::write(&marker, b"");
+    if requested {
+        Some(Reason::Requested)
+    } else {
+        crashed.then_some(Reason::PreviousCrash)
+    }
+}
+
+/// Startup went fine; the next run starts normally again.
+pub fn finish_startup() {
+    let _ = fs::remove_file(persist::data(STARTUP_MARKER));
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Rounded corners and camera cutout of a device type, in pixels of its
+/// native resolution, e.g. `{"corner_radius": 110, "cutout": {"x": 500,
+/// "y": 30, "width": 80, "height": 80}}` for a centered hole punch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenMask {
+    pub corner_radius: u32,
+    pub cutout: Option<Cutout>,
+}
+
+/// Drawn as a pill, so a square one is a hole punch and a wide one a notch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cutout {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Signed distance from `(x, y)` to a rectangle with rounded corners given
+/// by its center, half size and corner radius; negative inside.
+fn rounded_rect_distance((x, y): (f32, f32), (cx, cy): (f32, f32), (hw, hh): (f32, f32), radius: f32) -> f32 {
+    let qx = (x - cx).abs() - (hw - radius);
+    let qy = (y - cy).abs() - (hh - radius);
+    qx.max(0.0).hypot(qy.max(0.0)) + qx.max(qy).min(0.0) - radius
+}
+
+impl ScreenMask {
+    /// Makes the corners of an RGBA frame transparent and blacks out the
+    /// cutout, with anti-aliased edges. `native` is the screen size from
+    /// `wm size`, to scale from; the cutout is only drawn while the frame is
+    /// in that orientation, since the side it moves to when rotated is not
+    /// known.
+    pub fn apply(&self, rgba: &mut [u8], width: usize, height: usize, native: Option<(u32, u32)>) {
+        let (w, h) = (width as f32, height as f32);
+        let (native_w, native_h) = native.map_or((w, h), |(nw, nh)| (nw as f32, nh as f32));
+        let same_orientation = (w >= h) == (native_w >= native_h);
+        let scale = w.min(h) / native_w.min(native_h).max(1.0);
+        // Covered pixels become transparent with `clear`, black otherwise
+        let mut shade = |(x0, y0): (usize, usize), (x1, y1): (usize, usize), clear: bool, coverage: &dyn Fn(f32, f32) -> f32| {
+            for y in y0..y1.min(height) {
+                for x in x0..x1.min(width) {
+                    let coverage = coverage(x as f32 + 0.5, y as f32 + 0.5);
+                    let pixel = &mut rgba[(y * width + x) * 4..][..4];
+                    if clear {
+                        pixel[3] = (pixel[3] as f32 * (1.0 - coverage)) as u8;
+                    } else {
+                        pixel[..3].iter_mut().for_each(|c| *c = (*c as f32 * (1.0 - coverage)) as u8);
+                    }
+                }
+            }
+        };
+        let radius = (self.corner_radius as f32 * scale).min(w.min(h) / 2.0);
+        if radius >= 1.0 {
+            let outside = |x, y| (rounded_rect_distance((x, y), (w / 2.0, h / 2.0), (w / 2.0, h / 2.0), radius) + 0.5).clamp(0.0, 1.0);
+            let r = radius.ceil() as usize;
+            let (right, bottom) = (width.saturating_sub(r), height.saturating_sub(r));
+            for (x0, y0) in [(0, 0), (right, 0), (0, bottom), (right, bottom)] {
+                shade((x0, y0), (x0 + r, y0 + r), true, &outside);
+            }
+        }
+        if let Some(cutout) = self.cutout.filter(|c| c.width > 0 && c.height > 0 && same_orientation) {
+            let (cx, cy) = ((cutout.x as f32 + cutout.width as f32 / 2.0) * scale, (cutout.y as f32 + cutout.height as f32 / 2.0) * scale);
+            let (hw, hh) = (cutout.width as f32 * scale / 2.0, cutout.height as f32 * scale / 2.0);
+            let inside = |x, y| (0.5 - rounded_rect_distance((x, y), (cx, cy), (hw, hh), hw.min(hh))).clamp(0.0, 1.0);
+            let start = ((cx - hw).floor().max(0.0) as usize, (cy - hh).floor().max(0.0) as usize);
+            shade(start, ((cx + hw).ceil() as usize + 1, (cy + hh).ceil() as usize + 1), false, &inside);
+        }
+    }
+}
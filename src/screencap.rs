@@ -0,0 +1,146 @@
+use crate::adb;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Minimum gap between two screencaps, across all devices.
+const MIN_INTERVAL: Duration = Duration::from_millis(500);
+/// Requests not renewed for this long are dropped.
+const REQUEST_TTL: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Longest side of the stored pictures; callers only show thumbnails.
+const MAX_SIZE: u32 = 360;
+
+pub struct Capture {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+    pub at: Instant,
+    /// Increases with every capture of any device, for texture cache checks.
+    pub seq: u64,
+}
+
+#[derive(Default)]
+struct DeviceState {
+    /// Capture wanted no older than this, and when it was last asked for.
+    wanted: Option<(Duration, Instant)>,
+    latest: Option<Arc<Capture>>,
+    error: Option<String>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Shared {
+    devices: HashMap<String, DeviceState>,
+    seq: u64,
+}
+
+/// One background worker that takes screenshots for every feature that needs
+/// them, rate limited globally and backing off per device after errors.
+/// Callers renew their request each frame and read whatever is cached.
+#[derive(Default)]
+pub struct ScreencapService {
+    shared: Arc<Mutex<Shared>>,
+    started: bool,
+}
+
+impl ScreencapService {
+    /// Asks for a capture of `serial` no older than `max_age`.
+    pub fn request(&mut self, serial: &str, max_age: Duration) {
+        if !self.started {
+            self.started = true;
+            let shared = self.shared.clone();
+            thread::spawn(move || worker(&shared));
+        }
+        let mut shared = self.shared.lock().unwrap();
+        let device = shared.devices.entry(serial.to_string()).or_default();
+        let max_age = device.wanted.map_or(max_age, |(current, at)| if at.elapsed() < REQUEST_TTL { current.min(max_age) } else { max_age });
+        device.wanted = Some((max_age, Instant::now()));
+    }
+
+    pub fn latest(&self, serial: &str) -> Option<Arc<Capture>> {
+        self.shared.lock().unwrap().devices.get(serial).and_then(|d| d.latest.clone())
+    }
+
+    pub fn error(&self, serial: &str) -> Option<String> {
+        self.shared.lock().unwrap().devices.get(serial).and_then(|d| d.error.clone())
+    }
+}
+
+fn worker(shared: &Mutex<Shared>) {
+    loop {
+        let next = {
+            let mut shared = shared.lock().unwrap();
+            let now = Instant::now();
+            shared.devices.retain(|_, d| d.wanted.is_some_and(|(_, at)| now - at < REQUEST_TTL) || d.latest.is_some());
+            // The most overdue device goes first
+            shared
+                .devices
+                .iter()
+                .filter(|(_, d)| d.retry_at.is_none_or(|t| t <= now))
+                .filter_map(|(serial, d)| {
+                    let (max_age, asked) = d.wanted?;
+                    if now - asked >= REQUEST_TTL {
+                        return None;
+                    }
+                    let age = d.latest.as_ref().map_or(Duration::MAX, |c| now - c.at);
+                    (age >= max_age).then(|| (age.saturating_sub(max_age), serial.clone()))
+                })
+                .max()
+                .map(|(_, serial)| serial)
+        };
+        let Some(serial) = next else {
+            thread::sleep(Duration::from_millis(100));
+            continue;
+        };
+        let result = capture(&serial);
+        {
+            let mut shared = shared.lock().unwrap();
+            shared.seq += 1;
+            let seq = shared.seq;
+            let device = shared.devices.entry(serial).or_default();
+            match result {
+                Ok((width, height, rgba)) => {
+                    device.latest = Some(Arc::new(Capture { width, height, rgba, at: Instant::now(), seq }));
+                    device.error = None;
+                    device.failures = 0;
+                    device.retry_at = None;
+                }
+                Err(e) => {
+                    device.error = Some(e);
+                    device.failures += 1;
+                    let backoff = Duration::from_secs(1 << device.failures.min(6)).min(MAX_BACKOFF);
+                    device.retry_at = Some(Instant::now() + backoff);
+                }
+            }
+        }
+        thread::sleep(MIN_INTERVAL);
+    }
+}
+
+/// The PNG printed by `adb exec-out screencap -p`.
+fn screencap_png(serial: &str) -> Result<Vec<u8>, String> {
+    let output = adb::run(&["-s", serial, "exec-out", "screencap", "-p"], adb::SHORT, None)?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Saves a full-size screenshot of `serial` to `path`.
+pub fn save_screenshot(serial: &str, path: &Path) -> Result<(), String> {
+    let png = screencap_png(serial)?;
+    fs::write(path, png).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// `adb exec-out screencap -p`, decoded and scaled down to `MAX_SIZE`.
+fn capture(serial: &str) -> Result<(usize, usize, Vec<u8>), String> {
+    let png = screencap_png(serial)?;
+    let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png).map_err(|e| e.to_string())?;
+    let image = image.thumbnail(MAX_SIZE, MAX_SIZE).to_rgba8();
+    Ok((image.width() as usize, image.height() as usize, image.into_raw()))
+}
@@ -0,0 +1,43 @@
+/// Service name the app's entries are filed under in the OS keychain.
+const SERVICE: &str = "scrcpy-rust-gui";
+
+/// Credentials kept in the OS keychain (Keychain, Credential Manager or the
+/// Secret Service) instead of `settings.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    /// Bearer token sent with the device config download.
+    ConfigToken,
+}
+
+impl Secret {
+    fn account(self) -> &'static str {
+        match self {
+            Secret::ConfigToken => "config-download-token",
+        }
+    }
+}
+
+fn entry(secret: Secret) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, secret.account()).map_err(|e| format!("keychain unavailable: {}", e))
+}
+
+/// The stored value, `None` when nothing is stored.
+pub fn get(secret: Secret) -> Result<Option<String>, String> {
+    match entry(secret)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Stores `value`, or removes the entry when it is empty.
+pub fn set(secret: Secret, value: &str) -> Result<(), String> {
+    let entry = entry(secret)?;
+    if value.is_empty() {
+        return match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        };
+    }
+    entry.set_password(value).map_err(|e| e.to_string())
+}
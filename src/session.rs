@@ -0,0 +1,679 @@
+use crate::handoff::{self, CompanionRecord, SessionRecord};
+use crate::job::Job;
+use crate::journal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Oldest lines are dropped beyond this many captured output lines.
+const MAX_LOG_LINES: usize = 10_000;
+/// How long scrcpy gets to finish a recording and quit before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// One captured output line and when it was read.
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub at: Instant,
+    pub text: String,
+}
+
+pub type SessionLog = Arc<Mutex<VecDeque<LogLine>>>;
+
+pub fn capture_output(stream: impl Read + Send + 'static, log: SessionLog, prefix: Option<String>) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            let text = match &prefix {
+                Some(prefix) => format!("[{}] {}", prefix, line),
+                None => line,
+            };
+            let mut log = log.lock().unwrap();
+            if log.len() >= MAX_LOG_LINES {
+                log.pop_front();
+            }
+            log.push_back(LogLine { at: Instant::now(), text });
+        }
+    });
+}
+
+/// scrcpy defaults used when a session doesn't set its own bit rates.
+const DEFAULT_VIDEO_BIT_RATE: u64 = 8_000_000;
+const DEFAULT_AUDIO_BIT_RATE: u64 = 128_000;
+
+/// Parses scrcpy bit rate values such as `8M`, `800K` or `2000000`.
+pub fn parse_bit_rate(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (digits, factor) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1_000.0),
+        'm' | 'M' => (&value[..value.len() - 1], 1_000_000.0),
+        _ => (value, 1.0),
+    };
+    digits.parse::<f64>().ok().map(|v| (v * factor) as u64)
+}
+
+/// Whether a serial refers to a device connected over adb-over-Wi-Fi.
+pub fn is_wireless_serial(serial: &str) -> bool {
+    serial.contains(':') || serial.contains("._adb-tls-connect.")
+}
+
+/// Session key of an OTG session (`scrcpy --otg`), which is not tied to an
+/// adb serial: `otg` for the only USB device, `otg:<serial>` for a chosen one.
+pub fn otg_key(usb_serial: Option<&str>) -> String {
+    match usb_serial {
+        Some(serial) => format!("otg:{}", serial),
+        None => "otg".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionState {
+    Running,
+    /// The device disappeared from `adb devices` while mirroring.
+    Disconnected,
+    /// scrcpy exited on its own; carries the exit code when known.
+    Exited(Option<i32>),
+}
+
+/// Startup progress of scrcpy, read from its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LaunchStage {
+    PushingServer,
+    OpeningTunnel,
+    WaitingForFrame,
+}
+
+impl LaunchStage {
+    pub fn label(self) -> &'static str {
+        match self {
+            LaunchStage::PushingServer => "Pushing server…",
+            LaunchStage::OpeningTunnel => "Opening tunnel…",
+            LaunchStage::WaitingForFrame => "Waiting for first frame…",
+        }
+    }
+
+    pub fn progress(self) -> f32 {
+        match self {
+            LaunchStage::PushingServer => 0.1,
+            LaunchStage::OpeningTunnel => 0.4,
+            LaunchStage::WaitingForFrame => 0.75,
+        }
+    }
+}
+
+/// An auxiliary process (e.g. an ffmpeg relay) started and stopped together
+/// with a session; its output goes to the session log with a `[name]` prefix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanionSpec {
+    pub name: String,
+    pub args: Vec<String>,
+    /// Command run after the process was killed, to undo its effect on the device.
+    pub on_stop: Option<Vec<String>>,
+}
+
+/// A process started by this GUI, or one left running by a previous run of
+/// it and adopted by pid, without access to its output or exit code.
+enum Process {
+    Child(Child),
+    Adopted(u32),
+}
+
+impl Process {
+    fn pid(&self) -> u32 {
+        match self {
+            Process::Child(child) => child.id(),
+            Process::Adopted(pid) => *pid,
+        }
+    }
+
+    /// `Some(exit code)` once the process is gone.
+    fn try_wait(&mut self, program: &str) -> Option<Option<i32>> {
+        match self {
+            Process::Child(child) => child.try_wait().ok().flatten().map(|status| status.code()),
+            Process::Adopted(pid) => (!handoff::is_alive(*pid, program)).then_some(None),
+        }
+    }
+
+    fn kill(&mut self) {
+        match self {
+            Process::Child(child) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            Process::Adopted(pid) => handoff::kill(*pid),
+        }
+    }
+
+    /// Asks the process to quit and waits up to `STOP_TIMEOUT`, so scrcpy can
+    /// finalize its recording, before killing it. Blocks for that long.
+    fn stop(&mut self, program: &str) {
+        handoff::interrupt(self.pid());
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while Instant::now() < deadline {
+            if self.try_wait(program).is_some() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        self.kill();
+    }
+}
+
+struct Companion {
+    spec: CompanionSpec,
+    process: Process,
+    exited: bool,
+}
+
+/// Companion processes taken out of a session, with their `on_stop` commands.
+type Shutdown = Vec<(Process, Option<Vec<String>>)>;
+
+/// Kills the companions, then undoes their effect on the device. Blocks.
+fn shut_down(companions: Shutdown) {
+    for (mut process, on_stop) in companions {
+        process.kill();
+        if let Some((program, args)) = on_stop.as_ref().and_then(|cmd| cmd.split_first()) {
+            let _ = Command::new(program).args(args).stdout(Stdio::null()).stderr(Stdio::null()).status();
+        }
+    }
+}
+
+/// One scrcpy process launched by the GUI, with what is needed to relaunch it.
+pub struct Session {
+    pub serial: String,
+    pub binary: String,
+    pub args: Vec<String>,
+    pub started_at: Instant,
+    /// When the process was seen exiting or was stopped.
+    pub ended_at: Option<Instant>,
+    pub state: SessionState,
+    /// Captured stdout/stderr of scrcpy.
+    pub log: SessionLog,
+    /// `None` once scrcpy shows the first frame (or has nothing to show).
+    pub launch_stage: Option<LaunchStage>,
+    child: Option<Process>,
+    companions: Vec<Companion>,
+    /// The processes being stopped in the background, see `stop`.
+    stopping: Option<Job<()>>,
+}
+
+impl Session {
+    pub fn spawn(binary: &str, serial: &str, args: Vec<String>) -> std::io::Result<Self> {
+        let mut child = Command::new(binary)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        journal::record(binary, &args);
+        let log = SessionLog::default();
+        if let Some(stdout) = child.stdout.take() {
+            capture_output(stdout, log.clone(), None);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            capture_output(stderr, log.clone(), None);
+        }
+        // OTG pushes no server, so there are no launch stages to follow
+        let launch_stage = (!args.iter().any(|a| a == "--otg")).then_some(LaunchStage::PushingServer);
+        Ok(Self {
+            serial: serial.to_string(),
+            binary: binary.to_string(),
+            args,
+            started_at: Instant::now(),
+            ended_at: None,
+            state: SessionState::Running,
+            log,
+            launch_stage,
+            child: Some(Process::Child(child)),
+            companions: Vec::new(),
+            stopping: None,
+        })
+    }
+
+    /// Takes over a scrcpy process started by a previous run of the GUI, if
+    /// it is still running.
+    pub fn adopt(record: SessionRecord) -> Option<Self> {
+        if !handoff::is_alive(record.pid, &record.binary) {
+            return None;
+        }
+        let age = (chrono::Local::now() - record.started_at).to_std().unwrap_or_default();
+        let started_at = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+        let log = SessionLog::default();
+        log.lock().unwrap().push_back(LogLine {
+            at: Instant::now(),
+            text: format!("Re-attached to scrcpy (pid {}) after a GUI restart; earlier output is not available", record.pid),
+        });
+        let companions = record
+            .companions
+            .into_iter()
+            .map(|c| {
+                let exited = !c.spec.args.first().is_some_and(|program| handoff::is_alive(c.pid, program));
+                Companion { spec: c.spec, process: Process::Adopted(c.pid), exited }
+            })
+            .collect();
+        Some(Self {
+            serial: record.serial,
+            binary: record.binary,
+            args: record.args,
+            started_at,
+            ended_at: None,
+            state: SessionState::Running,
+            log,
+            launch_stage: None,
+            child: Some(Process::Adopted(record.pid)),
+            companions,
+            stopping: None,
+        })
+    }
+
+    /// Process id of scrcpy while it runs.
+    pub fn pid(&self) -> Option<u32> {
+        self.child.as_ref().filter(|_| self.is_running()).map(Process::pid)
+    }
+
+    /// What a later run of the GUI needs to adopt this session; `None` once
+    /// scrcpy is no longer running.
+    pub fn record(&self) -> Option<SessionRecord> {
+        let pid = self.pid()?;
+        let age = chrono::Duration::from_std(self.duration()).unwrap_or_default();
+        Some(SessionRecord {
+            serial: self.serial.clone(),
+            binary: self.binary.clone(),
+            args: self.args.clone(),
+            pid,
+            started_at: chrono::Local::now() - age,
+            companions: self
+                .companions
+                .iter()
+                .filter(|c| !c.exited)
+                .map(|c| CompanionRecord { spec: c.spec.clone(), pid: c.process.pid() })
+                .collect(),
+        })
+    }
+
+    /// Starts an auxiliary process that belongs to this session.
+    pub fn attach_companion(&mut self, spec: CompanionSpec) -> std::io::Result<()> {
+        let (program, args) = spec.args.split_first().ok_or_else(|| std::io::Error::other("empty command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(stdout) = child.stdout.take() {
+            capture_output(stdout, self.log.clone(), Some(spec.name.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            capture_output(stderr, self.log.clone(), Some(spec.name.clone()));
+        }
+        self.companions.push(Companion { spec, process: Process::Child(child), exited: false });
+        Ok(())
+    }
+
+    pub fn companion_names(&self) -> Vec<(String, bool)> {
+        self.companions.iter().map(|c| (c.spec.name.clone(), !c.exited)).collect()
+    }
+
+    /// A new session of the same device and binary with `args`, with the
+    /// companions of this one; scrcpy is stopped again if one fails to start.
+    fn spawn_like(&self, args: Vec<String>) -> std::io::Result<Self> {
+        let mut session = Self::spawn(&self.binary, &self.serial, args)?;
+        for companion in &self.companions {
+            if let Err(e) = session.attach_companion(companion.spec.clone()) {
+                session.stop();
+                return Err(e);
+            }
+        }
+        Ok(session)
+    }
+
+    /// Starts the same command again, e.g. after the device reconnected.
+    pub fn respawn(&self) -> std::io::Result<Self> {
+        self.spawn_like(self.args.clone())
+    }
+
+    /// Like `respawn`, recording to `path` instead of the previous file.
+    pub fn respawn_recording_to(&self, path: &std::path::Path) -> std::io::Result<Self> {
+        let mut args = Vec::new();
+        let mut iter = self.args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--record" || arg == "-r" {
+                iter.next();
+            } else if !arg.starts_with("--record=") {
+                args.push(arg.clone());
+            }
+        }
+        args.push(format!("--record={}", path.display()));
+        self.spawn_like(args)
+    }
+
+    /// Moves `launch_stage` forward from what scrcpy printed so far: the
+    /// server push, the device line from the server once the tunnel is up, and
+    /// the texture size once the first frame is decoded.
+    pub fn update_launch_stage(&mut self) {
+        let Some(current) = self.launch_stage else { return };
+        if !self.is_running() {
+            self.launch_stage = None;
+            return;
+        }
+        let headless = self.args.iter().any(|a| ["--no-video", "--no-window", "--no-playback", "--no-video-playback"].contains(&a.as_str()));
+        let mut stage = current;
+        for line in self.log.lock().unwrap().iter() {
+            let text = line.text.to_lowercase();
+            if text.contains("file pushed") || text.contains("files pushed") {
+                stage = stage.max(LaunchStage::OpeningTunnel);
+            } else if text.starts_with("[server]") && text.contains("device:") {
+                if headless {
+                    self.launch_stage = None;
+                    return;
+                }
+                stage = stage.max(LaunchStage::WaitingForFrame);
+            } else if text.contains("texture:") {
+                self.launch_stage = None;
+                return;
+            }
+        }
+        self.launch_stage = Some(stage);
+    }
+
+    pub fn log_lines(&self) -> Vec<String> {
+        self.log.lock().unwrap().iter().map(|l| l.text.clone()).collect()
+    }
+
+    /// The last error scrcpy printed, e.g. `ERROR: Could not find any ADB device`.
+    pub fn last_error(&self) -> Option<String> {
+        self.log.lock().unwrap().iter().rev().find(|l| l.text.starts_with("ERROR")).map(|l| l.text.clone())
+    }
+
+    /// When scrcpy first printed a line containing `needle`.
+    pub fn first_output_containing(&self, needle: &str) -> Option<Instant> {
+        self.log.lock().unwrap().iter().find(|l| l.text.contains(needle)).map(|l| l.at)
+    }
+
+    /// Output lines reporting that clipboard synchronization failed, which
+    /// happens on Android 14+ with some apps and clipboard restrictions.
+    pub fn clipboard_failures(&self) -> usize {
+        self.log
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| {
+                let text = l.text.to_lowercase();
+                text.contains("clipboard") && ["could not", "fail", "error", "exception"].iter().any(|w| text.contains(w))
+            })
+            .count()
+    }
+
+    /// Value of `--flag=value` or `--flag value` (or a short alias) in the args.
+    fn arg_value(&self, names: &[&str]) -> Option<&str> {
+        let mut iter = self.args.iter();
+        while let Some(arg) = iter.next() {
+            for name in names {
+                if arg == name {
+                    return iter.next().map(String::as_str);
+                }
+                if let Some(v) = arg.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+                    return Some(v);
+                }
+            }
+        }
+        None
+    }
+
+    fn has_arg(&self, name: &str) -> bool {
+        self.args.iter().any(|a| a == name)
+    }
+
+    /// File scrcpy records the session to, if `--record` was passed.
+    pub fn record_path(&self) -> Option<std::path::PathBuf> {
+        self.arg_value(&["--record", "-r"]).map(std::path::PathBuf::from)
+    }
+
+    /// Keyboard and mouse over USB (AOA) without mirroring or adb.
+    pub fn is_otg(&self) -> bool {
+        self.has_arg("--otg")
+    }
+
+    /// Mirroring without keyboard and mouse input (`--no-control`).
+    pub fn is_view_only(&self) -> bool {
+        self.has_arg("--no-control") || self.has_arg("-n")
+    }
+
+    pub fn is_wireless(&self) -> bool {
+        is_wireless_serial(&self.serial)
+    }
+
+    /// Expected network throughput from the configured (or default) bit rates.
+    pub fn estimated_bit_rate(&self) -> u64 {
+        let video = if self.has_arg("--no-video") {
+            0
+        } else {
+            self.arg_value(&["--video-bit-rate", "-b"]).and_then(parse_bit_rate).unwrap_or(DEFAULT_VIDEO_BIT_RATE)
+        };
+        let audio = if self.has_arg("--no-audio") {
+            0
+        } else {
+            self.arg_value(&["--audio-bit-rate"]).and_then(parse_bit_rate).unwrap_or(DEFAULT_AUDIO_BIT_RATE)
+        };
+        video + audio
+    }
+
+    /// Time spent mirroring, up to the end of the session if it is over.
+    pub fn duration(&self) -> Duration {
+        self.ended_at.unwrap_or_else(Instant::now) - self.started_at
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == SessionState::Running
+    }
+
+    /// Watchdog check: notices an exited process or a device that is no longer
+    /// listed by adb, in which case the leftover process is killed.
+    /// Returns whether the session stopped during this check.
+    pub fn check_health(&mut self, connected: &[String]) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        for companion in self.companions.iter_mut().filter(|c| !c.exited) {
+            let program = companion.spec.args.first().map(String::as_str).unwrap_or_default();
+            if let Some(code) = companion.process.try_wait(program) {
+                companion.exited = true;
+                let status = code.map_or_else(|| "an unknown status".to_string(), |c| format!("exit code {}", c));
+                self.log.lock().unwrap().push_back(LogLine {
+                    at: Instant::now(),
+                    text: format!("[{}] exited with {}", companion.spec.name, status),
+                });
+            }
+        }
+        if let Some(child) = &mut self.child
+            && let Some(code) = child.try_wait(&self.binary)
+        {
+            self.exited(code, connected);
+            return true;
+        }
+        // OTG devices need not be authorized in adb, or listed at all
+        if !self.is_otg() && !connected.contains(&self.serial) {
+            self.stop();
+            self.state = SessionState::Disconnected;
+            return true;
+        }
+        false
+    }
+
+    /// Notices right away that scrcpy exited, e.g. because its window was
+    /// closed. Adopted processes are left to `check_health`, as checking them
+    /// runs `ps`.
+    pub fn reap(&mut self, connected: &[String]) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        let Some(Process::Child(child)) = &mut self.child else { return false };
+        let Ok(Some(status)) = child.try_wait() else { return false };
+        self.exited(status.code(), connected);
+        true
+    }
+
+    fn exited(&mut self, code: Option<i32>, connected: &[String]) {
+        self.child = None;
+        let companions = self.take_companions();
+        if !companions.is_empty() {
+            self.stopping = Some(Job::spawn(move || shut_down(companions)));
+        }
+        self.ended_at = Some(Instant::now());
+        self.state = if self.is_otg() || connected.contains(&self.serial) {
+            SessionState::Exited(code)
+        } else {
+            SessionState::Disconnected
+        };
+    }
+
+    /// The companions still running, marked as exited; each one is left
+    /// behind as its bare pid.
+    fn take_companions(&mut self) -> Shutdown {
+        self.companions
+            .iter_mut()
+            .filter(|c| !c.exited)
+            .map(|c| {
+                c.exited = true;
+                let pid = c.process.pid();
+                (std::mem::replace(&mut c.process, Process::Adopted(pid)), c.spec.on_stop.clone())
+            })
+            .collect()
+    }
+
+    /// Stops scrcpy together with its companion processes without blocking:
+    /// waiting for scrcpy to finalize its recording, the kills and the
+    /// `on_stop` commands run in the background until `stop_finished`.
+    pub fn stop(&mut self) {
+        let companions = self.take_companions();
+        let child = self.child.take();
+        if child.is_some() {
+            self.ended_at = Some(Instant::now());
+        }
+        if companions.is_empty() && child.is_none() {
+            return;
+        }
+        let binary = self.binary.clone();
+        self.stopping = Some(Job::spawn(move || {
+            shut_down(companions);
+            if let Some(mut child) = child {
+                child.stop(&binary);
+            }
+        }));
+    }
+
+    /// Whether every process of the session is gone, so its recording is
+    /// complete and the device is free for another scrcpy.
+    pub fn stop_finished(&mut self) -> bool {
+        if self.stopping.as_ref().is_some_and(|job| job.poll().is_none()) {
+            return false;
+        }
+        self.stopping = None;
+        true
+    }
+
+    /// Blocks until `stop_finished`, e.g. when the GUI quits.
+    pub fn wait_stopped(&mut self) {
+        if let Some(job) = self.stopping.take() {
+            job.wait();
+        }
+    }
+}
+
+/// The mirroring sessions started from the GUI, at most one per device serial.
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: BTreeMap<String, Session>,
+    /// `(serial, pid)` of the sessions last written for hand-off.
+    handed_off: Vec<(String, u32)>,
+}
+
+impl SessionManager {
+    pub fn get(&self, serial: &str) -> Option<&Session> {
+        self.sessions.get(serial)
+    }
+
+    pub fn get_mut(&mut self, serial: &str) -> Option<&mut Session> {
+        self.sessions.get_mut(serial)
+    }
+
+    /// Adds a session, returning the previous one of the same device if any.
+    pub fn insert(&mut self, session: Session) -> Option<Session> {
+        self.sessions.insert(session.serial.clone(), session)
+    }
+
+    pub fn remove(&mut self, serial: &str) -> Option<Session> {
+        self.sessions.remove(serial)
+    }
+
+    pub fn is_running(&self, serial: &str) -> bool {
+        self.get(serial).is_some_and(Session::is_running)
+    }
+
+    pub fn any_running(&self) -> bool {
+        self.sessions.values().any(Session::is_running)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// All sessions, including exited ones still awaiting restart or dismissal.
+    pub fn iter(&self) -> impl Iterator<Item = &Session> {
+        self.sessions.values()
+    }
+
+    pub fn serials(&self) -> Vec<String> {
+        self.sessions.keys().cloned().collect()
+    }
+
+    pub fn update_launch_stages(&mut self) {
+        for session in self.sessions.values_mut() {
+            session.update_launch_stage();
+        }
+    }
+
+    /// Adopts the scrcpy processes still running from the previous run of the
+    /// GUI. Returns how many were taken over.
+    pub fn adopt_handoff(&mut self) -> usize {
+        let mut adopted = 0;
+        for record in handoff::load() {
+            if !self.sessions.contains_key(&record.serial)
+                && let Some(session) = Session::adopt(record)
+            {
+                self.insert(session);
+                adopted += 1;
+            }
+        }
+        self.save_handoff();
+        adopted
+    }
+
+    /// Writes the running sessions for the next run of the GUI, if they
+    /// changed since the last write.
+    pub fn save_handoff(&mut self) {
+        let records: Vec<SessionRecord> = self.sessions.values().filter_map(Session::record).collect();
+        let current: Vec<(String, u32)> = records.iter().map(|r| (r.serial.clone(), r.pid)).collect();
+        if current != self.handed_off {
+            handoff::save(&records);
+            self.handed_off = current;
+        }
+    }
+
+    /// Serials of the sessions whose scrcpy exited since the last call.
+    pub fn reap(&mut self, connected: &[String]) -> Vec<String> {
+        self.sessions
+            .values_mut()
+            .filter_map(|session| session.reap(connected).then(|| session.serial.clone()))
+            .collect()
+    }
+
+    /// Serials of the sessions that stopped during this check.
+    pub fn check_health(&mut self, connected: &[String]) -> Vec<String> {
+        self.sessions
+            .values_mut()
+            .filter_map(|session| session.check_health(connected).then(|| session.serial.clone()))
+            .collect()
+    }
+}
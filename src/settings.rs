@@ -0,0 +1,387 @@
+use crate::audio::{AudioCodec, AudioSource};
+use crate::config_source::SourceSettings;
+use crate::confirm::Confirm;
+use crate::geometry::WindowSizing;
+use crate::loans::Loan;
+use crate::persist;
+use crate::recordings::{AudioFormat, VideoFormat};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub const SETTINGS_PATH: &str = "settings.json";
+/// Where dropped files go on the device, like scrcpy's own drag and drop.
+pub const DEFAULT_DROP_FOLDER: &str = "/sdcard/Download/";
+
+/// `--video-codec` and `--video-encoder` for one device.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoEncoderChoice {
+    pub codec: String,
+    /// Empty lets the device pick its default encoder for the codec.
+    pub encoder: String,
+}
+
+/// Camera mirroring (`--video-source=camera`) for one device; empty fields
+/// leave the choice to scrcpy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSource {
+    pub camera_id: String,
+    pub size: String,
+    pub fps: Option<u32>,
+}
+
+impl CameraSource {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["--video-source=camera".to_string()];
+        if !self.camera_id.is_empty() {
+            args.push(format!("--camera-id={}", self.camera_id));
+        }
+        if !self.size.is_empty() {
+            args.push(format!("--camera-size={}", self.size));
+        }
+        if let Some(fps) = self.fps {
+            args.push(format!("--camera-fps={}", fps));
+        }
+        args
+    }
+}
+
+/// A virtual display created for the session instead of mirroring the main
+/// one (`--new-display`, scrcpy 3.0+); zero keeps the device's value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VirtualDisplay {
+    pub width: u32,
+    pub height: u32,
+    pub dpi: u32,
+}
+
+impl VirtualDisplay {
+    pub fn arg(&self) -> String {
+        let size = if self.width > 0 && self.height > 0 { format!("{}x{}", self.width, self.height) } else { String::new() };
+        match (size.is_empty(), self.dpi) {
+            (true, 0) => "--new-display".to_string(),
+            (_, 0) => format!("--new-display={}", size),
+            _ => format!("--new-display={}/{}", size, self.dpi),
+        }
+    }
+}
+
+/// scrcpy window flags and a fixed geometry for one device.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowPlacement {
+    pub fullscreen: bool,
+    pub always_on_top: bool,
+    pub borderless: bool,
+    /// Replaces the title from the window title template when not empty.
+    pub title: String,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl WindowPlacement {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for (on, flag) in [(self.fullscreen, "--fullscreen"), (self.always_on_top, "--always-on-top"), (self.borderless, "--window-borderless")] {
+            if on {
+                args.push(flag.to_string());
+            }
+        }
+        if !self.title.trim().is_empty() {
+            args.push(format!("--window-title={}", self.title.trim()));
+        }
+        for (name, value) in [("--window-x", self.x), ("--window-y", self.y)] {
+            if let Some(value) = value {
+                args.push(format!("{}={}", name, value));
+            }
+        }
+        for (name, value) in [("--window-width", self.width), ("--window-height", self.height)] {
+            if let Some(value) = value {
+                args.push(format!("{}={}", name, value));
+            }
+        }
+        args
+    }
+}
+
+/// Quality options set in the launch panel, replacing the device config's.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityOverrides {
+    pub max_size: Option<u32>,
+    pub video_bit_rate_mbps: Option<f32>,
+    pub max_fps: Option<u32>,
+}
+
+impl QualityOverrides {
+    pub fn args(&self) -> String {
+        let mut args = Vec::new();
+        if let Some(size) = self.max_size {
+            args.push(format!("--max-size={}", size));
+        }
+        if let Some(rate) = self.video_bit_rate_mbps {
+            args.push(format!("--video-bit-rate={}M", rate));
+        }
+        if let Some(fps) = self.max_fps {
+            args.push(format!("--max-fps={}", fps));
+        }
+        args.join(" ")
+    }
+}
+
+/// What is done to the device when its session ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloseBehavior {
+    #[default]
+    Nothing,
+    /// `--power-off-on-close`
+    PowerOff,
+    /// Turn the screen off with a sleep key event once scrcpy is gone.
+    ScreenOff,
+}
+
+impl CloseBehavior {
+    pub const ALL: [CloseBehavior; 3] = [CloseBehavior::Nothing, CloseBehavior::PowerOff, CloseBehavior::ScreenOff];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CloseBehavior::Nothing => "Leave the device as is",
+            CloseBehavior::PowerOff => "Power off (--power-off-on-close)",
+            CloseBehavior::ScreenOff => "Turn the screen off",
+        }
+    }
+}
+
+/// User preferences persisted in `settings.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Remote JSON config for device types.
+    pub config_url: String,
+    pub auto_download_on_start: bool,
+    /// Request only the entry of each connected model
+    /// (`GET <url>?model=<model>`) instead of the whole config.
+    pub config_per_model: bool,
+    /// Backend `config_url` points at and its options.
+    pub config_source: SourceSettings,
+    /// scrcpy executable to use for a given device serial, e.g. an older
+    /// release for a legacy Android 5 device.
+    pub scrcpy_binaries: HashMap<String, String>,
+    /// Default scrcpy executable; empty means `scrcpy` from PATH.
+    pub default_scrcpy_binary: String,
+    /// Extra download URLs per managed tool, one per line, tried in order
+    /// when the official download fails.
+    pub tool_mirrors: HashMap<String, String>,
+    /// Number of session logs kept in the `logs` directory.
+    pub session_log_retention: usize,
+    /// Number of launches kept in the launch history.
+    pub launch_history_size: usize,
+    /// Wait before a kept-alive session is relaunched, and relaunches allowed
+    /// before giving up.
+    pub keep_alive_delay_secs: u32,
+    pub keep_alive_max_retries: u32,
+    /// App version whose "What's new" panel was last dismissed.
+    pub last_seen_version: Option<String>,
+    /// Look for a newer GUI release on GitHub at startup.
+    pub check_for_updates: bool,
+    /// Template for scrcpy's `--window-title`; placeholders `{name}`, `{model}`,
+    /// `{serial}` and `{label}`. Empty leaves scrcpy's default title.
+    pub window_title_template: String,
+    pub recordings_dir: String,
+    /// Device folder that files dropped on the window (other than APKs) are copied to.
+    pub drop_folder: String,
+    pub audio_format: AudioFormat,
+    /// Start a separate audio-only recording whenever mirroring starts.
+    pub record_audio_with_session: bool,
+    /// scrcpy arguments appended after the device config, per device serial.
+    pub extra_args: HashMap<String, String>,
+    /// Max size, bit rate and FPS per device serial.
+    pub quality: HashMap<String, QualityOverrides>,
+    /// Video codec and encoder per device serial.
+    pub video_encoders: HashMap<String, VideoEncoderChoice>,
+    /// Devices mirrored from a camera instead of the display, by serial.
+    pub cameras: HashMap<String, CameraSource>,
+    /// Window flags and geometry per device serial.
+    pub window_placements: HashMap<String, WindowPlacement>,
+    /// Current user and due date per device serial.
+    pub loans: HashMap<String, Loan>,
+    /// Free-text notes per device serial (owner, quirks), for shared device pools.
+    pub device_notes: HashMap<String, String>,
+    /// Nicknames per device serial, e.g. "Pixel 7 – desk", shown instead of the serial.
+    pub device_aliases: HashMap<String, String>,
+    /// Free-form commands run in the device console, newest last.
+    pub shell_history: Vec<String>,
+    /// Package opened with `--start-app` per device serial.
+    pub start_apps: HashMap<String, String>,
+    /// The app picker hides system apps.
+    pub user_apps_only: bool,
+    /// Devices launched on a new virtual display, by serial.
+    pub virtual_displays: HashMap<String, VirtualDisplay>,
+    /// `--display-id` per device serial, for devices with several displays.
+    pub display_ids: HashMap<String, u32>,
+    /// `--turn-screen-off`, `--stay-awake` and `--show-touches` for every launch.
+    pub turn_screen_off: bool,
+    pub stay_awake: bool,
+    pub show_touches: bool,
+    /// Show taps and keys sent from the GUI on screen, for demos.
+    pub echo_input: bool,
+    /// Mask the camera cutout and round the corners in the embedded viewer,
+    /// for device types whose config describes them.
+    pub mask_embedded: bool,
+    /// Probe the device (shell, screen, disk space, encoders) before a
+    /// manual start and show a summary when something looks wrong.
+    pub preflight_check: bool,
+    /// Append every scrcpy launch to `command_journal.txt` as a command line.
+    pub command_journal: bool,
+    /// Default for devices whose profile doesn't set `close_behavior`.
+    pub close_behavior: CloseBehavior,
+    /// Locale for dates and numbers, e.g. `de_DE`; empty follows the system.
+    pub locale: String,
+    pub clock_24h: bool,
+    /// `--no-control`: mirror without input, e.g. on a projector.
+    pub view_only: bool,
+    /// Forward device audio while mirroring (scrcpy 2.0+, Android 11+).
+    pub forward_audio: bool,
+    pub audio_codec: AudioCodec,
+    pub audio_source: AudioSource,
+    /// Port of the local HTTP API (`GET /health`) on 127.0.0.1; 0 turns it off.
+    pub http_api_port: u16,
+    /// "Start" launches `scrcpy --otg` (keyboard and mouse only) instead of mirroring.
+    pub otg_mode: bool,
+    /// Record the mirrored session itself with `--record`.
+    pub record_session: bool,
+    pub video_format: VideoFormat,
+    /// scrcpy window size suggestion applied at launch.
+    pub window_sizing: WindowSizing,
+    /// Usable Wi-Fi throughput in Mbit/s used to warn about saturated links.
+    pub wifi_capacity_mbps: f32,
+    /// Whether a destructive action asks first; missing entries mean yes.
+    #[serde(deserialize_with = "known_confirmations")]
+    pub confirmations: HashMap<Confirm, bool>,
+    /// Operator mode for shared machines: hides settings, editing and shell
+    /// actions. Can also be set directly in the file by an admin.
+    pub operator_locked: bool,
+    /// Salted PBKDF2 of the PIN that unlocks operator mode; empty means the lock
+    /// can only be lifted by editing the file.
+    pub operator_pin_hash: String,
+    /// gnirehtet executable for reverse tethering; empty means from PATH.
+    pub gnirehtet_binary: String,
+    /// adb executable for every device command, e.g. the managed
+    /// platform-tools; empty means from PATH.
+    pub adb_binary: String,
+    /// Devices that use the PC's network connection while mirrored.
+    pub reverse_tethering: HashSet<String>,
+    /// Set while a corrupted settings file awaits restore/reset, or in safe
+    /// mode, so the file on disk is not overwritten with defaults.
+    #[serde(skip)]
+    pub save_blocked: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            config_url: "https://example.com/scrcpy_device_config.json".to_string(),
+            auto_download_on_start: true,
+            config_per_model: false,
+            config_source: SourceSettings::default(),
+            scrcpy_binaries: HashMap::new(),
+            default_scrcpy_binary: String::new(),
+            tool_mirrors: HashMap::new(),
+            session_log_retention: 20,
+            launch_history_size: 50,
+            keep_alive_delay_secs: 5,
+            keep_alive_max_retries: 5,
+            last_seen_version: None,
+            check_for_updates: false,
+            window_title_template: "{name} ({serial})".to_string(),
+            recordings_dir: "recordings".to_string(),
+            drop_folder: DEFAULT_DROP_FOLDER.to_string(),
+            audio_format: AudioFormat::Opus,
+            record_audio_with_session: false,
+            extra_args: HashMap::new(),
+            quality: HashMap::new(),
+            video_encoders: HashMap::new(),
+            cameras: HashMap::new(),
+            window_placements: HashMap::new(),
+            loans: HashMap::new(),
+            device_notes: HashMap::new(),
+            device_aliases: HashMap::new(),
+            shell_history: Vec::new(),
+            start_apps: HashMap::new(),
+            user_apps_only: true,
+            virtual_displays: HashMap::new(),
+            display_ids: HashMap::new(),
+            turn_screen_off: false,
+            stay_awake: false,
+            show_touches: false,
+            echo_input: false,
+            mask_embedded: false,
+            preflight_check: true,
+            command_journal: false,
+            view_only: false,
+            locale: String::new(),
+            clock_24h: true,
+            close_behavior: CloseBehavior::Nothing,
+            forward_audio: true,
+            audio_codec: AudioCodec::Opus,
+            audio_source: AudioSource::Output,
+            otg_mode: false,
+            http_api_port: 0,
+            record_session: false,
+            video_format: VideoFormat::Mp4,
+            window_sizing: WindowSizing::Auto,
+            wifi_capacity_mbps: 100.0,
+            confirmations: HashMap::new(),
+            operator_locked: false,
+            operator_pin_hash: String::new(),
+            gnirehtet_binary: String::new(),
+            adb_binary: String::new(),
+            reverse_tethering: HashSet::new(),
+            save_blocked: false,
+        }
+    }
+}
+
+/// Drops confirmation kinds this version no longer has instead of failing
+/// the whole settings file.
+fn known_confirmations<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<Confirm, bool>, D::Error> {
+    let raw = HashMap::<String, bool>::deserialize(deserializer)?;
+    Ok(raw.into_iter().filter_map(|(kind, ask)| serde_json::from_value(serde_json::Value::String(kind)).ok().map(|kind| (kind, ask))).collect())
+}
+
+impl Settings {
+    /// Defaults when the file is missing; `Err` when it exists but is unreadable,
+    /// so the caller can offer recovery instead of overwriting it.
+    pub fn load() -> Result<Self, String> {
+        persist::read_json(persist::data(SETTINGS_PATH)).map(Option::unwrap_or_default)
+    }
+
+    /// `recordings_dir`, a relative one being kept in the data folder.
+    pub fn recordings_folder(&self) -> String {
+        persist::data(&self.recordings_dir).display().to_string()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        if self.save_blocked {
+            return Err(std::io::Error::other("settings.json is read-only until recovery or safe mode ends"));
+        }
+        let data = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        persist::write_atomic(persist::data(SETTINGS_PATH), data)
+    }
+
+    pub fn needs_confirmation(&self, kind: Confirm) -> bool {
+        self.confirmations.get(&kind).copied().unwrap_or(true)
+    }
+
+    /// Per-serial override, if one is set and non-empty.
+    pub fn scrcpy_binary_override(&self, serial: &str) -> Option<&str> {
+        self.scrcpy_binaries
+            .get(serial)
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+    }
+}
@@ -0,0 +1,605 @@
+use crate::{DEVICE_CONFIG_PATH, DeviceConfig, ScrcpyGuiApp, adb, advanced, changelog, cmdline, journal, locale, logs, operator, persist, secrets};
+use std::collections::BTreeMap;
+use crate::config_source::SourceKind;
+use crate::confirm::Confirm;
+use crate::settings::{CloseBehavior, DEFAULT_DROP_FOLDER};
+use crate::tools::{DownloadState, ManagedTool, ToolDownload};
+use crate::updater::UpdateStatus;
+use eframe::egui;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsCategory {
+    General,
+    Paths,
+    Network,
+    Appearance,
+    Advanced,
+}
+
+impl SettingsCategory {
+    const ALL: [SettingsCategory; 5] = [
+        SettingsCategory::General,
+        SettingsCategory::Paths,
+        SettingsCategory::Network,
+        SettingsCategory::Appearance,
+        SettingsCategory::Advanced,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SettingsCategory::General => "General",
+            SettingsCategory::Paths => "Paths",
+            SettingsCategory::Network => "Network",
+            SettingsCategory::Appearance => "Appearance",
+            SettingsCategory::Advanced => "Advanced",
+        }
+    }
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn show_settings_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_settings;
+        egui::Window::new("⚙ Settings")
+            .open(&mut open)
+            .default_size([520.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(100.0);
+                        for category in SettingsCategory::ALL {
+                            ui.selectable_value(&mut self.settings_category, category, category.label());
+                        }
+                    });
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        egui::ScrollArea::vertical().show(ui, |ui| match self.settings_category {
+                            SettingsCategory::General => self.settings_general(ui),
+                            SettingsCategory::Paths => self.settings_paths(ui),
+                            SettingsCategory::Network => self.settings_network(ui),
+                            SettingsCategory::Appearance => self.settings_appearance(ui),
+                            SettingsCategory::Advanced => self.settings_advanced(ui),
+                        });
+                    });
+                });
+            });
+        self.show_settings = open;
+    }
+
+    fn settings_general(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Keep last session logs:");
+            if ui.add(egui::DragValue::new(&mut self.settings.session_log_retention).range(1..=500))
+                .on_hover_text("Older logs in the logs folder are deleted automatically")
+                .changed()
+            {
+                let _ = self.settings.save();
+                let _ = logs::prune_session_logs(self.settings.session_log_retention);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("When a session ends:");
+            let before = self.settings.close_behavior;
+            egui::ComboBox::from_id_salt("close_behavior")
+                .selected_text(before.label())
+                .show_ui(ui, |ui| {
+                    for behavior in CloseBehavior::ALL {
+                        ui.selectable_value(&mut self.settings.close_behavior, behavior, behavior.label());
+                    }
+                });
+            if self.settings.close_behavior != before {
+                let _ = self.settings.save();
+            }
+        })
+        .response
+        .on_hover_text("Device profiles can override this in Advanced");
+        ui.horizontal(|ui| {
+            ui.label("Keep alive (♻): relaunch after");
+            let mut changed = ui.add(egui::DragValue::new(&mut self.settings.keep_alive_delay_secs).range(1..=600).suffix(" s")).changed();
+            ui.label("at most");
+            changed |= ui.add(egui::DragValue::new(&mut self.settings.keep_alive_max_retries).range(1..=1000).suffix(" times")).changed();
+            if changed {
+                let _ = self.settings.save();
+            }
+        });
+        if ui.checkbox(&mut self.settings.preflight_check, "Check devices before starting")
+            .on_hover_text("Shell, screen state, free disk space and encoders; a summary is shown only when something looks wrong")
+            .changed()
+        {
+            let _ = self.settings.save();
+        }
+        if ui.checkbox(&mut self.settings.command_journal, "Log launches to the command journal")
+            .on_hover_text(format!("Appends each scrcpy launch to {} as a command line you can copy into scripts", journal::JOURNAL_PATH))
+            .changed()
+        {
+            journal::configure(self.settings.command_journal);
+            let _ = self.settings.save();
+        }
+        ui.separator();
+        ui.label(egui::RichText::new("Ask for confirmation before").strong());
+        for kind in Confirm::ALL {
+            let mut ask = self.settings.needs_confirmation(kind);
+            if ui.checkbox(&mut ask, kind.label()).changed() {
+                self.settings.confirmations.insert(kind, ask);
+                let _ = self.settings.save();
+            }
+        }
+        ui.separator();
+        ui.label(egui::RichText::new("Operator mode").strong());
+        ui.label("Hides settings, editing and shell actions so only device selection and start/stop remain.");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.pin_input).password(true).hint_text("PIN").desired_width(100.0));
+            if ui.add_enabled(!self.pin_input.trim().is_empty(), egui::Button::new("Set PIN")).clicked() {
+                self.settings.operator_pin_hash = operator::pin_hash(&self.pin_input);
+                self.pin_input.clear();
+                let _ = self.settings.save();
+            }
+            if ui.add_enabled(!self.settings.operator_pin_hash.is_empty(), egui::Button::new("🔒 Lock now")).clicked() {
+                self.lock_operator_mode();
+            }
+        });
+        ui.separator();
+        ui.label(egui::RichText::new(format!("scrcpy GUI v{}", changelog::APP_VERSION)).strong());
+        if ui.button("📜 Version history").clicked() {
+            self.show_version_history = true;
+        }
+        if ui.add_enabled(self.diagnostics_job.is_none(), egui::Button::new("📦 Export diagnostic bundle"))
+            .on_hover_text("Zip settings (secrets redacted), configs, recent logs and tool versions for bug reports")
+            .clicked()
+        {
+            self.export_diagnostics();
+        }
+        if ui.button("📥 Import profiles from another GUI…")
+            .on_hover_text("QtScrcpy config.ini, guiscrcpy.json, Escrcpy config.json or scripts with scrcpy commands, imported as presets")
+            .clicked()
+        {
+            self.open_migration();
+        }
+        ui.collapsing(format!("Expert settings ({})", advanced::ADVANCED_PATH), |ui| {
+            ui.label("Options without a UI, read at startup. Values in effect:");
+            if let Some(e) = &self.advanced_error {
+                ui.colored_label(egui::Color32::RED, format!("The file was ignored: {}", e));
+            }
+            let mut text = self.advanced.to_toml();
+            ui.add(egui::TextEdit::multiline(&mut text).code_editor().interactive(false).desired_rows(6));
+        });
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.settings.check_for_updates, "Check for GUI updates on start")
+                .on_hover_text("Looks for a newer release of this GUI on GitHub")
+                .changed()
+            {
+                let _ = self.settings.save();
+            }
+            if ui.add_enabled(!self.updater.is_busy(), egui::Button::new("Check now")).clicked() {
+                self.updater.check();
+            }
+        });
+        let mut download = None;
+        match &self.updater.status {
+            UpdateStatus::Idle => {}
+            UpdateStatus::Checking => { ui.label("Checking for updates…"); }
+            UpdateStatus::UpToDate => { ui.label(format!("v{} is the latest version.", changelog::APP_VERSION)); }
+            UpdateStatus::Available(info) => {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, format!("v{} is available.", info.version));
+                    if ui.button("⬇ Download update").clicked() {
+                        download = Some(info.clone());
+                    }
+                });
+            }
+            UpdateStatus::Downloading(version) => { ui.label(format!("Downloading v{}…", version)); }
+            UpdateStatus::Staged(version) => {
+                ui.colored_label(egui::Color32::GREEN, format!("v{} will be installed on next start.", version));
+            }
+            UpdateStatus::Failed(e) => { ui.colored_label(egui::Color32::RED, format!("Update failed: {}", e)); }
+        }
+        if let Some(info) = download {
+            self.updater.download(info);
+        }
+    }
+
+    fn settings_paths(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Recordings folder:");
+            if ui.text_edit_singleline(&mut self.settings.recordings_dir).lost_focus() {
+                let _ = self.settings.save();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Device drop folder:");
+            if ui.add(egui::TextEdit::singleline(&mut self.settings.drop_folder).hint_text(DEFAULT_DROP_FOLDER))
+                .on_hover_text("Files other than APKs dropped on the window are copied here on the selected device")
+                .lost_focus()
+            {
+                if self.settings.drop_folder.trim().is_empty() {
+                    self.settings.drop_folder = DEFAULT_DROP_FOLDER.to_string();
+                }
+                let _ = self.settings.save();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Session logs folder:");
+            ui.label(egui::RichText::new(persist::data(logs::LOGS_DIR).display().to_string()).monospace());
+        });
+        ui.horizontal(|ui| {
+            ui.label("Default scrcpy binary:");
+            if ui.add(egui::TextEdit::singleline(&mut self.settings.default_scrcpy_binary).hint_text("scrcpy (from PATH)"))
+                .lost_focus()
+            {
+                let _ = self.settings.save();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("gnirehtet binary:");
+            if ui.add(egui::TextEdit::singleline(&mut self.settings.gnirehtet_binary).hint_text("gnirehtet (from PATH)"))
+                .on_hover_text("Used for reverse tethering")
+                .lost_focus()
+            {
+                let _ = self.settings.save();
+            }
+        });
+        ui.separator();
+        ui.label(egui::RichText::new("Managed tools").strong());
+        for tool in ManagedTool::ALL {
+            self.managed_tool_row(ui, tool);
+        }
+        ui.separator();
+        ui.label(egui::RichText::new("scrcpy binary per device").strong());
+        if self.settings.scrcpy_binaries.is_empty() {
+            ui.label("No overrides; set one from the device panel.");
+        }
+        let mut remove = None;
+        let mut overrides: Vec<_> = self.settings.scrcpy_binaries.iter().collect();
+        overrides.sort();
+        for (serial, binary) in overrides {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(serial).strong());
+                ui.label(binary);
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(serial.clone());
+                }
+            });
+        }
+        if let Some(serial) = remove {
+            self.settings.scrcpy_binaries.remove(&serial);
+            let _ = self.settings.save();
+            self.detect_and_apply_device_type();
+        }
+    }
+
+    fn managed_tool_row(&mut self, ui: &mut egui::Ui, tool: ManagedTool) {
+        ui.push_id(tool.key(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tool.label()).strong());
+                match tool.installed_binary() {
+                    Some(path) => {
+                        ui.label(path.display().to_string());
+                        if tool == ManagedTool::Scrcpy && ui.small_button("Use as default").clicked() {
+                            self.settings.default_scrcpy_binary = path.display().to_string();
+                            let _ = self.settings.save();
+                        }
+                        let path = path.display().to_string();
+                        if tool == ManagedTool::PlatformTools {
+                            if self.settings.adb_binary == path {
+                                if ui.small_button("Use adb from PATH").clicked() {
+                                    self.settings.adb_binary.clear();
+                                    adb::set_program("");
+                                    let _ = self.settings.save();
+                                }
+                            } else if ui.small_button("Use this adb").on_hover_text("Run every adb command with this executable").clicked() {
+                                adb::set_program(&path);
+                                self.settings.adb_binary = path;
+                                let _ = self.settings.save();
+                            }
+                        }
+                    }
+                    None => { ui.label(egui::RichText::new("not installed").weak()); }
+                }
+            });
+            let mirrors = self.settings.tool_mirrors.entry(tool.key().to_string()).or_default();
+            if ui.add(egui::TextEdit::multiline(mirrors).desired_rows(1).hint_text("Mirror URLs, one per line (optional)"))
+                .lost_focus()
+            {
+                let _ = self.settings.save();
+            }
+            let progress = self.tool_downloads.get(&tool).map(ToolDownload::progress);
+            ui.horizontal(|ui| match progress.as_ref().map(|p| &p.state) {
+                None | Some(DownloadState::Done(_)) | Some(DownloadState::Cancelled) => {
+                    if let Some(DownloadState::Done(dir)) = progress.as_ref().map(|p| &p.state) {
+                        ui.colored_label(egui::Color32::GREEN, format!("Installed to {}", dir.display()));
+                    }
+                    let mut urls: Vec<String> = tool.default_url().into_iter().map(str::to_string).collect();
+                    urls.extend(
+                        self.settings.tool_mirrors.get(tool.key()).into_iter()
+                            .flat_map(|m| m.lines())
+                            .map(str::trim)
+                            .filter(|l| !l.is_empty())
+                            .map(str::to_string),
+                    );
+                    if ui.add_enabled(!urls.is_empty(), egui::Button::new("⬇ Download"))
+                        .on_disabled_hover_text(format!("No official build for {} {}; add a mirror URL", std::env::consts::OS, std::env::consts::ARCH))
+                        .clicked()
+                    {
+                        self.tool_downloads.insert(tool, ToolDownload::start(tool, urls));
+                    }
+                }
+                Some(state) => {
+                    let p = progress.as_ref().unwrap();
+                    let mb = p.downloaded as f32 / 1_000_000.0;
+                    let text = match p.total {
+                        Some(total) => format!("{:.1} / {:.1} MB", mb, total as f32 / 1_000_000.0),
+                        None => format!("{:.1} MB", mb),
+                    };
+                    ui.add(egui::ProgressBar::new(p.fraction().unwrap_or(0.0)).text(text).desired_width(180.0))
+                        .on_hover_text(&p.url);
+                    let download = &self.tool_downloads[&tool];
+                    match state {
+                        DownloadState::Running => {
+                            if ui.button("⏸ Pause").clicked() {
+                                download.pause();
+                            }
+                        }
+                        DownloadState::Paused | DownloadState::Failed(_) => {
+                            if ui.button("▶ Resume").clicked() {
+                                download.resume();
+                            }
+                        }
+                        _ => { ui.spinner(); }
+                    }
+                    if !matches!(state, DownloadState::Extracting) && ui.button("✖ Cancel").clicked() {
+                        download.cancel();
+                    }
+                    if let DownloadState::Failed(e) = state {
+                        ui.colored_label(egui::Color32::RED, e);
+                    }
+                }
+            });
+        });
+    }
+
+    /// Keychain secret of the config source: the HTTP bearer token or the S3
+    /// secret access key.
+    fn config_secret_field(&mut self, ui: &mut egui::Ui, kind: SourceKind) {
+        ui.horizontal(|ui| {
+            ui.label(if kind == SourceKind::S3 { "Secret key:" } else { "Access token:" });
+            let hint = if self.config_token_stored { "•••••• (in keychain)" } else { "none" };
+            ui.add(egui::TextEdit::singleline(&mut self.config_token_input).password(true).hint_text(hint).desired_width(160.0))
+                .on_hover_text("Sent as a bearer token over HTTP or used to sign S3 requests; stored in the OS keychain, not in settings.json");
+            if ui.add_enabled(!self.config_token_input.is_empty(), egui::Button::new("Save")).clicked() {
+                let token = std::mem::take(&mut self.config_token_input);
+                match secrets::set(secrets::Secret::ConfigToken, token.trim()) {
+                    Ok(()) => self.config_token_stored = true,
+                    Err(e) => self.notices.global(format!("⚠️ Failed to store the token in the keychain: {}", e)),
+                }
+            }
+            if self.config_token_stored && ui.button("Remove").clicked() {
+                match secrets::set(secrets::Secret::ConfigToken, "") {
+                    Ok(()) => self.config_token_stored = false,
+                    Err(e) => self.notices.global(format!("⚠️ Failed to remove the token from the keychain: {}", e)),
+                }
+            }
+        });
+    }
+
+    fn settings_network(&mut self, ui: &mut egui::Ui) {
+        let kind = self.settings.config_source.kind;
+        ui.horizontal(|ui| {
+            ui.label("Config source:");
+            egui::ComboBox::from_id_salt("config_source").selected_text(kind.label()).show_ui(ui, |ui| {
+                for option in SourceKind::ALL {
+                    ui.selectable_value(&mut self.settings.config_source.kind, option, option.label());
+                }
+            });
+            if self.settings.config_source.kind != kind {
+                self.config_watch = None;
+                let _ = self.settings.save();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(kind.location_label());
+            let hint = match kind {
+                SourceKind::Http => "Remote JSON config for device types",
+                SourceKind::Git => "Clone URL; pulled into the cache on every download",
+                SourceKind::LocalDir => "Local or network folder; the config is re-read when the file changes",
+                SourceKind::S3 => "e.g. https://s3.eu-west-1.amazonaws.com or a MinIO URL",
+            };
+            if ui.text_edit_singleline(&mut self.settings.config_url).on_hover_text(hint).lost_focus() {
+                let _ = self.settings.save();
+            }
+            if ui.add_enabled(self.config_download_job.is_none(), egui::Button::new("⬇ Download"))
+                .on_hover_text("Download the latest config from the source")
+                .clicked()
+            {
+                self.download_config();
+            }
+        });
+        let source = &mut self.settings.config_source;
+        let mut changed = false;
+        if kind != SourceKind::Http {
+            ui.horizontal(|ui| {
+                ui.label(if kind == SourceKind::S3 { "Object key:" } else { "File:" });
+                changed |= ui.text_edit_singleline(&mut source.file).lost_focus();
+            });
+        }
+        if kind == SourceKind::Git {
+            ui.horizontal(|ui| {
+                ui.label("Branch:");
+                changed |= ui.add(egui::TextEdit::singleline(&mut source.branch).hint_text("default")).lost_focus();
+            });
+        }
+        if kind == SourceKind::S3 {
+            ui.horizontal(|ui| {
+                ui.label("Bucket:");
+                changed |= ui.add(egui::TextEdit::singleline(&mut source.bucket).desired_width(140.0)).lost_focus();
+                ui.label("Region:");
+                changed |= ui.add(egui::TextEdit::singleline(&mut source.region).desired_width(100.0)).lost_focus();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Access key id:");
+                changed |= ui.add(egui::TextEdit::singleline(&mut source.access_key).hint_text("none for a public bucket")).lost_focus();
+            });
+        }
+        if changed {
+            let _ = self.settings.save();
+        }
+        if matches!(kind, SourceKind::Http | SourceKind::S3) {
+            self.config_secret_field(ui, kind);
+        }
+        if ui.checkbox(&mut self.settings.auto_download_on_start, "Auto download config on start")
+            .on_hover_text("Download config at app startup; in per-model mode, whenever a new model connects")
+            .changed()
+        {
+            let _ = self.settings.save();
+        }
+        if ui.checkbox(&mut self.settings.config_per_model, "Download only the entries of connected models")
+            .on_hover_text("Over HTTP requests GET <url>?model=<model> per model; other sources pick the entries out of the whole file. Only those entries are merged, for large fleet configs")
+            .changed()
+        {
+            let _ = self.settings.save();
+        }
+        ui.horizontal(|ui| {
+            ui.label("Usable Wi-Fi throughput:");
+            if ui.add(egui::DragValue::new(&mut self.settings.wifi_capacity_mbps).range(1.0..=10_000.0).suffix(" Mbit/s"))
+                .on_hover_text("Wireless sessions above ~80% of this are flagged")
+                .changed()
+            {
+                let _ = self.settings.save();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("HTTP API port:");
+            let response = ui.add(egui::DragValue::new(&mut self.settings.http_api_port).range(0..=65535))
+                .on_hover_text("Serves GET /health on 127.0.0.1 with the session states; 0 turns it off");
+            if response.drag_stopped() || response.lost_focus() {
+                let _ = self.settings.save();
+                self.restart_http_api();
+            }
+            if let Some(api) = &self.http_api {
+                ui.label(format!("http://127.0.0.1:{}/health", api.port));
+            }
+        });
+    }
+
+    fn settings_appearance(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Locale:");
+            let known = locale::LocaleFormat::is_known(&self.settings.locale);
+            let response = ui.add(egui::TextEdit::singleline(&mut self.settings.locale).hint_text("system").desired_width(80.0))
+                .on_hover_text("Dates and numbers in the recordings, stats and history, e.g. en_GB or de_DE");
+            if !known {
+                ui.colored_label(egui::Color32::RED, "unknown locale, using the system's");
+            }
+            if response.lost_focus() {
+                let _ = self.settings.save();
+            }
+            if ui.checkbox(&mut self.settings.clock_24h, "24-hour clock").changed() {
+                let _ = self.settings.save();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Window title:");
+            if ui.add(egui::TextEdit::singleline(&mut self.settings.window_title_template).hint_text("scrcpy default"))
+                .on_hover_text("Placeholders: {name}, {model}, {serial}, {label}. Leave empty for scrcpy's own title.")
+                .lost_focus()
+            {
+                let _ = self.settings.save();
+            }
+        });
+    }
+
+    fn settings_advanced(&mut self, ui: &mut egui::Ui) {
+        if self.device_config.is_empty() {
+            ui.colored_label(egui::Color32::RED, "Device config missing or invalid!");
+        }
+        ui.label(egui::RichText::new(format!("Device configs ({})", DEVICE_CONFIG_PATH)).strong());
+        ui.label("Keys are matched against the device model; \"default\" is used when nothing matches.");
+        let draft = self.config_draft.get_or_insert_with(|| {
+            let mut entries: Vec<_> = self.device_config.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            entries
+        });
+        let mut remove = None;
+        egui::ScrollArea::vertical().id_salt("device_configs").max_height(260.0).show(ui, |ui| {
+            egui::Grid::new("device_config_grid").num_columns(6).striped(true).show(ui, |ui| {
+                ui.label("Model key");
+                ui.label("Label");
+                ui.label("scrcpy args");
+                ui.label("Watermark");
+                ui.label("On close");
+                ui.end_row();
+                for (i, (key, config)) in draft.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(key).desired_width(100.0));
+                    ui.add(egui::TextEdit::singleline(&mut config.label).desired_width(110.0));
+                    ui.add(egui::TextEdit::singleline(&mut config.scrcpy_args).desired_width(200.0));
+                    ui.horizontal(|ui| {
+                        let mut on = config.watermark.is_some();
+                        if ui.checkbox(&mut on, "").on_hover_text("Burn device name and time into recordings (needs ffmpeg)").changed() {
+                            config.watermark = on.then(Default::default);
+                        }
+                        if let Some(watermark) = &mut config.watermark {
+                            ui.checkbox(&mut watermark.device_name, "Name");
+                            ui.checkbox(&mut watermark.timestamp, "Time");
+                            ui.add(egui::TextEdit::singleline(&mut watermark.text).hint_text("Extra text").desired_width(80.0));
+                        }
+                    });
+                    egui::ComboBox::from_id_salt(("config_close_behavior", i))
+                        .selected_text(config.close_behavior.map_or("Global default", CloseBehavior::label))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut config.close_behavior, None, "Global default");
+                            for behavior in CloseBehavior::ALL {
+                                ui.selectable_value(&mut config.close_behavior, Some(behavior), behavior.label());
+                            }
+                        });
+                    if ui.small_button("🗑").on_hover_text("Remove entry").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(i) = remove {
+            draft.remove(i);
+        }
+        let (mut save, mut revert) = (false, false);
+        ui.horizontal(|ui| {
+            if ui.button("➕ Add entry").clicked() {
+                draft.push((String::new(), DeviceConfig::default()));
+            }
+            save = ui.button("💾 Save").clicked();
+            revert = ui.button("Revert").on_hover_text("Discard unsaved edits").clicked();
+        });
+        if save {
+            match self.save_config_draft() {
+                Ok(()) => self.notices.global(format!("✅ Saved {}.", DEVICE_CONFIG_PATH)),
+                Err(e) => self.notices.global(format!("⚠️ Failed to save {}: {}", DEVICE_CONFIG_PATH, e)),
+            }
+        } else if revert {
+            self.config_draft = None;
+        }
+    }
+
+    /// Validates the edited entries, writes them to disk and makes them the
+    /// running config.
+    fn save_config_draft(&mut self) -> Result<(), String> {
+        let Some(draft) = &self.config_draft else { return Ok(()) };
+        let mut config = BTreeMap::new();
+        for (key, entry) in draft {
+            let key = key.trim();
+            if key.is_empty() {
+                return Err("every entry needs a model key".to_string());
+            }
+            if entry.label.trim().is_empty() {
+                return Err(format!("{}: label is empty", key));
+            }
+            cmdline::split(&entry.scrcpy_args).map_err(|e| format!("{}: {}", key, e))?;
+            if config.insert(key.to_string(), entry.clone()).is_some() {
+                return Err(format!("duplicate model key {}", key));
+            }
+        }
+        let data = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+        persist::write_atomic(persist::data(DEVICE_CONFIG_PATH), data).map_err(|e| e.to_string())?;
+        self.device_config = config.into_iter().collect();
+        self.config_draft = None;
+        self.detect_and_apply_device_type();
+        Ok(())
+    }
+}
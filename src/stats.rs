@@ -0,0 +1,64 @@
+use crate::persist;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const STATS_PATH: &str = "stats.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceStats {
+    pub sessions: u64,
+    pub mirroring_secs: u64,
+    pub recorded_bytes: u64,
+}
+
+/// Usage statistics kept on this machine only; nothing is ever sent anywhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    pub devices: HashMap<String, DeviceStats>,
+    /// Launch count per profile (device config label).
+    pub profiles: HashMap<String, u64>,
+}
+
+impl UsageStats {
+    /// Empty stats when the file is missing; `Err` when it can't be parsed.
+    pub fn load() -> Result<Self, String> {
+        persist::read_json(persist::data(STATS_PATH)).map(Option::unwrap_or_default)
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(data) => {
+                if let Err(e) = persist::write_atomic(persist::data(STATS_PATH), data) {
+                    eprintln!("Failed to save {}: {}", STATS_PATH, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize stats: {}", e),
+        }
+    }
+
+    pub fn record_launch(&mut self, serial: &str, profile: &str) {
+        self.devices.entry(serial.to_string()).or_default().sessions += 1;
+        if !profile.is_empty() {
+            *self.profiles.entry(profile.to_string()).or_default() += 1;
+        }
+        self.save();
+    }
+
+    pub fn record_session_end(&mut self, serial: &str, duration: Duration) {
+        self.devices.entry(serial.to_string()).or_default().mirroring_secs += duration.as_secs();
+        self.save();
+    }
+
+    pub fn record_recording(&mut self, serial: &str, bytes: u64) {
+        self.devices.entry(serial.to_string()).or_default().recorded_bytes += bytes;
+        self.save();
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+        self.save();
+    }
+}
@@ -0,0 +1,169 @@
+use crate::ScrcpyGuiApp;
+use crate::http_api::HttpApi;
+use crate::session::{Session, SessionState};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Wait before trying again after a restart failed, multiplied by the number
+/// of recent failures up to `MAX_BACKOFF` times.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+const MAX_BACKOFF: u32 = 6;
+/// scrcpy exiting sooner than this after a start counts as a failed start.
+const QUICK_EXIT: Duration = Duration::from_secs(30);
+
+/// A session started with a surveillance preset: it records in segments and
+/// is restarted whenever scrcpy exits.
+pub struct Watch {
+    pub segment: Duration,
+    pub segment_started: Instant,
+    /// Segments finished so far.
+    pub segments: u32,
+    /// Restarts after scrcpy exited or the device dropped off.
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    retry_at: Option<Instant>,
+    /// Set while the previous scrcpy quits before the next start; whether
+    /// that start is a restart rather than a new segment.
+    stopping: Option<bool>,
+}
+
+impl Watch {
+    pub fn new(segment_minutes: u32) -> Self {
+        Self {
+            segment: Duration::from_secs(u64::from(segment_minutes.max(1)) * 60),
+            segment_started: Instant::now(),
+            segments: 0,
+            restarts: 0,
+            last_error: None,
+            retry_at: None,
+            stopping: None,
+        }
+    }
+}
+
+impl ScrcpyGuiApp {
+    /// Starts the next segment of watched sessions that ran long enough, and
+    /// restarts the ones that stopped once their device is listed again.
+    pub(crate) fn run_surveillance(&mut self) {
+        for serial in self.surveillance.keys().cloned().collect::<Vec<_>>() {
+            let Some(session) = self.sessions.get(&serial) else {
+                self.surveillance.remove(&serial);
+                continue;
+            };
+            let watch = &self.surveillance[&serial];
+            let restart = match watch.stopping {
+                Some(restart) => restart,
+                None => {
+                    let rotate = session.is_running() && watch.segment_started.elapsed() >= watch.segment;
+                    let restart = !session.is_running()
+                        && self.devices.contains(&serial)
+                        && !self.automation_failures.is_paused(&serial)
+                        && watch.retry_at.is_none_or(|at| Instant::now() >= at);
+                    if !rotate && !restart {
+                        continue;
+                    }
+                    if let Some(session) = self.sessions.get_mut(&serial) {
+                        session.stop();
+                    }
+                    if let Some(watch) = self.surveillance.get_mut(&serial) {
+                        watch.stopping = Some(restart);
+                    }
+                    restart
+                }
+            };
+            // The camera can only be opened by one scrcpy at a time
+            if !self.sessions.get_mut(&serial).is_some_and(Session::stop_finished) {
+                continue;
+            }
+            let rotate = !restart;
+            let path = self.record_path_for(&serial);
+            let Some(mut old) = self.sessions.remove(&serial) else { continue };
+            let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+            let result = std::fs::create_dir_all(dir).and_then(|_| old.respawn_recording_to(&path));
+            let quick_exit = restart && old.duration() < QUICK_EXIT;
+            if quick_exit {
+                let last_line = old.log_lines().pop().unwrap_or_default();
+                let error = format!("scrcpy exited after {}s: {}", old.duration().as_secs(), last_line);
+                self.automation_failures.record(&serial, "Surveillance restart", error);
+            } else if let Err(e) = &result {
+                self.automation_failures.record(&serial, "Surveillance restart", e.to_string());
+            }
+            let backoff = RETRY_DELAY * self.automation_failures.count(&serial).clamp(1, MAX_BACKOFF);
+            let Some(watch) = self.surveillance.get_mut(&serial) else { continue };
+            watch.stopping = None;
+            match result {
+                Ok(new_session) => {
+                    if rotate {
+                        watch.segments += 1;
+                    } else {
+                        watch.restarts += 1;
+                    }
+                    watch.segment_started = Instant::now();
+                    watch.last_error = None;
+                    // A start that fails right away is retried with a growing delay
+                    watch.retry_at = quick_exit.then(|| Instant::now() + backoff);
+                    self.sessions.insert(new_session);
+                    self.finish_session(old);
+                }
+                Err(e) => {
+                    watch.last_error = Some(e.to_string());
+                    watch.retry_at = Some(Instant::now() + backoff);
+                    old.state = SessionState::Exited(None);
+                    self.sessions.insert(old);
+                }
+            }
+        }
+    }
+
+    /// Session states for `GET /health`; unhealthy while a watched session
+    /// is not running.
+    pub(crate) fn publish_health(&self, api: &HttpApi) {
+        let sessions: Vec<_> = self
+            .sessions
+            .iter()
+            .map(|session| {
+                let state = match &session.state {
+                    SessionState::Running => "running",
+                    SessionState::Disconnected => "disconnected",
+                    SessionState::Exited(_) => "exited",
+                };
+                let watch = self.surveillance.get(&session.serial).map(|w| {
+                    serde_json::json!({
+                        "segment": session.record_path(),
+                        "segments": w.segments,
+                        "restarts": w.restarts,
+                        "last_error": w.last_error,
+                    })
+                });
+                serde_json::json!({
+                    "serial": session.serial,
+                    "state": state,
+                    "uptime_secs": session.duration().as_secs(),
+                    "surveillance": watch,
+                })
+            })
+            .collect();
+        let healthy = self.surveillance.keys().all(|serial| self.sessions.is_running(serial));
+        let body = serde_json::json!({ "healthy": healthy, "sessions": sessions });
+        api.set_health(healthy, body.to_string());
+    }
+
+    pub(crate) fn show_surveillance(&mut self, ui: &mut egui::Ui) {
+        for (serial, watch) in &self.surveillance {
+            let left = watch.segment.saturating_sub(watch.segment_started.elapsed()).as_secs();
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "📹 {}: segment {}, next in {}:{:02}, {} restart(s)",
+                    self.device_display_name(serial),
+                    watch.segments + 1,
+                    left / 60,
+                    left % 60,
+                    watch.restarts
+                ));
+                if let Some(e) = &watch.last_error {
+                    ui.colored_label(egui::Color32::RED, format!("Restart failed: {}", e));
+                }
+            });
+        }
+    }
+}
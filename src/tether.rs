@@ -0,0 +1,16 @@
+use crate::session::CompanionSpec;
+
+/// Reverse tethering through gnirehtet: `gnirehtet run` installs the client
+/// if needed, starts the relay and keeps the device's VPN connected to it for
+/// as long as the process lives. `gnirehtet stop` disconnects the device.
+pub fn gnirehtet_companion(binary: &str, serial: &str) -> CompanionSpec {
+    let binary = match binary.trim() {
+        "" => "gnirehtet",
+        b => b,
+    };
+    CompanionSpec {
+        name: "gnirehtet".to_string(),
+        args: vec![binary.to_string(), "run".to_string(), serial.to_string()],
+        on_stop: Some(vec![binary.to_string(), "stop".to_string(), serial.to_string()]),
+    }
+}
@@ -0,0 +1,295 @@
+use crate::persist;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub const TOOLS_DIR: &str = "tools";
+
+/// Attempts per URL (each resuming where the last one stopped) before the
+/// next mirror is tried.
+const ATTEMPTS_PER_URL: usize = 3;
+
+const RUN: u8 = 0;
+const PAUSE: u8 = 1;
+const CANCEL: u8 = 2;
+
+/// A binary bundle the app can download and unpack for the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManagedTool {
+    Scrcpy,
+    PlatformTools,
+}
+
+impl ManagedTool {
+    pub const ALL: [ManagedTool; 2] = [ManagedTool::Scrcpy, ManagedTool::PlatformTools];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            ManagedTool::Scrcpy => "scrcpy",
+            ManagedTool::PlatformTools => "platform-tools",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ManagedTool::Scrcpy => "scrcpy",
+            ManagedTool::PlatformTools => "Android platform-tools (adb)",
+        }
+    }
+
+    /// Official download for the current OS and architecture; `None` where
+    /// no release is published, e.g. for Linux on ARM.
+    pub fn default_url(self) -> Option<&'static str> {
+        use std::env::consts::{ARCH, OS};
+        let url = match (self, OS, ARCH) {
+            (ManagedTool::Scrcpy, "windows", "x86_64") => "https://github.com/Genymobile/scrcpy/releases/download/v3.3.1/scrcpy-win64-v3.3.1.zip",
+            (ManagedTool::Scrcpy, "windows", "x86") => "https://github.com/Genymobile/scrcpy/releases/download/v3.3.1/scrcpy-win32-v3.3.1.zip",
+            (ManagedTool::Scrcpy, "macos", "aarch64") => "https://github.com/Genymobile/scrcpy/releases/download/v3.3.1/scrcpy-macos-aarch64-v3.3.1.tar.gz",
+            (ManagedTool::Scrcpy, "macos", "x86_64") => "https://github.com/Genymobile/scrcpy/releases/download/v3.3.1/scrcpy-macos-x86_64-v3.3.1.tar.gz",
+            (ManagedTool::Scrcpy, "linux", "x86_64") => "https://github.com/Genymobile/scrcpy/releases/download/v3.3.1/scrcpy-linux-x86_64-v3.3.1.tar.gz",
+            (ManagedTool::PlatformTools, "windows", "x86_64" | "x86") => "https://dl.google.com/android/repository/platform-tools-latest-windows.zip",
+            // The macOS build is universal
+            (ManagedTool::PlatformTools, "macos", _) => "https://dl.google.com/android/repository/platform-tools-latest-darwin.zip",
+            (ManagedTool::PlatformTools, "linux", "x86_64") => "https://dl.google.com/android/repository/platform-tools-latest-linux.zip",
+            _ => return None,
+        };
+        Some(url)
+    }
+
+    pub fn install_dir(self) -> PathBuf {
+        persist::data(TOOLS_DIR).join(self.key())
+    }
+
+    /// Path of the main executable inside the unpacked bundle, if installed.
+    pub fn installed_binary(self) -> Option<PathBuf> {
+        let name = match self {
+            ManagedTool::Scrcpy => "scrcpy",
+            ManagedTool::PlatformTools => "adb",
+        };
+        self.installed_file(&format!("{}{}", name, std::env::consts::EXE_SUFFIX))
+    }
+
+    /// Any file of the unpacked bundle, e.g. `scrcpy-server`.
+    pub fn installed_file(self, name: &str) -> Option<PathBuf> {
+        find_file(&self.install_dir(), name, 3)
+    }
+}
+
+fn find_file(dir: &Path, name: &str, depth: usize) -> Option<PathBuf> {
+    let entries: Vec<_> = fs::read_dir(dir).ok()?.filter_map(Result::ok).collect();
+    if let Some(e) = entries.iter().find(|e| e.file_name() == name && e.path().is_file()) {
+        return Some(e.path());
+    }
+    if depth == 0 {
+        return None;
+    }
+    entries
+        .iter()
+        .filter(|e| e.path().is_dir())
+        .find_map(|e| find_file(&e.path(), name, depth - 1))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadState {
+    Running,
+    Paused,
+    Cancelled,
+    Extracting,
+    Done(PathBuf),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadProgress {
+    pub state: DownloadState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub url: String,
+}
+
+impl DownloadProgress {
+    pub fn fraction(&self) -> Option<f32> {
+        self.total.filter(|t| *t > 0).map(|t| self.downloaded as f32 / t as f32)
+    }
+}
+
+/// Resumable download of a managed tool into `tools/`, trying mirror URLs in
+/// order. Partial data is kept in `<tool>.part` so pausing, a crash or a
+/// dropped connection continues from where it stopped.
+pub struct ToolDownload {
+    pub tool: ManagedTool,
+    urls: Vec<String>,
+    progress: Arc<Mutex<DownloadProgress>>,
+    control: Arc<AtomicU8>,
+}
+
+impl ToolDownload {
+    pub fn start(tool: ManagedTool, urls: Vec<String>) -> Self {
+        let download = Self {
+            tool,
+            progress: Arc::new(Mutex::new(DownloadProgress {
+                state: DownloadState::Running,
+                downloaded: 0,
+                total: None,
+                url: urls.first().cloned().unwrap_or_default(),
+            })),
+            urls,
+            control: Arc::new(AtomicU8::new(RUN)),
+        };
+        download.spawn();
+        download
+    }
+
+    fn spawn(&self) {
+        self.control.store(RUN, Ordering::Relaxed);
+        self.progress.lock().unwrap().state = DownloadState::Running;
+        let (tool, urls, progress, control) = (self.tool, self.urls.clone(), self.progress.clone(), self.control.clone());
+        thread::spawn(move || {
+            let state = match run_download(tool, &urls, &progress, &control) {
+                Ok(Some(dir)) => DownloadState::Done(dir),
+                Ok(None) if control.load(Ordering::Relaxed) == CANCEL => {
+                    let _ = fs::remove_file(part_path(tool));
+                    DownloadState::Cancelled
+                }
+                Ok(None) => DownloadState::Paused,
+                Err(e) => DownloadState::Failed(e),
+            };
+            progress.lock().unwrap().state = state;
+        });
+    }
+
+    pub fn progress(&self) -> DownloadProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    pub fn pause(&self) {
+        self.control.store(PAUSE, Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        if self.progress().state == DownloadState::Running {
+            self.control.store(CANCEL, Ordering::Relaxed);
+        } else {
+            let _ = fs::remove_file(part_path(self.tool));
+            self.progress.lock().unwrap().state = DownloadState::Cancelled;
+        }
+    }
+
+    /// Continues a paused or failed download from the partial file.
+    pub fn resume(&self) {
+        if matches!(self.progress().state, DownloadState::Paused | DownloadState::Failed(_)) {
+            self.spawn();
+        }
+    }
+}
+
+fn part_path(tool: ManagedTool) -> PathBuf {
+    persist::data(TOOLS_DIR).join(format!("{}.part", tool.key()))
+}
+
+/// Returns `Ok(None)` when interrupted by pause/cancel.
+fn run_download(tool: ManagedTool, urls: &[String], progress: &Mutex<DownloadProgress>, control: &AtomicU8) -> Result<Option<PathBuf>, String> {
+    fs::create_dir_all(persist::data(TOOLS_DIR)).map_err(|e| e.to_string())?;
+    let part = part_path(tool);
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(None)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut last_error = "no download URL configured".to_string();
+    let mut finished_url = None;
+    'urls: for url in urls {
+        progress.lock().unwrap().url = url.clone();
+        for _ in 0..ATTEMPTS_PER_URL {
+            match fetch_range(&client, url, &part, progress, control) {
+                Ok(true) => {
+                    finished_url = Some(url.clone());
+                    break 'urls;
+                }
+                Ok(false) => return Ok(None),
+                Err(e) => {
+                    last_error = format!("{}: {}", url, e);
+                    thread::sleep(Duration::from_secs(2));
+                }
+            }
+        }
+    }
+    let Some(url) = finished_url else {
+        return Err(last_error);
+    };
+    progress.lock().unwrap().state = DownloadState::Extracting;
+    let dir = tool.install_dir();
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    extract(&part, &dir, &url).map_err(|e| format!("extracting failed: {}", e))?;
+    let _ = fs::remove_file(&part);
+    Ok(Some(dir))
+}
+
+/// Downloads the remainder of `url` into `part`. `Ok(true)` when complete,
+/// `Ok(false)` when paused or cancelled.
+fn fetch_range(client: &reqwest::blocking::Client, url: &str, part: &Path, progress: &Mutex<DownloadProgress>, control: &AtomicU8) -> Result<bool, String> {
+    let have = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let mut request = client.get(url);
+    if have > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", have));
+    }
+    let mut response = request.send().map_err(|e| e.to_string())?;
+    let status = response.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && have > 0 {
+        // The partial file already holds the whole archive
+        return Ok(true);
+    }
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status));
+    }
+    // A server ignoring the Range header sends the full file again
+    let resumed = status == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .map_err(|e| e.to_string())?;
+    let mut downloaded = if resumed { have } else { 0 };
+    {
+        let mut p = progress.lock().unwrap();
+        p.downloaded = downloaded;
+        p.total = response.content_length().map(|len| len + downloaded);
+    }
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        if control.load(Ordering::Relaxed) != RUN {
+            return Ok(false);
+        }
+        let n = response.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+        downloaded += n as u64;
+        progress.lock().unwrap().downloaded = downloaded;
+    }
+    file.sync_all().map_err(|e| e.to_string())?;
+    let total = progress.lock().unwrap().total;
+    if total.is_some_and(|t| downloaded < t) {
+        return Err("connection closed early".to_string());
+    }
+    Ok(true)
+}
+
+fn extract(archive: &Path, dest: &Path, url: &str) -> io::Result<()> {
+    fs::create_dir_all(dest)?;
+    let file = fs::File::open(archive)?;
+    if url.ends_with(".zip") {
+        zip::ZipArchive::new(file).map_err(io::Error::other)?.extract(dest).map_err(io::Error::other)
+    } else {
+        tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(dest)
+    }
+}
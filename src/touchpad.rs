@@ -0,0 +1,134 @@
+use crate::ScrcpyGuiApp;
+use crate::adb::shell as adb_shell;
+use crate::{embedded, input_echo};
+use crate::job::Job;
+use eframe::egui;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Pointer movement below this many points counts as a tap.
+const TAP_SLOP: f32 = 6.0;
+const PAD_WIDTH: f32 = 220.0;
+
+/// Remote control through `adb shell input`, for devices scrcpy cannot mirror.
+/// Gestures run one at a time so they reach the device in order.
+#[derive(Default)]
+pub struct TouchPad {
+    /// Where and when the current drag started, relative to the pad.
+    drag: Option<(egui::Pos2, Instant)>,
+    queue: VecDeque<(String, String)>,
+    job: Option<Job<Result<String, String>>>,
+    pub error: Option<String>,
+}
+
+impl TouchPad {
+    fn send(&mut self, serial: &str, command: String) {
+        self.queue.push_back((serial.to_string(), command));
+        self.poll();
+    }
+
+    pub fn poll(&mut self) {
+        if let Some(result) = self.job.as_ref().and_then(Job::poll) {
+            self.job = None;
+            self.error = result.err();
+        }
+        if self.job.is_none()
+            && let Some((serial, command)) = self.queue.pop_front()
+        {
+            self.job = Some(Job::spawn(move || adb_shell(&serial, &command)));
+        }
+    }
+
+    fn busy(&self) -> bool {
+        self.job.is_some() || !self.queue.is_empty()
+    }
+}
+
+impl ScrcpyGuiApp {
+    pub(crate) fn show_touch_pad(&mut self, ui: &mut egui::Ui) {
+        let Some(serial) = self.selected_serial() else { return };
+        ui.collapsing("👆 Touch pad", |ui| {
+            ui.label("Taps and swipes here are sent with adb shell input, without scrcpy.");
+            let Some((width, height)) = self.device_props.get(&serial).and_then(|i| i.screen_size) else {
+                ui.label("Waiting for the device screen size…");
+                return;
+            };
+            let size = egui::vec2(PAD_WIDTH, PAD_WIDTH * height as f32 / width as f32);
+            let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+            let painter = ui.painter_at(rect);
+            // The last preview screenshot, if any, helps aiming
+            match &self.preview_texture {
+                Some((_, texture)) => {
+                    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                    painter.image(texture.id(), rect, uv, egui::Color32::from_white_alpha(160));
+                }
+                None => {
+                    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+                }
+            }
+            painter.rect_stroke(rect, 4.0, ui.visuals().widgets.inactive.bg_stroke, egui::StrokeKind::Inside);
+            if self.settings.echo_input {
+                self.input_echo.paint_touches(&painter, rect, &serial);
+            }
+            let relative = |pos: egui::Pos2| {
+                let x = ((pos.x - rect.min.x) / rect.width()).clamp(0.0, 1.0);
+                let y = ((pos.y - rect.min.y) / rect.height()).clamp(0.0, 1.0);
+                (x, y)
+            };
+            let to_device = |pos: egui::Pos2| {
+                let x = ((pos.x - rect.min.x) / rect.width()).clamp(0.0, 1.0) * width as f32;
+                let y = ((pos.y - rect.min.y) / rect.height()).clamp(0.0, 1.0) * height as f32;
+                (x.round() as u32, y.round() as u32)
+            };
+            let pad = &mut self.touch_pad;
+            let echo = &mut self.input_echo;
+            if response.drag_started()
+                && let Some(pos) = response.interact_pointer_pos()
+            {
+                pad.drag = Some((pos, Instant::now()));
+            }
+            if let (Some((start, _)), Some(pos)) = (pad.drag, response.interact_pointer_pos()) {
+                painter.line_segment([start, pos], ui.visuals().selection.stroke);
+            }
+            if response.drag_stopped()
+                && let (Some((start, at)), Some(end)) = (pad.drag.take(), response.interact_pointer_pos())
+            {
+                let (x1, y1) = to_device(start);
+                let (x2, y2) = to_device(end);
+                if start.distance(end) < TAP_SLOP {
+                    pad.send(&serial, format!("input tap {} {}", x1, y1));
+                    echo.push(&serial, "👆 Tap", Some(relative(start)));
+                } else {
+                    let millis = at.elapsed().as_millis().clamp(50, 3000);
+                    pad.send(&serial, format!("input swipe {} {} {} {} {}", x1, y1, x2, y2, millis));
+                    echo.push(&serial, "👆 Swipe", Some(relative(end)));
+                }
+            } else if response.clicked()
+                && let Some(pos) = response.interact_pointer_pos()
+            {
+                let (x, y) = to_device(pos);
+                pad.send(&serial, format!("input tap {} {}", x, y));
+                echo.push(&serial, "👆 Tap", Some(relative(pos)));
+            }
+            ui.horizontal(|ui| {
+                for (label, hint, keycode) in [
+                    ("◀", "Back", embedded::KEYCODE_BACK),
+                    ("●", "Home", embedded::KEYCODE_HOME),
+                    ("▣", "Recent apps", embedded::KEYCODE_APP_SWITCH),
+                    ("⏻", "Power", embedded::KEYCODE_POWER),
+                ] {
+                    if ui.button(label).on_hover_text(hint).clicked() {
+                        pad.send(&serial, format!("input keyevent {}", keycode));
+                        echo.push(&serial, format!("⌨ {}", input_echo::key_name(keycode)), None);
+                    }
+                }
+                if pad.busy() {
+                    ui.spinner();
+                }
+            });
+            if let Some(e) = &pad.error {
+                ui.colored_label(egui::Color32::RED, format!("Input failed: {}", e));
+            }
+        });
+    }
+}
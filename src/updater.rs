@@ -0,0 +1,197 @@
+use crate::changelog::{self, APP_VERSION};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/joranmarcy/scrcpy-rust-gui/releases/latest";
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateInfo {
+    pub version: String,
+    asset_url: String,
+    checksum_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateStatus {
+    Idle,
+    Checking,
+    UpToDate,
+    Available(UpdateInfo),
+    Downloading(String),
+    /// The new binary is verified and will replace this one on next start.
+    Staged(String),
+    Failed(String),
+}
+
+fn client() -> reqwest::Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .user_agent(concat!("scrcpy-rust-gui/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// Asks GitHub for the latest release and picks the asset for this platform,
+/// which must come with a `<asset>.sha256` checksum file.
+fn check_latest() -> Result<Option<UpdateInfo>, String> {
+    let release: GithubRelease = client()
+        .and_then(|c| c.get(RELEASES_URL).send())
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| e.to_string())?;
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    if changelog::parse_version(&version) <= changelog::parse_version(APP_VERSION) {
+        return Ok(None);
+    }
+    let os = std::env::consts::OS;
+    let arch = std::env::consts::ARCH;
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name.contains(os) && a.name.contains(arch) && !a.name.ends_with(".sha256"))
+        .ok_or_else(|| format!("Release v{} has no build for {}-{}", version, os, arch))?;
+    let checksum_name = format!("{}.sha256", asset.name);
+    let checksum = release
+        .assets
+        .iter()
+        .find(|a| a.name == checksum_name)
+        .ok_or_else(|| format!("Release v{} has no checksum for {}", version, asset.name))?;
+    Ok(Some(UpdateInfo {
+        version,
+        asset_url: asset.browser_download_url.clone(),
+        checksum_url: checksum.browser_download_url.clone(),
+    }))
+}
+
+fn staged_path() -> std::io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    Ok(exe.with_extension("new"))
+}
+
+/// Downloads the new binary, verifies its SHA-256 and writes it next to the
+/// running executable.
+fn download_and_stage(info: &UpdateInfo) -> Result<(), String> {
+    let client = client().map_err(|e| e.to_string())?;
+    let checksum_text = client
+        .get(&info.checksum_url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.text())
+        .map_err(|e| e.to_string())?;
+    let expected = checksum_text
+        .split_whitespace()
+        .next()
+        .ok_or("Empty checksum file")?
+        .to_lowercase();
+    let bytes = client
+        .get(&info.asset_url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.bytes())
+        .map_err(|e| e.to_string())?;
+    let actual: String = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != expected {
+        return Err("Checksum mismatch, update discarded".to_string());
+    }
+    let path = staged_path().map_err(|e| e.to_string())?;
+    crate::persist::write_atomic(&path, &bytes).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Replaces the running executable with a staged update and relaunches it.
+/// Returns `true` if the caller should exit because the new binary was started.
+pub fn apply_staged_update() -> bool {
+    let (Ok(exe), Ok(staged)) = (std::env::current_exe(), staged_path()) else {
+        return false;
+    };
+    let old = exe.with_extension("old");
+    let _ = std::fs::remove_file(&old);
+    if !staged.exists() {
+        return false;
+    }
+    // A running binary can be renamed (even on Windows) but not overwritten
+    if std::fs::rename(&exe, &old).is_err() {
+        return false;
+    }
+    if let Err(e) = std::fs::rename(&staged, &exe) {
+        eprintln!("Failed to install update: {}", e);
+        let _ = std::fs::rename(&old, &exe);
+        return false;
+    }
+    std::process::Command::new(&exe)
+        .args(std::env::args_os().skip(1))
+        .spawn()
+        .is_ok()
+}
+
+/// Runs update checks and downloads off the UI thread.
+pub struct Updater {
+    pub status: UpdateStatus,
+    rx: Option<Receiver<UpdateStatus>>,
+}
+
+impl Default for Updater {
+    fn default() -> Self {
+        Self {
+            status: UpdateStatus::Idle,
+            rx: None,
+        }
+    }
+}
+
+impl Updater {
+    pub fn is_busy(&self) -> bool {
+        matches!(self.status, UpdateStatus::Checking | UpdateStatus::Downloading(_))
+    }
+
+    pub fn check(&mut self) {
+        self.run(UpdateStatus::Checking, || match check_latest() {
+            Ok(Some(info)) => UpdateStatus::Available(info),
+            Ok(None) => UpdateStatus::UpToDate,
+            Err(e) => UpdateStatus::Failed(e),
+        });
+    }
+
+    pub fn download(&mut self, info: UpdateInfo) {
+        self.run(UpdateStatus::Downloading(info.version.clone()), move || match download_and_stage(&info) {
+            Ok(()) => UpdateStatus::Staged(info.version),
+            Err(e) => UpdateStatus::Failed(e),
+        });
+    }
+
+    fn run(&mut self, status: UpdateStatus, job: impl FnOnce() -> UpdateStatus + Send + 'static) {
+        let (tx, rx) = mpsc::channel();
+        self.status = status;
+        self.rx = Some(rx);
+        thread::spawn(move || {
+            let _ = tx.send(job());
+        });
+    }
+
+    pub fn poll(&mut self) {
+        if let Some(rx) = &self.rx
+            && let Ok(status) = rx.try_recv()
+        {
+            self.status = status;
+            self.rx = None;
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// CPU and memory use of the scrcpy processes, sampled on the health check
+/// tick. CPU needs two samples, so it reads 0 right after a launch.
+pub struct ProcessUsage {
+    system: System,
+    /// `(cpu % of one core, resident bytes)` per pid.
+    samples: HashMap<u32, (f32, u64)>,
+}
+
+impl Default for ProcessUsage {
+    fn default() -> Self {
+        Self { system: System::new(), samples: HashMap::new() }
+    }
+}
+
+impl ProcessUsage {
+    pub fn refresh(&mut self, pids: &[u32]) {
+        let pids: Vec<Pid> = pids.iter().map(|&pid| Pid::from_u32(pid)).collect();
+        self.system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), true, ProcessRefreshKind::nothing().with_cpu().with_memory());
+        self.samples = pids
+            .iter()
+            .filter_map(|pid| self.system.process(*pid).map(|p| (pid.as_u32(), (p.cpu_usage(), p.memory()))))
+            .collect();
+    }
+
+    pub fn get(&self, pid: u32) -> Option<(f32, u64)> {
+        self.samples.get(&pid).copied()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::ScrcpyGuiApp;
+use std::time::{Duration, Instant};
+
+/// A relaunched session that runs this long counts as recovered, and the
+/// retry counter starts over.
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+/// Keep-alive state of a session that is relaunched whenever scrcpy exits
+/// without the user stopping it.
+#[derive(Debug, Default)]
+pub struct KeepAlive {
+    /// Relaunches since the session last ran for `STABLE_RUN`.
+    pub retries: u32,
+    /// Set once `retries` reached the limit from settings.
+    pub gave_up: bool,
+    retry_at: Option<Instant>,
+}
+
+impl ScrcpyGuiApp {
+    /// Relaunches kept-alive sessions that stopped, after the delay from
+    /// settings and once their device is listed again.
+    pub(crate) fn run_keep_alive(&mut self) {
+        let delay = Duration::from_secs(self.settings.keep_alive_delay_secs.into());
+        let max_retries = self.settings.keep_alive_max_retries;
+        let mut restart = Vec::new();
+        for (serial, keep) in &mut self.keep_alive {
+            let Some(session) = self.sessions.get(serial) else { continue };
+            if session.is_running() {
+                if session.duration() >= STABLE_RUN {
+                    keep.retries = 0;
+                }
+                keep.retry_at = None;
+                continue;
+            }
+            let listed = session.is_otg() || self.devices.contains(serial);
+            if keep.gave_up || !listed || self.automation_failures.is_paused(serial) {
+                continue;
+            }
+            if keep.retries >= max_retries {
+                keep.gave_up = true;
+                self.notices.device(serial, format!("⚠️ Keep-alive gave up after {} relaunches.", keep.retries));
+                continue;
+            }
+            match keep.retry_at {
+                None => keep.retry_at = Some(Instant::now() + delay),
+                Some(at) if Instant::now() >= at => {
+                    keep.retries += 1;
+                    keep.retry_at = None;
+                    restart.push(serial.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        for serial in restart {
+            self.restart_session(&serial);
+        }
+    }
+
+    /// "Relaunching in 3s (2/5)" for a stopped kept-alive session.
+    pub(crate) fn keep_alive_status(&self, serial: &str) -> Option<String> {
+        let keep = self.keep_alive.get(serial)?;
+        let max = self.settings.keep_alive_max_retries;
+        if keep.gave_up {
+            return Some(format!("♻ gave up ({}/{})", keep.retries, max));
+        }
+        let secs = keep.retry_at?.saturating_duration_since(Instant::now()).as_secs();
+        Some(format!("♻ relaunching in {}s ({}/{})", secs, keep.retries + 1, max))
+    }
+}
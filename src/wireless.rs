@@ -0,0 +1,288 @@
+use crate::adb;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Port `adb tcpip` switches the device to.
+pub const TCPIP_PORT: u16 = 5555;
+
+fn adb(args: &[&str]) -> Result<String, String> {
+    let output = adb::run(args, adb::SHORT, None)?;
+    let text = String::from_utf8_lossy(&output.stdout).to_string() + &String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        Ok(text.trim().to_string())
+    } else {
+        Err(text.trim().to_string())
+    }
+}
+
+/// Appends the default port when `address` has none.
+pub fn with_port(address: &str) -> String {
+    let address = address.trim();
+    if address.contains(':') { address.to_string() } else { format!("{}:{}", address, TCPIP_PORT) }
+}
+
+/// `adb connect` reports most failures on stdout with a zero exit code.
+pub fn connect(address: &str) -> Result<String, String> {
+    let address = with_port(address);
+    let out = adb(&["connect", &address])?;
+    if out.contains("connected to") {
+        Ok(format!("Connected to {}.", address))
+    } else {
+        Err(out)
+    }
+}
+
+pub fn disconnect(serial: &str) -> Result<String, String> {
+    adb(&["disconnect", serial]).map(|_| format!("Disconnected {}.", serial))
+}
+
+/// Wi-Fi address of a USB device, from the `wlan0` interface.
+fn device_ip(serial: &str) -> Result<String, String> {
+    let out = adb(&["-s", serial, "shell", "ip", "-f", "inet", "addr", "show", "wlan0"])?;
+    out.split_whitespace()
+        .skip_while(|word| *word != "inet")
+        .nth(1)
+        .and_then(|cidr| cidr.split('/').next())
+        .map(str::to_string)
+        .ok_or_else(|| "the device has no Wi-Fi address; is it on the same network?".to_string())
+}
+
+/// `adb tcpip 5555` on a USB device, then `adb connect` to its Wi-Fi address.
+pub fn switch_to_wifi(serial: &str) -> Result<String, String> {
+    let ip = device_ip(serial)?;
+    adb(&["-s", serial, "tcpip", &TCPIP_PORT.to_string()])?;
+    // adbd restarts in TCP mode and is briefly unreachable
+    thread::sleep(Duration::from_secs(2));
+    connect(&ip)
+}
+
+/// `adb mdns services` as `(instance, service, address)` rows.
+fn mdns_services() -> Vec<(String, String, String)> {
+    adb(&["mdns", "services"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let row = (parts.next()?.to_string(), parts.next()?.to_string(), parts.next()?.to_string());
+            row.2.contains(':').then_some(row)
+        })
+        .collect()
+}
+
+fn host(address: &str) -> &str {
+    address.rsplit_once(':').map_or(address, |(host, _)| host)
+}
+
+/// `adb pair` with the code shown on the phone.
+pub fn pair(address: &str, code: &str) -> Result<String, String> {
+    let address = address.trim();
+    let out = adb(&["pair", address, code.trim()])?;
+    if out.contains("Successfully paired") {
+        Ok(out)
+    } else {
+        Err(out)
+    }
+}
+
+/// The pairing port differs from the debugging port, so after pairing look
+/// for the phone's `_adb-tls-connect` service with the same IP and connect
+/// to it. adb often connects by itself, which is fine too.
+fn connect_after_pairing(ip: &str) -> Result<String, String> {
+    for _ in 0..10 {
+        let services = mdns_services();
+        if let Some((_, _, address)) = services.iter().find(|(_, service, address)| service.starts_with("_adb-tls-connect") && host(address) == ip) {
+            return connect(address);
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    Err(format!("paired with {}, but it did not advertise a debugging port; connect with the IP address & port shown on the phone", ip))
+}
+
+pub fn pair_and_connect(address: &str, code: &str) -> Result<String, String> {
+    pair(address, code)?;
+    connect_after_pairing(host(address.trim()))
+}
+
+/// Which side runs the hotspot when there is no shared Wi-Fi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hotspot {
+    /// The phone joins a hotspot shared by this computer.
+    Computer,
+    /// This computer joins the phone's hotspot; the phone is the gateway.
+    Phone,
+}
+
+impl Hotspot {
+    pub const ALL: [Hotspot; 2] = [Hotspot::Computer, Hotspot::Phone];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Hotspot::Computer => "Phone joins this computer's hotspot",
+            Hotspot::Phone => "Computer joins the phone's hotspot",
+        }
+    }
+
+    pub fn steps(self) -> [&'static str; 3] {
+        match self {
+            Hotspot::Computer => [
+                "Turn on the mobile hotspot of this computer (Windows: Settings → Mobile hotspot, macOS: Internet Sharing, Linux: Wi-Fi → Hotspot).",
+                "Connect the phone to that network in its Wi-Fi settings.",
+                "Plug the phone in over USB once so adb over TCP can be enabled, or turn on Wireless debugging after pairing, then press Find phone.",
+            ],
+            Hotspot::Phone => [
+                "Turn on the phone's hotspot (Settings → Network → Hotspot & tethering).",
+                "Connect this computer to the phone's network.",
+                "Plug the phone in over USB once so adb over TCP can be enabled, or turn on Wireless debugging after pairing, then press Find phone.",
+            ],
+        }
+    }
+}
+
+/// IPv4 addresses of this computer on private networks, which includes a
+/// hotspot it shares or joined.
+fn host_addresses() -> Vec<Ipv4Addr> {
+    let (program, args): (&str, &[&str]) = if cfg!(windows) {
+        ("ipconfig", &[])
+    } else if cfg!(target_os = "macos") {
+        ("ifconfig", &[])
+    } else {
+        ("ip", &["-4", "-o", "addr", "show"])
+    };
+    let out = Command::new(program).args(args).output().map(|o| String::from_utf8_lossy(&o.stdout).to_string()).unwrap_or_default();
+    let mut addresses: Vec<Ipv4Addr> = out
+        .lines()
+        .filter(|line| line.contains("inet") || line.contains("IPv4"))
+        .flat_map(|line| line.split(|c: char| !(c.is_ascii_digit() || c == '.')))
+        .filter_map(|word| word.parse::<Ipv4Addr>().ok())
+        .filter(Ipv4Addr::is_private)
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+fn same_subnet(a: Ipv4Addr, b: Ipv4Addr) -> bool {
+    a.octets()[..3] == b.octets()[..3]
+}
+
+/// Hosts of this computer's /24 networks that accept connections on the adb
+/// port; the gateway (`.1`) first, as a phone's hotspot usually is.
+fn scan_for_adb(own: &[Ipv4Addr]) -> Vec<Ipv4Addr> {
+    let candidates: Vec<Ipv4Addr> = own
+        .iter()
+        .flat_map(|ip| {
+            let [a, b, c, _] = ip.octets();
+            (1..=254).map(move |d| Ipv4Addr::new(a, b, c, d))
+        })
+        .filter(|ip| !own.contains(ip))
+        .collect();
+    let mut found: Vec<Ipv4Addr> = candidates
+        .chunks(64)
+        .flat_map(|chunk| {
+            let probes: Vec<_> = chunk
+                .iter()
+                .map(|&ip| {
+                    thread::spawn(move || {
+                        let address = SocketAddr::from((ip, TCPIP_PORT));
+                        TcpStream::connect_timeout(&address, Duration::from_millis(300)).is_ok().then_some(ip)
+                    })
+                })
+                .collect();
+            probes.into_iter().filter_map(|probe| probe.join().ok().flatten()).collect::<Vec<_>>()
+        })
+        .collect();
+    found.sort_by_key(|ip| ip.octets()[3] != 1);
+    found
+}
+
+/// Finds the phone on the hotspot network and connects to it. With `usb`,
+/// adb over TCP is enabled on that device first and its address is taken
+/// from the interface sharing a network with this computer.
+pub fn connect_over_hotspot(usb: Option<&str>) -> Result<String, String> {
+    let own = host_addresses();
+    if own.is_empty() {
+        return Err("this computer has no private network address; is the hotspot connected?".to_string());
+    }
+    if let Some(serial) = usb {
+        let out = adb(&["-s", serial, "shell", "ip", "-f", "inet", "addr", "show"])?;
+        let phone = out
+            .split_whitespace()
+            .filter_map(|word| word.split('/').next()?.parse::<Ipv4Addr>().ok())
+            .find(|ip| own.iter().any(|host| same_subnet(*host, *ip) && host != ip))
+            .ok_or_else(|| "the phone has no address on this computer's networks; is it on the hotspot?".to_string())?;
+        adb(&["-s", serial, "tcpip", &TCPIP_PORT.to_string()])?;
+        thread::sleep(Duration::from_secs(2));
+        return connect(&phone.to_string());
+    }
+    // A phone paired before advertises its wireless debugging port
+    if let Some((_, _, address)) = mdns_services()
+        .into_iter()
+        .find(|(_, service, address)| service.starts_with("_adb-tls-connect") && host(address).parse().is_ok_and(|ip| own.iter().any(|h| same_subnet(*h, ip))))
+    {
+        return connect(&address);
+    }
+    let mut last_error = format!("no device answered on port {} on {}", TCPIP_PORT, own.iter().map(|ip| format!("{}/24", ip)).collect::<Vec<_>>().join(", "));
+    for ip in scan_for_adb(&own) {
+        match connect(&ip.to_string()) {
+            Ok(msg) => return Ok(msg),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// "Pair device with QR code": the phone scans `WIFI:T:ADB;S:<name>;P:<password>;;`
+/// and then advertises a pairing service named `<name>` over mDNS.
+pub struct QrPairing {
+    pub name: String,
+    pub password: String,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl QrPairing {
+    pub fn new() -> Self {
+        Self {
+            name: format!("scrcpy-gui-{:04}", random_number() % 10_000),
+            password: format!("{:06}", random_number() % 1_000_000),
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn payload(&self) -> String {
+        format!("WIFI:T:ADB;S:{};P:{};;", self.name, self.password)
+    }
+
+    /// QR code modules as rows of `width` booleans, `true` being dark.
+    pub fn modules(&self) -> Option<(usize, Vec<bool>)> {
+        let code = qrcode::QrCode::new(self.payload()).ok()?;
+        let dark = code.to_colors().into_iter().map(|c| c == qrcode::Color::Dark).collect();
+        Some((code.width(), dark))
+    }
+
+    /// Waits for the phone to scan the code, then pairs and connects.
+    pub fn wait_and_pair(name: &str, password: &str, cancel: &AtomicBool) -> Result<String, String> {
+        for _ in 0..120 {
+            if cancel.load(Ordering::Relaxed) {
+                return Err("pairing cancelled".to_string());
+            }
+            if let Some((_, _, address)) = mdns_services().into_iter().find(|(instance, service, _)| instance == name && service.starts_with("_adb-tls-pairing")) {
+                return pair_and_connect(&address, password);
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+        Err("no phone scanned the QR code within two minutes".to_string())
+    }
+}
+
+/// Non-cryptographic randomness from the std hasher's random keys; enough for
+/// a short-lived pairing code.
+fn random_number() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+    hasher.finish()
+}
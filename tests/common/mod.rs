@@ -0,0 +1,24 @@
+//! Points the core library at the fake tools built with `--features fake-tools`.
+
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+pub const SERIAL: &str = "emulator-5554";
+pub const FAKE_SCRCPY: &str = env!("CARGO_BIN_EXE_fake-scrcpy");
+
+pub fn use_fake_adb() {
+    scrpy_rust::adb::set_program(env!("CARGO_BIN_EXE_fake-adb"));
+}
+
+/// Polls `done` until it holds or `timeout` passes.
+pub fn wait_for(timeout: Duration, mut done: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if done() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    false
+}
@@ -0,0 +1,24 @@
+use scrpy_rust::cmdline::{merge_args, option_value, quote, split};
+
+#[test]
+fn overrides_replace_options_of_the_profile() {
+    let merged = merge_args("--max-size=1024 -b 8M --crop=1080:1920:0:0 --stay-awake", "--video-bit-rate=4M --crop 540:960:0:0");
+    assert_eq!(merged, "--max-size=1024 --stay-awake --video-bit-rate=4M --crop 540:960:0:0");
+}
+
+#[test]
+fn short_and_long_forms_read_the_same() {
+    assert_eq!(option_value("-m 1080 --max-fps=60", "--max-size").as_deref(), Some("1080"));
+    assert_eq!(option_value("--max-size=720 -m 1080", "--max-size").as_deref(), Some("1080"));
+    assert_eq!(option_value("--stay-awake", "--stay-awake"), None);
+}
+
+#[test]
+fn quoted_command_lines_round_trip() {
+    let args = vec!["--window-title=Pixel 7 – desk".to_string(), "--max-size=1024".to_string()];
+    let line = args.iter().map(|a| quote(a)).collect::<Vec<_>>().join(" ");
+    if cfg!(not(windows)) {
+        assert_eq!(split(&line), Ok(args));
+    }
+    assert!(split("--window-title='unterminated").is_err());
+}
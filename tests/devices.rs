@@ -0,0 +1,35 @@
+#![cfg(feature = "fake-tools")]
+
+mod common;
+
+use common::{SERIAL, use_fake_adb, wait_for};
+use scrpy_rust::adb;
+use scrpy_rust::devices::{self, DevicePropsCache};
+use std::time::Duration;
+
+#[test]
+fn refresh_lists_only_ready_devices() {
+    use_fake_adb();
+    assert_eq!(devices::list(), vec![SERIAL.to_string()]);
+}
+
+#[test]
+fn properties_are_fetched_in_the_background() {
+    use_fake_adb();
+    let mut cache = DevicePropsCache::default();
+    cache.request_all(&[SERIAL.to_string()]);
+    assert!(cache.is_pending(SERIAL));
+    assert!(wait_for(Duration::from_secs(5), || cache.poll().contains(&SERIAL.to_string())));
+    let info = cache.get(SERIAL).expect("properties of the fake device");
+    assert_eq!(info.model, "Pixel 7");
+    assert_eq!(info.sdk, Some(34));
+    assert_eq!(info.screen_size, Some((1080, 2400)));
+}
+
+#[test]
+fn shell_reports_output_and_unknown_serials() {
+    use_fake_adb();
+    assert_eq!(adb::shell(SERIAL, "echo ok").as_deref(), Ok("ok"));
+    let error = adb::shell("R58M123ABC", "echo ok").unwrap_err();
+    assert!(error.contains("not found"), "{}", error);
+}
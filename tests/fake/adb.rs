@@ -0,0 +1,51 @@
+//! Stand-in for `adb` in the integration tests: one authorized device, one
+//! unauthorized and one offline, with canned shell output.
+
+use std::process::exit;
+
+/// The only device that answers.
+const SERIAL: &str = "emulator-5554";
+
+const GETPROP: &str = "[ro.product.model]: [Pixel 7]
+[ro.product.manufacturer]: [Google]
+[ro.build.version.release]: [14]
+[ro.build.version.sdk]: [34]";
+
+fn shell(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "getprop" => GETPROP,
+        "wm size" => "Physical size: 1080x2400",
+        "echo ok" => "ok",
+        "dumpsys battery" => "Current Battery Service state:\n  status: 2\n  level: 76\n  scale: 100",
+        _ => return None,
+    })
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args[..] {
+        ["devices"] => {
+            println!("List of devices attached");
+            println!("{}\tdevice", SERIAL);
+            println!("R58M123ABC\tunauthorized");
+            println!("192.168.1.20:5555\toffline");
+        }
+        ["-s", serial, ..] if serial != SERIAL => {
+            eprintln!("adb: device '{}' not found", serial);
+            exit(1);
+        }
+        ["-s", _, "shell", ref command @ ..] => match shell(&command.join(" ")) {
+            Some(output) => println!("{}", output),
+            None => {
+                eprintln!("/system/bin/sh: {}: not found", command.join(" "));
+                exit(127);
+            }
+        },
+        ["-s", _, "reboot", ..] => {}
+        _ => {
+            eprintln!("adb: unknown command {}", args.join(" "));
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Stand-in for `scrcpy` in the integration tests. It prints a startup log
+//! like scrcpy 3.x and then mirrors until killed, or until `--time-limit`
+//! seconds have passed.
+
+use std::io::Write;
+use std::process::exit;
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value = |name: &str| args.iter().find_map(|a| a.strip_prefix(name)?.strip_prefix('=').map(str::to_string));
+    println!("scrcpy 3.1 <https://github.com/Genymobile/scrcpy>");
+    if args.iter().any(|a| a == "--version") {
+        return;
+    }
+    if args.iter().any(|a| a == "--list-encoders") {
+        println!("[server] INFO: List of video encoders:");
+        println!("    --video-codec=h264 --video-encoder='c2.android.avc.encoder'");
+        println!("    --video-codec=h265 --video-encoder='c2.android.hevc.encoder'");
+        return;
+    }
+    if value("--serial").is_some_and(|s| s != "emulator-5554") {
+        eprintln!("ERROR: Could not find any ADB device");
+        exit(1);
+    }
+    println!("INFO: ADB device found:");
+    println!("INFO:     -->   (usb)  emulator-5554  device  Pixel_7");
+    println!("[server] INFO: Device: [Google] Pixel 7 (Android 14)");
+    println!("INFO: Renderer: opengl");
+    println!("INFO: Texture: 1080x2400");
+    let _ = std::io::stdout().flush();
+    match value("--time-limit").and_then(|v| v.parse::<u64>().ok()) {
+        Some(secs) => thread::sleep(Duration::from_secs(secs)),
+        None => loop {
+            thread::sleep(Duration::from_secs(1));
+        },
+    }
+}
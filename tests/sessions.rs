@@ -0,0 +1,65 @@
+#![cfg(feature = "fake-tools")]
+
+mod common;
+
+use common::{FAKE_SCRCPY, SERIAL, wait_for};
+use scrpy_rust::session::{Session, SessionManager, SessionState};
+use std::time::Duration;
+
+fn connected() -> Vec<String> {
+    vec![SERIAL.to_string()]
+}
+
+fn spawn(args: &[&str]) -> Session {
+    let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    args.insert(0, format!("--serial={}", SERIAL));
+    Session::spawn(FAKE_SCRCPY, SERIAL, args).expect("fake scrcpy starts")
+}
+
+#[test]
+fn exit_is_reaped_with_its_code_and_output() {
+    let mut session = spawn(&["--time-limit=1"]);
+    assert!(session.is_running());
+    assert!(wait_for(Duration::from_secs(5), || session.reap(&connected())));
+    assert_eq!(session.state, SessionState::Exited(Some(0)));
+    assert!(session.log_lines().iter().any(|l| l.contains("Device: [Google] Pixel 7")));
+}
+
+#[test]
+fn stop_ends_a_running_session() {
+    let mut session = spawn(&[]);
+    assert!(wait_for(Duration::from_secs(5), || !session.log_lines().is_empty()));
+    session.stop();
+    assert!(session.pid().is_none());
+    assert!(session.record().is_none());
+    session.wait_stopped();
+    assert!(session.stop_finished());
+}
+
+#[test]
+fn unplugged_device_marks_the_session_disconnected() {
+    let mut session = spawn(&[]);
+    assert!(session.check_health(&[]));
+    assert_eq!(session.state, SessionState::Disconnected);
+}
+
+#[test]
+fn failed_launch_reports_the_error() {
+    let mut session = Session::spawn(FAKE_SCRCPY, "R58M123ABC", vec!["--serial=R58M123ABC".to_string()]).expect("fake scrcpy starts");
+    assert!(wait_for(Duration::from_secs(5), || session.reap(&["R58M123ABC".to_string()])));
+    assert_eq!(session.state, SessionState::Exited(Some(1)));
+    assert!(session.last_error().is_some_and(|e| e.contains("Could not find any ADB device")));
+}
+
+#[test]
+fn manager_keeps_one_session_per_device() {
+    let mut sessions = SessionManager::default();
+    assert!(sessions.insert(spawn(&[])).is_none());
+    let mut replaced = sessions.insert(spawn(&[])).expect("the first session is handed back");
+    replaced.stop();
+    assert!(sessions.is_running(SERIAL));
+    assert_eq!(sessions.serials(), vec![SERIAL.to_string()]);
+    let mut session = sessions.remove(SERIAL).expect("session of the device");
+    session.stop();
+    assert!(!sessions.any_running());
+}